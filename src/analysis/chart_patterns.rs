@@ -0,0 +1,354 @@
+use crate::analysis::{Direction, Pattern, PatternKind, PatternPoint, PriceZone, Swing};
+use crate::domain::Bar;
+
+/// Classical chart pattern recognition
+///
+/// Matches consecutive swings against double tops and bottoms,
+/// head-and-shoulders, ascending, descending and symmetrical triangles, and
+/// bull and bear flags. Price levels are treated as equal when they are
+/// within the relative tolerance of each other.
+///
+/// The completion zone spans the breakout level, e.g. the neckline, and the
+/// measured move target projected from it. A pattern is complete once a bar
+/// after its last swing closes beyond the breakout level.
+pub struct ChartPatterns {
+    tolerance: f32,
+}
+
+impl Default for ChartPatterns {
+    fn default() -> Self {
+        ChartPatterns { tolerance: 0.02 }
+    }
+}
+
+impl ChartPatterns {
+    pub fn new(tolerance: f32) -> Self {
+        ChartPatterns { tolerance }
+    }
+
+    pub fn scan(&self, data: &[Bar], swings: &[Swing]) -> Vec<Pattern> {
+        let mut patterns: Vec<Pattern> = swings
+            .windows(3)
+            .filter_map(|points| self.double(data, points))
+            .collect();
+        for points in swings.windows(5) {
+            patterns.extend(self.head_and_shoulders(data, points));
+            patterns.extend(self.triangle(data, points));
+            patterns.extend(self.flag(data, points));
+        }
+        patterns
+    }
+
+    /// Two swings at an equal level separated by a swing at the neckline.
+    fn double(&self, data: &[Bar], points: &[Swing]) -> Option<Pattern> {
+        let direction = Direction::from_swing(&points[0]);
+        let (first, neckline, second) = (points[0].price, points[1].price, points[2].price);
+        let confidence = self.level_score(first, second);
+        if confidence == 0.0 {
+            return None;
+        }
+
+        let (kind, labels) = match direction {
+            Direction::Down => (PatternKind::DoubleTop, ["Top 1", "Neckline", "Top 2"]),
+            Direction::Up => (
+                PatternKind::DoubleBottom,
+                ["Bottom 1", "Neckline", "Bottom 2"],
+            ),
+        };
+        let extreme =
+            -direction.sign() * (-direction.sign() * first).max(-direction.sign() * second);
+        let target = neckline - (extreme - neckline);
+
+        Some(Pattern {
+            kind,
+            direction,
+            points: labelled(points, &labels),
+            completion: PriceZone::new(neckline, target),
+            confidence,
+            complete: breaks_out(data, points, direction, |_| neckline),
+        })
+    }
+
+    /// A head beyond two shoulders at an equal level, with a neckline through
+    /// the swings between them.
+    fn head_and_shoulders(&self, data: &[Bar], points: &[Swing]) -> Option<Pattern> {
+        let direction = Direction::from_swing(&points[0]);
+        let peak = -direction.sign();
+        let price = |i: usize| points[i].price;
+        if peak * price(2) <= peak * price(0) || peak * price(2) <= peak * price(4) {
+            return None;
+        }
+        let confidence = self.level_score(price(0), price(4));
+        if confidence == 0.0 {
+            return None;
+        }
+
+        let (start, end) = (points[1].index as f32, points[3].index as f32);
+        let neckline = move |index: usize| {
+            price(1) + (price(3) - price(1)) * (index as f32 - start) / (end - start)
+        };
+        let height = price(2) - neckline(points[2].index);
+        let breakout = neckline(points[4].index);
+        let kind = match direction {
+            Direction::Down => PatternKind::HeadAndShoulders,
+            Direction::Up => PatternKind::InverseHeadAndShoulders,
+        };
+
+        Some(Pattern {
+            kind,
+            direction,
+            points: labelled(
+                points,
+                &[
+                    "Left Shoulder",
+                    "Neckline",
+                    "Head",
+                    "Neckline",
+                    "Right Shoulder",
+                ],
+            ),
+            completion: PriceZone::new(breakout, breakout - height),
+            confidence,
+            complete: breaks_out(data, points, direction, neckline),
+        })
+    }
+
+    /// Highs and lows converging: flat highs and rising lows for an ascending
+    /// triangle, falling highs and flat lows for a descending triangle, and
+    /// falling highs and rising lows for a symmetrical triangle, which
+    /// continues the trend entering it.
+    fn triangle(&self, data: &[Bar], points: &[Swing]) -> Option<Pattern> {
+        let (highs, lows): (Vec<&Swing>, Vec<&Swing>) = points
+            .iter()
+            .partition(|swing| Direction::from_swing(swing) == Direction::Down);
+        let highs: Vec<f32> = highs.iter().map(|swing| swing.price).collect();
+        let lows: Vec<f32> = lows.iter().map(|swing| swing.price).collect();
+
+        let flatness = |prices: &[f32]| {
+            let scores: Vec<f32> = prices
+                .windows(2)
+                .map(|pair| self.level_score(pair[0], pair[1]))
+                .collect();
+            if scores.contains(&0.0) {
+                0.0
+            } else {
+                scores.iter().sum::<f32>() / scores.len() as f32
+            }
+        };
+        let falling = |prices: &[f32]| prices.windows(2).all(|pair| pair[1] < pair[0]);
+        let rising = |prices: &[f32]| prices.windows(2).all(|pair| pair[1] > pair[0]);
+        let (flat_highs, flat_lows) = (flatness(&highs), flatness(&lows));
+
+        let first_range = highs[0] - lows[0];
+        let last_range = highs[highs.len() - 1] - lows[lows.len() - 1];
+        let (kind, direction, confidence) = if flat_highs > 0.0 && rising(&lows) {
+            (PatternKind::AscendingTriangle, Direction::Up, flat_highs)
+        } else if flat_lows > 0.0 && falling(&highs) {
+            (PatternKind::DescendingTriangle, Direction::Down, flat_lows)
+        } else if falling(&highs) && rising(&lows) {
+            // The trend entering the triangle rose to its first swing high
+            let direction = match Direction::from_swing(&points[0]) {
+                Direction::Down => Direction::Up,
+                Direction::Up => Direction::Down,
+            };
+            let convergence = (1.0 - last_range / first_range).clamp(0.0, 1.0);
+            (PatternKind::SymmetricalTriangle, direction, convergence)
+        } else {
+            return None;
+        };
+
+        let breakout = match direction {
+            Direction::Up => highs[highs.len() - 1],
+            Direction::Down => lows[lows.len() - 1],
+        };
+        let height = highs.iter().cloned().fold(f32::MIN, f32::max)
+            - lows.iter().cloned().fold(f32::MAX, f32::min);
+
+        Some(Pattern {
+            kind,
+            direction,
+            points: labelled(points, &["1", "2", "3", "4", "5"]),
+            completion: PriceZone::new(breakout, breakout + direction.sign() * height),
+            confidence,
+            complete: breaks_out(data, points, direction, |_| breakout),
+        })
+    }
+
+    /// A sharp pole followed by a channel of lower highs and lower lows, or
+    /// higher highs and higher lows after a falling pole, that retraces no
+    /// more than half of the pole.
+    fn flag(&self, data: &[Bar], points: &[Swing]) -> Option<Pattern> {
+        let direction = Direction::from_swing(&points[0]);
+        let q = |i: usize| direction.sign() * points[i].price;
+        let pole = q(1) - q(0);
+        let retracement = q(1) - q(4);
+        if q(3) >= q(1) || q(4) >= q(2) || retracement > 0.5 * pole {
+            return None;
+        }
+
+        let breakout = points[3].price;
+        let kind = match direction {
+            Direction::Up => PatternKind::BullFlag,
+            Direction::Down => PatternKind::BearFlag,
+        };
+
+        Some(Pattern {
+            kind,
+            direction,
+            points: labelled(points, &["Pole", "1", "2", "3", "4"]),
+            completion: PriceZone::new(breakout, breakout + direction.sign() * pole),
+            confidence: 1.0 - retracement / pole,
+            complete: breaks_out(data, points, direction, |_| breakout),
+        })
+    }
+
+    /// Scores how equal two price levels are, from 1 when equal to 0 when
+    /// they are apart by the tolerance or more.
+    fn level_score(&self, a: f32, b: f32) -> f32 {
+        let scale = a.abs().max(b.abs()) * self.tolerance;
+        if scale == 0.0 {
+            return 0.0;
+        }
+        (1.0 - (a - b).abs() / scale).max(0.0)
+    }
+}
+
+fn labelled(points: &[Swing], labels: &[&str]) -> Vec<PatternPoint> {
+    points
+        .iter()
+        .zip(labels)
+        .map(|(swing, label)| PatternPoint::from_swing(label, swing))
+        .collect()
+}
+
+/// Whether a bar after the last swing closes beyond the breakout level in the
+/// direction of the pattern.
+fn breaks_out<F>(data: &[Bar], points: &[Swing], direction: Direction, level: F) -> bool
+where
+    F: Fn(usize) -> f32,
+{
+    let last = points[points.len() - 1].index;
+    data.iter()
+        .enumerate()
+        .skip(last + 1)
+        .any(|(i, bar)| direction.sign() * (bar.close - level(i)) > 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::analysis::chart_patterns::ChartPatterns;
+    use crate::analysis::{Direction, PatternKind, Swing, SwingKind};
+    use crate::domain::Bar;
+
+    /// Bars moving in a straight line between the given prices, five bars
+    /// apart, so that the prices fall on the bars of `sample_swings`.
+    fn interpolated_bars(prices: &[f32]) -> Vec<Bar> {
+        let mut closes: Vec<f32> = prices
+            .windows(2)
+            .flat_map(|pair| {
+                (0..5).map(move |step| pair[0] + (pair[1] - pair[0]) * step as f32 / 5.0)
+            })
+            .collect();
+        closes.extend(prices.last());
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Bar {
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    /// Alternating confirmed swings at the given prices, five bars apart,
+    /// starting with a swing of the given kind.
+    fn sample_swings(first: SwingKind, prices: &[f32]) -> Vec<Swing> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| Swing {
+                kind: if (i % 2 == 0) == (first == SwingKind::High) {
+                    SwingKind::High
+                } else {
+                    SwingKind::Low
+                },
+                index: i * 5,
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i * 5),
+                price: *price,
+                confirmed_at: Some(i * 5 + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_double_top() {
+        let prices = [120.0, 110.0, 120.5];
+        let data = interpolated_bars(&[120.0, 110.0, 120.5, 105.0]);
+        let swings = sample_swings(SwingKind::High, &prices);
+
+        let patterns = ChartPatterns::default().scan(&data, &swings);
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].kind, PatternKind::DoubleTop);
+        assert_eq!(patterns[0].direction, Direction::Down);
+        assert_float_absolute_eq!(patterns[0].completion.low, 99.5, 1e-3);
+        assert_float_absolute_eq!(patterns[0].completion.high, 110.0, 1e-3);
+        assert!(patterns[0].complete);
+
+        let data = interpolated_bars(&prices);
+        let patterns = ChartPatterns::default().scan(&data, &swings);
+        assert!(!patterns[0].complete);
+    }
+
+    #[test]
+    fn test_head_and_shoulders() {
+        let prices = [115.0, 105.0, 125.0, 106.0, 116.0];
+        let data = interpolated_bars(&[115.0, 105.0, 125.0, 106.0, 116.0, 100.0]);
+        let swings = sample_swings(SwingKind::High, &prices);
+
+        let patterns = ChartPatterns::default().scan(&data, &swings);
+        let pattern = patterns
+            .iter()
+            .find(|pattern| pattern.kind == PatternKind::HeadAndShoulders)
+            .unwrap();
+        assert_eq!(pattern.points[2].label, "Head");
+        assert_float_absolute_eq!(pattern.completion.low, 87.0, 1e-3);
+        assert_float_absolute_eq!(pattern.completion.high, 106.5, 1e-3);
+        assert!(pattern.complete);
+    }
+
+    #[test]
+    fn test_triangle_and_flag() {
+        let prices = [100.0, 120.0, 106.0, 120.5, 112.0];
+        let data = interpolated_bars(&prices);
+        let swings = sample_swings(SwingKind::Low, &prices);
+
+        let patterns = ChartPatterns::default().scan(&data, &swings);
+        let triangle = patterns
+            .iter()
+            .find(|pattern| pattern.kind == PatternKind::AscendingTriangle)
+            .unwrap();
+        assert_eq!(triangle.direction, Direction::Up);
+        assert_float_absolute_eq!(triangle.completion.low, 120.5, 1e-3);
+        assert_float_absolute_eq!(triangle.completion.high, 141.0, 1e-3);
+        assert!(!triangle.complete);
+
+        let prices = [100.0, 130.0, 124.0, 128.0, 121.0];
+        let data = interpolated_bars(&prices);
+        let swings = sample_swings(SwingKind::Low, &prices);
+
+        let patterns = ChartPatterns::default().scan(&data, &swings);
+        let flag = patterns
+            .iter()
+            .find(|pattern| pattern.kind == PatternKind::BullFlag)
+            .unwrap();
+        assert_float_absolute_eq!(flag.confidence, 0.7, 1e-3);
+        assert_float_absolute_eq!(flag.completion.high, 158.0, 1e-3);
+    }
+}
@@ -0,0 +1,218 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{Direction, PatternPoint, Swing, SwingKind};
+use crate::domain::{Bar, Line};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DivergenceKind {
+    /// Price makes a new extreme that the oscillator fails to confirm,
+    /// signalling a reversal.
+    Regular,
+    /// The oscillator makes a new extreme that price fails to confirm,
+    /// signalling a continuation.
+    Hidden,
+}
+
+/// An oscillator extreme anchoring a divergence.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OscillatorPoint {
+    pub index: usize,
+    pub timestamp: String,
+    pub value: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    pub direction: Direction,
+    /// The oscillator line, e.g. `rsi` or `macd:histogram`.
+    pub source: String,
+    /// Two consecutive swing highs or swing lows.
+    pub price: [PatternPoint; 2],
+    /// The oscillator extremes nearest to the two swings.
+    pub oscillator: [OscillatorPoint; 2],
+    /// Bar on which the second swing was confirmed, if it has been.
+    pub confirmed_at: Option<usize>,
+}
+
+/// Divergence detection between price swings and an oscillator
+///
+/// Compares consecutive swing lows, and consecutive swing highs, with the
+/// extremes of the oscillator within `window` bars of each swing:
+///
+/// regular bullish = lower low in price, higher low in the oscillator
+/// hidden bullish  = higher low in price, lower low in the oscillator
+/// regular bearish = higher high in price, lower high in the oscillator
+/// hidden bearish  = lower high in price, higher high in the oscillator
+pub struct Divergences {
+    window: usize,
+}
+
+impl Default for Divergences {
+    fn default() -> Self {
+        Divergences { window: 2 }
+    }
+}
+
+impl Divergences {
+    pub fn new(window: usize) -> Self {
+        Divergences { window }
+    }
+
+    pub fn scan(
+        &self,
+        data: &[Bar],
+        swings: &[Swing],
+        line: &Line,
+        source: &str,
+    ) -> Vec<Divergence> {
+        swings
+            .windows(3)
+            .filter_map(|points| {
+                let (first, second) = (&points[0], &points[2]);
+                let a = self.extreme(data, line, first)?;
+                let b = self.extreme(data, line, second)?;
+
+                let direction = Direction::from_swing(first);
+                // Oriented so that lows are the extremes of interest
+                let sign = direction.sign();
+                let price_change = sign * (second.price - first.price);
+                let oscillator_change = sign * (b.value - a.value);
+                let kind = if price_change < 0.0 && oscillator_change > 0.0 {
+                    DivergenceKind::Regular
+                } else if price_change > 0.0 && oscillator_change < 0.0 {
+                    DivergenceKind::Hidden
+                } else {
+                    return None;
+                };
+
+                Some(Divergence {
+                    kind,
+                    direction,
+                    source: source.to_string(),
+                    price: [
+                        PatternPoint::from_swing("1", first),
+                        PatternPoint::from_swing("2", second),
+                    ],
+                    oscillator: [a, b],
+                    confirmed_at: second.confirmed_at,
+                })
+            })
+            .collect()
+    }
+
+    /// The lowest oscillator value around a swing low, or the highest around
+    /// a swing high.
+    fn extreme(&self, data: &[Bar], line: &Line, swing: &Swing) -> Option<OscillatorPoint> {
+        let start = swing.index.saturating_sub(self.window);
+        let end = (swing.index + self.window).min(line.len().saturating_sub(1));
+        let sign = match swing.kind {
+            SwingKind::Low => 1.0,
+            SwingKind::High => -1.0,
+        };
+
+        (start..=end)
+            .filter_map(|index| {
+                line.get(index)
+                    .copied()
+                    .flatten()
+                    .map(|value| (index, value))
+            })
+            .min_by(|a, b| (sign * a.1).total_cmp(&(sign * b.1)))
+            .map(|(index, value)| OscillatorPoint {
+                index,
+                timestamp: data
+                    .get(index)
+                    .map_or_else(String::new, |bar| bar.timestamp.clone()),
+                value,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use crate::analysis::divergences::{DivergenceKind, Divergences};
+    use crate::analysis::{Direction, Swing, SwingKind};
+    use crate::domain::Bar;
+
+    /// Bars moving in a straight line between the given prices, five bars
+    /// apart, so that the prices fall on the bars of `sample_swings`.
+    fn interpolated_bars(prices: &[f32]) -> Vec<Bar> {
+        let mut closes: Vec<f32> = prices
+            .windows(2)
+            .flat_map(|pair| {
+                (0..5).map(move |step| pair[0] + (pair[1] - pair[0]) * step as f32 / 5.0)
+            })
+            .collect();
+        closes.extend(prices.last());
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Bar {
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    /// Alternating confirmed swings at the given prices, five bars apart,
+    /// starting with a swing of the given kind.
+    fn sample_swings(first: SwingKind, prices: &[f32]) -> Vec<Swing> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| Swing {
+                kind: if (i % 2 == 0) == (first == SwingKind::High) {
+                    SwingKind::High
+                } else {
+                    SwingKind::Low
+                },
+                index: i * 5,
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i * 5),
+                price: *price,
+                confirmed_at: Some(i * 5 + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_divergences() {
+        let prices = [100.0, 110.0, 95.0, 108.0];
+        let data = interpolated_bars(&prices);
+        let swings = sample_swings(SwingKind::Low, &prices);
+        let mut line: VecDeque<Option<f32>> = vec![Some(50.0); data.len()].into();
+        line[0] = None;
+        line[1] = Some(20.0);
+        line[5] = Some(70.0);
+        line[10] = Some(30.0);
+        line[14] = Some(75.0);
+
+        let divergences = Divergences::default().scan(&data, &swings, &line, "rsi");
+        assert_eq!(divergences.len(), 2);
+
+        let bullish = &divergences[0];
+        assert_eq!(bullish.kind, DivergenceKind::Regular);
+        assert_eq!(bullish.direction, Direction::Up);
+        assert_eq!(bullish.price[1].price, 95.0);
+        assert_eq!(bullish.oscillator[0].index, 1);
+        assert_eq!(bullish.oscillator[1].value, 30.0);
+        assert_eq!(bullish.confirmed_at, Some(11));
+
+        let bearish = &divergences[1];
+        assert_eq!(bearish.kind, DivergenceKind::Hidden);
+        assert_eq!(bearish.direction, Direction::Down);
+        assert_eq!(bearish.oscillator[1].index, 14);
+        assert_eq!(bearish.source, "rsi");
+
+        let divergences = Divergences::new(0).scan(&data, &swings, &line, "rsi");
+        assert!(divergences.is_empty());
+    }
+}
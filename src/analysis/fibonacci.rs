@@ -0,0 +1,256 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{PatternPoint, Swing};
+use crate::domain::Bar;
+
+/// Retracement ratios of the move between the first two anchors.
+pub const RETRACEMENT_RATIOS: [f32; 7] = [0.0, 0.236, 0.382, 0.5, 0.618, 0.786, 1.0];
+
+/// Extension ratios of the move between the first two anchors, projected
+/// from the first anchor, or from the third anchor when there is one. Ratios
+/// up to 1 are retracements, so extensions start above it.
+pub const EXTENSION_RATIOS: [f32; 6] = [1.272, 1.618, 2.0, 2.618, 3.618, 4.236];
+
+/// Fibonacci numbers of the time zones, in multiples of the bars between the
+/// first two anchors.
+pub const TIME_ZONES: [usize; 11] = [0, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89];
+
+const ANCHOR_LABELS: [&str; 3] = ["A", "B", "C"];
+
+/// A horizontal level at a Fibonacci ratio of a move.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriceLevel {
+    pub ratio: f32,
+    pub price: f32,
+}
+
+/// A vertical level at a Fibonacci number of bars from the first anchor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeLevel {
+    pub number: usize,
+    pub index: usize,
+    /// Unix time of the bar, in seconds. Bars beyond the series are
+    /// extrapolated at the spacing of its last two bars.
+    pub time: i64,
+}
+
+/// Fibonacci retracement, extension and time zone levels between two or
+/// three anchors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FibonacciLevels {
+    pub anchors: Vec<PatternPoint>,
+    pub retracements: Vec<PriceLevel>,
+    pub extensions: Vec<PriceLevel>,
+    pub time_zones: Vec<TimeLevel>,
+}
+
+impl FibonacciLevels {
+    /// Calculates the levels between the anchors, in time order.
+    ///
+    /// Retracements are measured back from the second anchor towards the
+    /// first. Extensions project the move from the first to the second anchor
+    /// from the first anchor, or from the third anchor when there is one,
+    /// e.g. the end of a correction.
+    pub fn new(data: &[Bar], anchors: Vec<PatternPoint>) -> Result<Self, String> {
+        if !(2..=3).contains(&anchors.len()) {
+            return Err(format!(
+                "fibonacci: expected 2 or 3 anchors, found {}",
+                anchors.len()
+            ));
+        }
+        if anchors
+            .windows(2)
+            .any(|pair| pair[1].index <= pair[0].index)
+        {
+            return Err("fibonacci: anchors must be in time order".to_string());
+        }
+
+        let (start, end) = (&anchors[0], &anchors[1]);
+        let change = end.price - start.price;
+        let origin = anchors.get(2).unwrap_or(start).price;
+        let retracements = RETRACEMENT_RATIOS
+            .iter()
+            .map(|ratio| PriceLevel {
+                ratio: *ratio,
+                price: end.price - ratio * change,
+            })
+            .collect();
+        let extensions = EXTENSION_RATIOS
+            .iter()
+            .map(|ratio| PriceLevel {
+                ratio: *ratio,
+                price: origin + ratio * change,
+            })
+            .collect();
+        let bars = end.index - start.index;
+        let time_zones = TIME_ZONES
+            .iter()
+            .map(|number| {
+                let index = start.index + number * bars;
+                TimeLevel {
+                    number: *number,
+                    index,
+                    time: bar_time(data, index),
+                }
+            })
+            .collect();
+
+        Ok(FibonacciLevels {
+            anchors,
+            retracements,
+            extensions,
+            time_zones,
+        })
+    }
+
+    /// Anchors the levels at the last `count` swings.
+    pub fn from_swings(data: &[Bar], swings: &[Swing], count: usize) -> Result<Self, String> {
+        if swings.len() < count {
+            return Err(format!(
+                "fibonacci: expected at least {} swings, found {}",
+                count,
+                swings.len()
+            ));
+        }
+        let anchors = swings[swings.len() - count..]
+            .iter()
+            .zip(ANCHOR_LABELS)
+            .map(|(swing, label)| PatternPoint::from_swing(label, swing))
+            .collect();
+        FibonacciLevels::new(data, anchors)
+    }
+
+    /// Anchors the levels at the bars with the given indices, alternating
+    /// between lows and highs. The first anchor is a low when the second bar
+    /// closes above the first, and a high otherwise.
+    pub fn from_indices(data: &[Bar], indices: &[usize]) -> Result<Self, String> {
+        if let Some(index) = indices.iter().find(|index| **index >= data.len()) {
+            return Err(format!("fibonacci: no bar at index {}", index));
+        }
+        let rising = indices.len() > 1 && data[indices[1]].close > data[indices[0]].close;
+        let anchors = indices
+            .iter()
+            .zip(ANCHOR_LABELS)
+            .enumerate()
+            .map(|(i, (index, label))| {
+                let bar = &data[*index];
+                PatternPoint {
+                    label: label.to_string(),
+                    index: *index,
+                    timestamp: bar.timestamp.clone(),
+                    price: if (i % 2 == 0) == rising {
+                        bar.low
+                    } else {
+                        bar.high
+                    },
+                }
+            })
+            .collect();
+        FibonacciLevels::new(data, anchors)
+    }
+}
+
+/// Unix time of the bar at `index`, extrapolated beyond the last bar.
+fn bar_time(data: &[Bar], index: usize) -> i64 {
+    let time = |bar: &Bar| {
+        bar.datetime()
+            .map_or(0, |datetime| datetime.and_utc().timestamp())
+    };
+    if let Some(bar) = data.get(index) {
+        return time(bar);
+    }
+    match data {
+        [.., prev, last] => {
+            time(last) + (time(last) - time(prev)) * (index + 1 - data.len()) as i64
+        }
+        [last] => time(last),
+        [] => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::analysis::fibonacci::FibonacciLevels;
+    use crate::analysis::{Swing, SwingKind};
+    use crate::domain::Bar;
+
+    /// Bars moving in a straight line between the given prices, five bars
+    /// apart, so that the prices fall on the bars of `sample_swings`.
+    fn interpolated_bars(prices: &[f32]) -> Vec<Bar> {
+        let mut closes: Vec<f32> = prices
+            .windows(2)
+            .flat_map(|pair| {
+                (0..5).map(move |step| pair[0] + (pair[1] - pair[0]) * step as f32 / 5.0)
+            })
+            .collect();
+        closes.extend(prices.last());
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Bar {
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    /// Alternating confirmed swings at the given prices, five bars apart,
+    /// starting with a swing of the given kind.
+    fn sample_swings(first: SwingKind, prices: &[f32]) -> Vec<Swing> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| Swing {
+                kind: if (i % 2 == 0) == (first == SwingKind::High) {
+                    SwingKind::High
+                } else {
+                    SwingKind::Low
+                },
+                index: i * 5,
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i * 5),
+                price: *price,
+                confirmed_at: Some(i * 5 + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_retracements_and_extensions() {
+        let prices = [100.0, 200.0, 150.0];
+        let data = interpolated_bars(&prices);
+        let swings = sample_swings(SwingKind::Low, &prices);
+
+        let levels = FibonacciLevels::from_swings(&data, &swings, 2).unwrap();
+        assert_eq!(levels.anchors[0].label, "A");
+        assert_eq!(levels.anchors[0].price, 200.0);
+        assert_float_absolute_eq!(levels.retracements[2].price, 169.1, 1e-3);
+        assert_float_absolute_eq!(levels.extensions[1].price, 119.1, 1e-3);
+
+        let levels = FibonacciLevels::from_swings(&data, &swings, 3).unwrap();
+        assert_float_absolute_eq!(levels.retracements[4].price, 138.2, 1e-3);
+        assert_float_absolute_eq!(levels.extensions[1].price, 311.8, 1e-3);
+
+        assert!(FibonacciLevels::from_swings(&data, &swings, 4).is_err());
+    }
+
+    #[test]
+    fn test_time_zones() {
+        let data = interpolated_bars(&[100.0, 120.0, 110.0]);
+
+        let levels = FibonacciLevels::from_indices(&data, &[2, 5]).unwrap();
+        assert_eq!(levels.anchors[0].price, data[2].low);
+        assert_eq!(levels.time_zones[4].index, 17);
+        let last = data[10].datetime().unwrap().and_utc().timestamp();
+        assert_eq!(levels.time_zones[4].time, last + 7 * 60);
+
+        assert!(FibonacciLevels::from_indices(&data, &[5, 2]).is_err());
+        assert!(FibonacciLevels::from_indices(&data, &[2, 11]).is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use crate::analysis::{
+    Direction, Pattern, PatternKind, PatternPoint, PriceZone, Swing, ratio_score,
+};
+
+/// Ideal Fibonacci ratios of a harmonic pattern, as (min, max) ranges.
+struct HarmonicRatios {
+    kind: PatternKind,
+    /// AB as a retracement of XA.
+    ab: (f32, f32),
+    /// BC as a retracement of AB.
+    bc: (f32, f32),
+    /// CD as an extension of BC.
+    cd: (f32, f32),
+    /// AD as a retracement of XA, which locates point D.
+    ad: (f32, f32),
+}
+
+const HARMONIC_RATIOS: [HarmonicRatios; 4] = [
+    HarmonicRatios {
+        kind: PatternKind::Gartley,
+        ab: (0.618, 0.618),
+        bc: (0.382, 0.886),
+        cd: (1.272, 1.618),
+        ad: (0.786, 0.786),
+    },
+    HarmonicRatios {
+        kind: PatternKind::Bat,
+        ab: (0.382, 0.5),
+        bc: (0.382, 0.886),
+        cd: (1.618, 2.618),
+        ad: (0.886, 0.886),
+    },
+    HarmonicRatios {
+        kind: PatternKind::Butterfly,
+        ab: (0.786, 0.786),
+        bc: (0.382, 0.886),
+        cd: (1.618, 2.24),
+        ad: (1.27, 1.618),
+    },
+    HarmonicRatios {
+        kind: PatternKind::Crab,
+        ab: (0.382, 0.618),
+        bc: (0.382, 0.886),
+        cd: (2.24, 3.618),
+        ad: (1.618, 1.618),
+    },
+];
+
+/// Harmonic pattern recognition
+///
+/// Matches five consecutive swings X, A, B, C and D against the Fibonacci
+/// ratios of the Gartley, Bat, Butterfly and Crab patterns. A ratio matches
+/// when it lies within its ideal range, widened by the relative tolerance,
+/// and the confidence of a pattern is the mean score of its ratios.
+///
+/// The completion zone is the potential reversal zone of point D: the
+/// projection of the AD retracement of XA, narrowed to its overlap with the
+/// CD extension of BC when the two agree. The last four swings are also
+/// matched as X, A, B and C of a pattern whose point D has yet to form.
+///
+/// A pattern starting from a low is bullish and one starting from a high is
+/// bearish.
+pub struct HarmonicPatterns {
+    tolerance: f32,
+}
+
+impl Default for HarmonicPatterns {
+    fn default() -> Self {
+        HarmonicPatterns { tolerance: 0.05 }
+    }
+}
+
+impl HarmonicPatterns {
+    pub fn new(tolerance: f32) -> Self {
+        HarmonicPatterns { tolerance }
+    }
+
+    pub fn scan(&self, swings: &[Swing]) -> Vec<Pattern> {
+        let mut patterns: Vec<Pattern> = swings
+            .windows(5)
+            .flat_map(|points| self.matches(points))
+            .collect();
+        if let Some(points) = swings.len().checked_sub(4).map(|start| &swings[start..]) {
+            patterns.extend(self.matches(points));
+        }
+        patterns
+    }
+
+    /// Patterns matched by the points X, A, B, C and, if formed, D.
+    fn matches(&self, points: &[Swing]) -> Vec<Pattern> {
+        let price = |i: usize| points[i].price;
+        let (xa, ab, bc) = (
+            (price(1) - price(0)).abs(),
+            (price(2) - price(1)).abs(),
+            (price(3) - price(2)).abs(),
+        );
+        if xa == 0.0 || ab == 0.0 || bc == 0.0 {
+            return Vec::new();
+        }
+        let direction = Direction::from_swing(&points[0]);
+        // Point D lies below A in a bullish pattern
+        let towards_d = -direction.sign();
+
+        HARMONIC_RATIOS
+            .iter()
+            .filter_map(|ratios| {
+                let mut scores = vec![
+                    ratio_score(ab / xa, ratios.ab, self.tolerance),
+                    ratio_score(bc / ab, ratios.bc, self.tolerance),
+                ];
+                if let Some(d) = points.get(4) {
+                    let cd = (d.price - price(3)).abs();
+                    let ad = (d.price - price(1)).abs();
+                    scores.push(ratio_score(cd / bc, ratios.cd, self.tolerance));
+                    scores.push(ratio_score(ad / xa, ratios.ad, self.tolerance));
+                }
+                if scores.contains(&0.0) {
+                    return None;
+                }
+
+                let widen = |(min, max): (f32, f32)| {
+                    (min * (1.0 - self.tolerance), max * (1.0 + self.tolerance))
+                };
+                let (ad_min, ad_max) = widen(ratios.ad);
+                let (cd_min, cd_max) = widen(ratios.cd);
+                let retracement = PriceZone::new(
+                    price(1) + towards_d * ad_min * xa,
+                    price(1) + towards_d * ad_max * xa,
+                );
+                let extension = PriceZone::new(
+                    price(3) + towards_d * cd_min * bc,
+                    price(3) + towards_d * cd_max * bc,
+                );
+
+                Some(Pattern {
+                    kind: ratios.kind,
+                    direction,
+                    points: points
+                        .iter()
+                        .zip(["X", "A", "B", "C", "D"])
+                        .map(|(swing, label)| PatternPoint::from_swing(label, swing))
+                        .collect(),
+                    completion: retracement.intersect(&extension).unwrap_or(retracement),
+                    confidence: scores.iter().sum::<f32>() / scores.len() as f32,
+                    complete: points.len() == 5,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::analysis::harmonics::HarmonicPatterns;
+    use crate::analysis::{Direction, PatternKind, Swing, SwingKind};
+
+    /// Alternating confirmed swings at the given prices, five bars apart,
+    /// starting with a swing of the given kind.
+    fn sample_swings(first: SwingKind, prices: &[f32]) -> Vec<Swing> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| Swing {
+                kind: if (i % 2 == 0) == (first == SwingKind::High) {
+                    SwingKind::High
+                } else {
+                    SwingKind::Low
+                },
+                index: i * 5,
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i * 5),
+                price: *price,
+                confirmed_at: Some(i * 5 + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_gartley() {
+        // AB = 61.8% of XA, BC = 61.8% of AB, CD = 144% of BC and AD = 78.6% of XA
+        let data = sample_swings(SwingKind::Low, &[100.0, 200.0, 138.2, 176.39, 121.4]);
+
+        let patterns = HarmonicPatterns::default().scan(&data);
+        let gartley = patterns
+            .iter()
+            .find(|pattern| pattern.kind == PatternKind::Gartley && pattern.complete)
+            .unwrap();
+        assert_eq!(gartley.direction, Direction::Up);
+        assert_eq!(gartley.points[4].label, "D");
+        assert_float_absolute_eq!(gartley.confidence, 1.0, 1e-3);
+        assert!(gartley.completion.low < 121.4 && gartley.completion.high > 121.4);
+        assert!(
+            patterns
+                .iter()
+                .all(|pattern| pattern.kind != PatternKind::Crab)
+        );
+    }
+
+    #[test]
+    fn test_forming_pattern() {
+        // The last four swings form X, A, B and C of a bearish Bat
+        let data = sample_swings(SwingKind::High, &[200.0, 100.0, 145.0, 120.0]);
+
+        let patterns = HarmonicPatterns::default().scan(&data);
+        let bat = patterns
+            .iter()
+            .find(|pattern| pattern.kind == PatternKind::Bat)
+            .unwrap();
+        assert_eq!(bat.direction, Direction::Down);
+        assert!(!bat.complete);
+        // D is expected near the 88.6% retracement of XA
+        assert!(bat.completion.low <= 188.6 && bat.completion.high >= 188.6);
+    }
+}
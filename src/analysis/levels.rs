@@ -0,0 +1,309 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{PriceZone, Swing};
+use crate::domain::Bar;
+
+/// Most round number levels within the price range of the bars.
+const MAX_ROUND_LEVELS: usize = 10;
+/// Smallest volume of a high volume node, as a multiple of the mean volume
+/// per price bin.
+const VOLUME_NODE_FACTOR: f32 = 1.5;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LevelKind {
+    Support,
+    Resistance,
+}
+
+/// Evidence that a price acts as support or resistance.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelSource {
+    Swing,
+    VolumeNode,
+    RoundNumber,
+}
+
+/// A support or resistance zone relative to the last close.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Level {
+    pub kind: LevelKind,
+    pub price: f32,
+    pub zone: PriceZone,
+    /// Score relative to the strongest level, which scores 1.
+    pub strength: f32,
+    /// Number of separate visits of the bars to the zone.
+    pub touches: usize,
+    pub sources: Vec<LevelSource>,
+}
+
+/// Support and resistance detection
+///
+/// Clusters the prices of swing pivots, high volume nodes of the volume at
+/// price profile and round numbers that lie within a relative `tolerance` of
+/// each other into zones. Round numbers are the multiples of the smallest
+/// power of ten ticks that gives at most ten levels over the bars.
+///
+/// The raw score of a zone is:
+///
+/// score = pivots + touches / 2 + 2 * volume nodes + round numbers
+///
+/// Zones that the bars never visit are dropped.
+pub struct SupportResistance {
+    tick_size: f32,
+    tolerance: f32,
+    bins: usize,
+}
+
+impl SupportResistance {
+    pub fn new(tick_size: f32) -> Self {
+        SupportResistance {
+            tick_size,
+            tolerance: 0.005,
+            bins: 50,
+        }
+    }
+
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Levels ordered from the strongest.
+    pub fn levels(&self, data: &[Bar], swings: &[Swing]) -> Vec<Level> {
+        let Some(last) = data.last() else {
+            return Vec::new();
+        };
+        let low = data.iter().map(|bar| bar.low).fold(f32::MAX, f32::min);
+        let high = data.iter().map(|bar| bar.high).fold(f32::MIN, f32::max);
+
+        let mut candidates: Vec<(f32, LevelSource)> = swings
+            .iter()
+            .map(|swing| (swing.price, LevelSource::Swing))
+            .chain(
+                self.volume_nodes(data, low, high)
+                    .into_iter()
+                    .map(|price| (price, LevelSource::VolumeNode)),
+            )
+            .chain(
+                self.round_numbers(low, high)
+                    .into_iter()
+                    .map(|price| (price, LevelSource::RoundNumber)),
+            )
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut clusters: Vec<Vec<(f32, LevelSource)>> = Vec::new();
+        for candidate in candidates {
+            match clusters.last_mut() {
+                Some(cluster) if candidate.0 - cluster[0].0 <= self.tolerance * candidate.0 => {
+                    cluster.push(candidate)
+                }
+                _ => clusters.push(vec![candidate]),
+            }
+        }
+
+        let mut levels: Vec<(f32, Level)> = clusters
+            .into_iter()
+            .filter_map(|cluster| {
+                let price =
+                    cluster.iter().map(|(price, _)| price).sum::<f32>() / cluster.len() as f32;
+                let margin = self.tolerance * price / 2.0;
+                let zone = PriceZone::new(
+                    cluster[0].0.min(price - margin),
+                    cluster[cluster.len() - 1].0.max(price + margin),
+                );
+                let touches = touches(data, &zone);
+                if touches == 0 {
+                    return None;
+                }
+                let score = cluster
+                    .iter()
+                    .map(|(_, source)| match source {
+                        LevelSource::Swing => 1.0,
+                        LevelSource::VolumeNode => 2.0,
+                        LevelSource::RoundNumber => 1.0,
+                    })
+                    .sum::<f32>()
+                    + touches as f32 / 2.0;
+                let kind = if price <= last.close {
+                    LevelKind::Support
+                } else {
+                    LevelKind::Resistance
+                };
+                let mut sources: Vec<LevelSource> = Vec::new();
+                for (_, source) in &cluster {
+                    if !sources.contains(source) {
+                        sources.push(*source);
+                    }
+                }
+
+                Some((
+                    score,
+                    Level {
+                        kind,
+                        price,
+                        zone,
+                        strength: 0.0,
+                        touches,
+                        sources,
+                    },
+                ))
+            })
+            .collect();
+
+        levels.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let strongest = levels.first().map_or(1.0, |(score, _)| *score);
+        levels
+            .into_iter()
+            .map(|(score, mut level)| {
+                level.strength = score / strongest;
+                level
+            })
+            .collect()
+    }
+
+    /// Prices of the bins of the volume at price profile holding a local
+    /// maximum of volume well above the mean. The volume of each bar is
+    /// spread evenly over the bins its range covers.
+    fn volume_nodes(&self, data: &[Bar], low: f32, high: f32) -> Vec<f32> {
+        let width = (high - low) / self.bins as f32;
+        if width <= 0.0 {
+            return Vec::new();
+        }
+        let bin = |price: f32| (((price - low) / width) as usize).min(self.bins - 1);
+
+        let mut profile = vec![0.0; self.bins];
+        for bar in data {
+            let (first, last) = (bin(bar.low), bin(bar.high));
+            let share = bar.volume / (last - first + 1) as f32;
+            for volume in &mut profile[first..=last] {
+                *volume += share;
+            }
+        }
+
+        let mean = profile.iter().sum::<f32>() / self.bins as f32;
+        (0..self.bins)
+            .filter(|&i| {
+                let volume = profile[i];
+                volume >= VOLUME_NODE_FACTOR * mean
+                    && (i == 0 || volume >= profile[i - 1])
+                    && (i + 1 == self.bins || volume > profile[i + 1])
+            })
+            .map(|i| low + (i as f32 + 0.5) * width)
+            .collect()
+    }
+
+    fn round_numbers(&self, low: f32, high: f32) -> Vec<f32> {
+        if self.tick_size <= 0.0 || high <= low {
+            return Vec::new();
+        }
+        // In f64 with some slack, so that a price on a multiple is not lost
+        // to rounding
+        let (low, high) = (low as f64, high as f64);
+        let mut step = self.tick_size as f64;
+        while (high - low) / step > MAX_ROUND_LEVELS as f64 {
+            step *= 10.0;
+        }
+        let first = (low / step - 1e-6).ceil() as i64;
+        let last = (high / step + 1e-6).floor() as i64;
+        (first..=last).map(|n| (n as f64 * step) as f32).collect()
+    }
+}
+
+/// Number of separate runs of bars whose range overlaps the zone.
+fn touches(data: &[Bar], zone: &PriceZone) -> usize {
+    let mut touches = 0;
+    let mut inside = false;
+    for bar in data {
+        let touching = bar.high >= zone.low && bar.low <= zone.high;
+        if touching && !inside {
+            touches += 1;
+        }
+        inside = touching;
+    }
+    touches
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::analysis::levels::{LevelKind, LevelSource, SupportResistance};
+    use crate::analysis::{Swing, SwingKind};
+    use crate::domain::Bar;
+
+    /// Bars moving in a straight line between the given prices, five bars
+    /// apart, so that the prices fall on the bars of `sample_swings`.
+    fn interpolated_bars(prices: &[f32]) -> Vec<Bar> {
+        let mut closes: Vec<f32> = prices
+            .windows(2)
+            .flat_map(|pair| {
+                (0..5).map(move |step| pair[0] + (pair[1] - pair[0]) * step as f32 / 5.0)
+            })
+            .collect();
+        closes.extend(prices.last());
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| Bar {
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+                open: *close,
+                high: *close,
+                low: *close,
+                close: *close,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    /// Alternating confirmed swings at the given prices, five bars apart,
+    /// starting with a swing of the given kind.
+    fn sample_swings(first: SwingKind, prices: &[f32]) -> Vec<Swing> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| Swing {
+                kind: if (i % 2 == 0) == (first == SwingKind::High) {
+                    SwingKind::High
+                } else {
+                    SwingKind::Low
+                },
+                index: i * 5,
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i * 5),
+                price: *price,
+                confirmed_at: Some(i * 5 + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_levels() {
+        let prices = [100.0, 110.0, 100.2, 110.3, 100.1];
+        let data = interpolated_bars(&[100.0, 110.0, 100.2, 110.3, 100.1, 104.0]);
+        let swings = sample_swings(SwingKind::Low, &prices);
+
+        let levels = SupportResistance::new(0.01).levels(&data, &swings);
+        let support = &levels[0];
+        assert_eq!(support.kind, LevelKind::Support);
+        assert_eq!(support.touches, 3);
+        assert_float_absolute_eq!(support.strength, 1.0, 1e-6);
+        assert!(support.zone.low <= 100.0 && support.zone.high >= 100.2);
+        assert!(support.sources.contains(&LevelSource::RoundNumber));
+
+        let resistance = &levels[1];
+        assert_eq!(resistance.kind, LevelKind::Resistance);
+        assert_eq!(resistance.touches, 2);
+        assert!(resistance.zone.low <= 110.0 && resistance.zone.high >= 110.3);
+        assert!(resistance.strength < 1.0);
+    }
+
+    #[test]
+    fn test_round_numbers() {
+        let levels = SupportResistance::new(0.0001).round_numbers(0.6512, 0.6987);
+        assert_eq!(levels.len(), 4);
+        assert_float_absolute_eq!(levels[0], 0.66, 1e-6);
+    }
+}
@@ -0,0 +1,19 @@
+mod chart_patterns;
+mod divergences;
+mod fibonacci;
+mod harmonics;
+mod levels;
+mod patterns;
+mod swings;
+mod waves;
+
+pub use chart_patterns::ChartPatterns;
+pub use divergences::{Divergence, DivergenceKind, Divergences, OscillatorPoint};
+pub use fibonacci::{
+    EXTENSION_RATIOS, FibonacciLevels, PriceLevel, RETRACEMENT_RATIOS, TIME_ZONES, TimeLevel,
+};
+pub use harmonics::HarmonicPatterns;
+pub use levels::{Level, LevelKind, LevelSource, SupportResistance};
+pub use patterns::{Direction, Mark, Pattern, PatternKind, PatternPoint, PriceZone, ratio_score};
+pub use swings::{ReversalThreshold, Swing, SwingKind, ZigZag};
+pub use waves::{ElliottWave, Guideline, WaveCount, WavePattern};
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{Swing, SwingKind};
+use crate::domain::Bar;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    Up,
+    Down,
+}
+
+impl Direction {
+    /// Direction of a pattern starting from the given swing, which moves up
+    /// from a low and down from a high.
+    pub fn from_swing(swing: &Swing) -> Self {
+        match swing.kind {
+            SwingKind::Low => Direction::Up,
+            SwingKind::High => Direction::Down,
+        }
+    }
+
+    /// Sign that orients prices so that the direction is up.
+    pub fn sign(&self) -> f32 {
+        match self {
+            Direction::Up => 1.0,
+            Direction::Down => -1.0,
+        }
+    }
+}
+
+/// A labelled pivot of a pattern.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PatternPoint {
+    pub label: String,
+    pub index: usize,
+    pub timestamp: String,
+    pub price: f32,
+}
+
+impl PatternPoint {
+    pub fn from_swing(label: &str, swing: &Swing) -> Self {
+        PatternPoint {
+            label: label.to_string(),
+            index: swing.index,
+            timestamp: swing.timestamp.clone(),
+            price: swing.price,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriceZone {
+    pub low: f32,
+    pub high: f32,
+}
+
+impl PriceZone {
+    pub fn new(a: f32, b: f32) -> Self {
+        PriceZone {
+            low: a.min(b),
+            high: a.max(b),
+        }
+    }
+
+    /// The overlap of two zones, if any.
+    pub fn intersect(&self, other: &PriceZone) -> Option<PriceZone> {
+        let low = self.low.max(other.low);
+        let high = self.high.min(other.high);
+        (low <= high).then_some(PriceZone { low, high })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatternKind {
+    Gartley,
+    Bat,
+    Butterfly,
+    Crab,
+    DoubleTop,
+    DoubleBottom,
+    HeadAndShoulders,
+    InverseHeadAndShoulders,
+    AscendingTriangle,
+    DescendingTriangle,
+    SymmetricalTriangle,
+    BullFlag,
+    BearFlag,
+}
+
+impl PatternKind {
+    pub fn name(&self) -> &str {
+        match self {
+            PatternKind::Gartley => "Gartley",
+            PatternKind::Bat => "Bat",
+            PatternKind::Butterfly => "Butterfly",
+            PatternKind::Crab => "Crab",
+            PatternKind::DoubleTop => "Double Top",
+            PatternKind::DoubleBottom => "Double Bottom",
+            PatternKind::HeadAndShoulders => "Head and Shoulders",
+            PatternKind::InverseHeadAndShoulders => "Inverse Head and Shoulders",
+            PatternKind::AscendingTriangle => "Ascending Triangle",
+            PatternKind::DescendingTriangle => "Descending Triangle",
+            PatternKind::SymmetricalTriangle => "Symmetrical Triangle",
+            PatternKind::BullFlag => "Bull Flag",
+            PatternKind::BearFlag => "Bear Flag",
+        }
+    }
+
+    /// Whether the pattern is a harmonic XABCD pattern.
+    pub fn is_harmonic(&self) -> bool {
+        matches!(
+            self,
+            PatternKind::Gartley | PatternKind::Bat | PatternKind::Butterfly | PatternKind::Crab
+        )
+    }
+}
+
+/// A price pattern and the zone in which it is expected to complete. For
+/// harmonic patterns this is the potential reversal zone of point D, and for
+/// chart patterns the range between the breakout level and the measured
+/// move target.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    /// Direction in which price is expected to move on completion.
+    pub direction: Direction,
+    pub points: Vec<PatternPoint>,
+    pub completion: PriceZone,
+    /// How closely the pattern matches its ideal proportions, from 0 to 1.
+    pub confidence: f32,
+    /// Whether the pattern has completed: point D has formed for harmonic
+    /// patterns, or price has closed beyond the breakout level for chart
+    /// patterns.
+    pub complete: bool,
+}
+
+impl Pattern {
+    /// Index of the bar at which the pattern was last updated.
+    pub fn last_index(&self) -> usize {
+        self.points.last().map_or(0, |point| point.index)
+    }
+}
+
+/// A chart mark in the format of the TradingView charting library `getMarks`
+/// response, placed on the last bar of the pattern it carries.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Mark {
+    pub id: String,
+    /// Unix time of the bar, in seconds.
+    pub time: i64,
+    pub color: String,
+    pub text: String,
+    pub label: String,
+    #[serde(rename = "labelFontColor")]
+    pub label_font_color: String,
+    #[serde(rename = "minSize")]
+    pub min_size: u32,
+    pub pattern: Pattern,
+}
+
+impl Mark {
+    pub fn new(pattern: Pattern, data: &[Bar]) -> Self {
+        let index = pattern.last_index();
+        let time = data
+            .get(index)
+            .and_then(Bar::datetime)
+            .map_or(0, |datetime| datetime.and_utc().timestamp());
+        let (bias, color) = match pattern.direction {
+            Direction::Up => ("Bullish", "green"),
+            Direction::Down => ("Bearish", "red"),
+        };
+        let status = if pattern.complete { "" } else { "forming " };
+
+        Mark {
+            id: format!("{:?}-{}", pattern.kind, index).to_lowercase(),
+            time,
+            color: color.to_string(),
+            text: format!(
+                "{} {}{} ({:.0}%), completion {} - {}",
+                bias,
+                status,
+                pattern.kind.name(),
+                pattern.confidence * 100.0,
+                pattern.completion.low,
+                pattern.completion.high
+            ),
+            label: if pattern.kind.is_harmonic() { "H" } else { "P" }.to_string(),
+            label_font_color: "white".to_string(),
+            min_size: 14,
+            pattern,
+        }
+    }
+}
+
+/// Scores a ratio against the range of ideal ratios, from 1 inside the range
+/// to 0 at a relative distance of `tolerance` beyond either end.
+pub fn ratio_score(ratio: f32, (min, max): (f32, f32), tolerance: f32) -> f32 {
+    let distance = if ratio < min {
+        (min - ratio) / min
+    } else if ratio > max {
+        (ratio - max) / max
+    } else {
+        0.0
+    };
+    (1.0 - distance / tolerance).max(0.0)
+}
@@ -0,0 +1,355 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::Bar;
+use crate::indicators::ATR;
+
+/// The distance price must travel away from an extreme before the extreme is
+/// confirmed as a swing.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ReversalThreshold {
+    /// A percentage of the extreme price.
+    Percent(f32),
+    /// A multiple of the average true range over a period.
+    ATR(usize, f32), // period, multiplier
+    /// A number of bars without a new extreme, regardless of distance.
+    Bars(usize),
+}
+
+/// Three times the 14-bar average true range.
+impl Default for ReversalThreshold {
+    fn default() -> Self {
+        ReversalThreshold::ATR(14, 3.0)
+    }
+}
+
+impl ReversalThreshold {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ReversalThreshold::Percent(percent) if !percent.is_finite() || *percent <= 0.0 => {
+                Err("swings: percent must be a positive number".to_string())
+            }
+            ReversalThreshold::ATR(0, _) => {
+                Err("swings: atr period must be at least 1".to_string())
+            }
+            ReversalThreshold::ATR(_, multiplier)
+                if !multiplier.is_finite() || *multiplier <= 0.0 =>
+            {
+                Err("swings: atr multiplier must be a positive number".to_string())
+            }
+            ReversalThreshold::Bars(0) => Err("swings: bars must be at least 1".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Parses thresholds of the form `percent:5`, `atr:14:3` and `bars:5`.
+impl FromStr for ReversalThreshold {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').map(str::trim).collect();
+        let threshold = match parts.as_slice() {
+            ["percent", percent] => ReversalThreshold::Percent(parse(percent)?),
+            ["atr", period, multiplier] => {
+                ReversalThreshold::ATR(parse(period)?, parse(multiplier)?)
+            }
+            ["atr", period] => ReversalThreshold::ATR(parse(period)?, 3.0),
+            ["bars", bars] => ReversalThreshold::Bars(parse(bars)?),
+            _ => return Err(format!("swings: unsupported reversal threshold '{}'", s)),
+        };
+        threshold.validate()?;
+        Ok(threshold)
+    }
+}
+
+impl fmt::Display for ReversalThreshold {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReversalThreshold::Percent(percent) => write!(f, "percent:{}", percent),
+            ReversalThreshold::ATR(period, multiplier) => {
+                write!(f, "atr:{}:{}", period, multiplier)
+            }
+            ReversalThreshold::Bars(bars) => write!(f, "bars:{}", bars),
+        }
+    }
+}
+
+fn parse<T: FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("swings: invalid threshold value '{}'", value))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SwingKind {
+    High,
+    Low,
+}
+
+/// A swing high or low at the extreme of a bar.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Swing {
+    pub kind: SwingKind,
+    /// Index of the bar that made the extreme.
+    pub index: usize,
+    pub timestamp: String,
+    pub price: f32,
+    /// Index of the bar on which the reversal threshold was met. Tentative
+    /// swings, which may still be extended by a new extreme, have none.
+    pub confirmed_at: Option<usize>,
+}
+
+impl Swing {
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+/// ZigZag swing detection
+///
+/// Follows price from one extreme to the next, alternating between swing
+/// highs and lows. An extreme is confirmed as a swing once price reverses
+/// from it by the reversal threshold, at which point the most extreme price
+/// since becomes the next candidate swing in the opposite direction.
+///
+/// Until the first swing is confirmed, a candidate high and a candidate low
+/// are followed from the first bar and whichever is confirmed first sets the
+/// direction. The last candidate is returned as a tentative swing.
+pub struct ZigZag {
+    threshold: ReversalThreshold,
+}
+
+/// A candidate swing and the opposite extreme reached since it was made.
+#[derive(Clone, Copy)]
+struct Candidate {
+    kind: SwingKind,
+    index: usize,
+    price: f32,
+    opposite: Option<(usize, f32)>,
+}
+
+impl Candidate {
+    fn new(kind: SwingKind, index: usize, bar: &Bar) -> Self {
+        let price = match kind {
+            SwingKind::High => bar.high,
+            SwingKind::Low => bar.low,
+        };
+        Candidate {
+            kind,
+            index,
+            price,
+            opposite: None,
+        }
+    }
+
+    /// Extends the candidate with a new extreme, or otherwise tracks the
+    /// opposite extreme.
+    fn update(&mut self, index: usize, bar: &Bar) {
+        match self.kind {
+            SwingKind::High if bar.high > self.price => {
+                *self = Candidate::new(self.kind, index, bar)
+            }
+            SwingKind::Low if bar.low < self.price => *self = Candidate::new(self.kind, index, bar),
+            SwingKind::High => {
+                if self.opposite.is_none_or(|(_, low)| bar.low < low) {
+                    self.opposite = Some((index, bar.low));
+                }
+            }
+            SwingKind::Low => {
+                if self.opposite.is_none_or(|(_, high)| bar.high > high) {
+                    self.opposite = Some((index, bar.high));
+                }
+            }
+        }
+    }
+
+    /// The candidate in the opposite direction, starting from the opposite
+    /// extreme and carrying the extreme reached since, up to `index`.
+    fn reverse(&self, data: &[Bar], index: usize) -> Option<Candidate> {
+        let (start, _) = self.opposite?;
+        let kind = match self.kind {
+            SwingKind::High => SwingKind::Low,
+            SwingKind::Low => SwingKind::High,
+        };
+        let mut candidate = Candidate::new(kind, start, &data[start]);
+        for (i, bar) in data.iter().enumerate().take(index + 1).skip(start + 1) {
+            candidate.update(i, bar);
+        }
+        Some(candidate)
+    }
+
+    fn to_swing(self, data: &[Bar], confirmed_at: Option<usize>) -> Swing {
+        Swing {
+            kind: self.kind,
+            index: self.index,
+            timestamp: data[self.index].timestamp.clone(),
+            price: self.price,
+            confirmed_at,
+        }
+    }
+}
+
+impl ZigZag {
+    pub fn new(threshold: ReversalThreshold) -> Self {
+        ZigZag { threshold }
+    }
+
+    pub fn swings(&self, data: &[Bar]) -> Vec<Swing> {
+        let mut swings = Vec::new();
+        let Some(first) = data.first() else {
+            return swings;
+        };
+
+        let atr = match self.threshold {
+            ReversalThreshold::ATR(period, _) => ATR.calculate_average_true_range(data, period),
+            _ => VecDeque::new(),
+        };
+
+        let mut high = Candidate::new(SwingKind::High, 0, first);
+        let mut low = Candidate::new(SwingKind::Low, 0, first);
+        let mut current: Option<Candidate> = None;
+
+        for (i, bar) in data.iter().enumerate().skip(1) {
+            let candidate = match current.as_mut() {
+                Some(candidate) => {
+                    candidate.update(i, bar);
+                    self.is_reversal(candidate, i, &atr).then_some(*candidate)
+                }
+                None => {
+                    high.update(i, bar);
+                    low.update(i, bar);
+                    [high, low]
+                        .into_iter()
+                        .find(|candidate| self.is_reversal(candidate, i, &atr))
+                }
+            };
+
+            if let Some(candidate) = candidate {
+                swings.push(candidate.to_swing(data, Some(i)));
+                current = candidate.reverse(data, i);
+            }
+        }
+
+        if let Some(candidate) = current {
+            swings.push(candidate.to_swing(data, None));
+        }
+
+        swings
+    }
+
+    /// Whether price has reversed from the candidate far enough, as of the
+    /// bar at `index`, to confirm it.
+    fn is_reversal(
+        &self,
+        candidate: &Candidate,
+        index: usize,
+        atr: &VecDeque<Option<f32>>,
+    ) -> bool {
+        let Some((_, opposite)) = candidate.opposite else {
+            return false;
+        };
+        let distance = (candidate.price - opposite).abs();
+
+        match self.threshold {
+            ReversalThreshold::Percent(percent) => distance >= candidate.price * percent / 100.0,
+            ReversalThreshold::ATR(_, multiplier) => atr
+                .get(index)
+                .cloned()
+                .flatten()
+                .is_some_and(|atr| distance >= atr * multiplier),
+            ReversalThreshold::Bars(bars) => index - candidate.index >= bars,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::analysis::swings::{ReversalThreshold, SwingKind, ZigZag};
+    use crate::domain::Bar;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_percent_swings() {
+        let data = sample_bars();
+
+        let swings = ZigZag::new(ReversalThreshold::Percent(8.0)).swings(&data);
+        let summary: Vec<_> = swings
+            .iter()
+            .map(|swing| (swing.kind, swing.index, swing.price, swing.confirmed_at))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SwingKind::Low, 0, 95.0, Some(1)),
+                (SwingKind::High, 6, 111.0, Some(7)),
+                (SwingKind::Low, 9, 96.0, Some(10)),
+                (SwingKind::High, 11, 109.0, None),
+            ]
+        );
+        assert_eq!(swings[1].timestamp, "2023-01-01T00:06:00Z");
+        assert!(!swings[3].is_confirmed());
+    }
+
+    #[test]
+    fn test_bar_count_swings() {
+        let data = sample_bars();
+
+        let swings = ZigZag::new(ReversalThreshold::Bars(2)).swings(&data);
+        let summary: Vec<_> = swings
+            .iter()
+            .map(|swing| (swing.kind, swing.index, swing.confirmed_at))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (SwingKind::Low, 0, Some(2)),
+                (SwingKind::High, 6, Some(8)),
+                (SwingKind::Low, 9, Some(11)),
+                (SwingKind::High, 11, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_reversal_threshold() {
+        assert_eq!("percent:5".parse(), Ok(ReversalThreshold::Percent(5.0)));
+        assert_eq!("atr:14:2.5".parse(), Ok(ReversalThreshold::ATR(14, 2.5)));
+        assert_eq!("bars:3".parse(), Ok(ReversalThreshold::Bars(3)));
+        assert!("bars:0".parse::<ReversalThreshold>().is_err());
+        assert!("fibonacci:5".parse::<ReversalThreshold>().is_err());
+    }
+}
@@ -0,0 +1,368 @@
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::{Direction, PatternPoint, Swing};
+
+/// Relative distance from a Fibonacci target at which a ratio stops scoring,
+/// e.g. 0.2 scores a ratio of 0.5 against a target of 0.618 as 0.05.
+const RATIO_TOLERANCE: f32 = 0.2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WavePattern {
+    Impulse,
+    Zigzag,
+    Flat,
+    Triangle,
+}
+
+/// How closely a wave ratio follows a Fibonacci guideline, scored from 0 to 1.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Guideline {
+    pub description: String,
+    pub ratio: f32,
+    pub score: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WaveCount {
+    pub pattern: WavePattern,
+    pub direction: Direction,
+    /// Labelled pivots, starting from the origin of the pattern labelled `0`.
+    pub points: Vec<PatternPoint>,
+    pub guidelines: Vec<Guideline>,
+    /// Mean score of the guidelines.
+    pub score: f32,
+    /// Whether the last point of the pattern is a confirmed swing.
+    pub complete: bool,
+}
+
+/// Elliott Wave candidate counts
+///
+/// Labels consecutive swings as impulse waves (1-5) and corrective zigzags
+/// (A-B-C), flats (A-B-C) and contracting triangles (A-B-C-D-E). The degree
+/// of the counts follows the reversal threshold used to find the swings.
+///
+/// Candidates with a wave of no length, or that break a rule, are discarded:
+/// - Impulse: wave 2 does not retrace beyond the start of wave 1, wave 3 is
+///   not the shortest of waves 1, 3 and 5, and wave 4 does not overlap the
+///   price territory of wave 1.
+/// - Zigzag: wave B retraces at most 78.6% of wave A and wave C moves beyond
+///   the end of wave A.
+/// - Flat: wave B retraces 90% to 138.2% of wave A and wave C is at least
+///   61.8% of wave A.
+/// - Triangle: waves C, D and E are each shorter than the previous wave in the
+///   same direction.
+///
+/// The remaining candidates are scored against the Fibonacci guidelines of
+/// their pattern and ranked by score, with more recent counts first on ties.
+pub struct ElliottWave;
+
+impl ElliottWave {
+    /// All valid counts in the swings, ranked from best to worst.
+    pub fn counts(&self, swings: &[Swing]) -> Vec<WaveCount> {
+        let mut counts: Vec<WaveCount> = Vec::new();
+
+        for start in 0..swings.len() {
+            if let Some(points) = swings.get(start..start + 6) {
+                counts.extend(self.impulse(points));
+                counts.extend(self.triangle(points));
+            }
+            if let Some(points) = swings.get(start..start + 4) {
+                counts.extend(self.zigzag(points));
+                counts.extend(self.flat(points));
+            }
+        }
+
+        counts.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| last_index(b).cmp(&last_index(a)))
+        });
+        counts
+    }
+
+    /// The `n` best counts in the swings.
+    pub fn top_counts(&self, swings: &[Swing], n: usize) -> Vec<WaveCount> {
+        let mut counts = self.counts(swings);
+        counts.truncate(n);
+        counts
+    }
+
+    fn impulse(&self, points: &[Swing]) -> Option<WaveCount> {
+        let legs = legs(points)?;
+        let (wave1, wave2, wave3, wave4, wave5) = (legs[0], legs[1], legs[2], legs[3], legs[4]);
+        let p = |i: usize| signed(points, i);
+
+        // Rules
+        if p(2) <= p(0) || (wave3 < wave1 && wave3 < wave5) || p(4) <= p(1) {
+            return None;
+        }
+
+        let (retrace2, retrace4) = (wave2 / wave1, wave4 / wave3);
+        let alternates = (retrace2 >= 0.5) != (retrace4 >= 0.5);
+        let guidelines = vec![
+            guideline(
+                "wave 2 retraces 50% to 61.8% of wave 1",
+                retrace2,
+                &[0.5, 0.618],
+            ),
+            guideline(
+                "wave 3 extends 161.8% of wave 1",
+                wave3 / wave1,
+                &[1.618, 2.618],
+            ),
+            guideline(
+                "wave 4 retraces 23.6% to 38.2% of wave 3",
+                retrace4,
+                &[0.236, 0.382],
+            ),
+            guideline("wave 5 equals wave 1", wave5 / wave1, &[0.618, 1.0, 1.618]),
+            Guideline {
+                description: "waves 2 and 4 alternate in depth".to_string(),
+                ratio: retrace4 / retrace2,
+                score: if alternates { 1.0 } else { 0.0 },
+            },
+        ];
+
+        Some(wave_count(
+            WavePattern::Impulse,
+            points,
+            &["0", "1", "2", "3", "4", "5"],
+            guidelines,
+        ))
+    }
+
+    fn zigzag(&self, points: &[Swing]) -> Option<WaveCount> {
+        let legs = legs(points)?;
+        let (wave_a, wave_b, wave_c) = (legs[0], legs[1], legs[2]);
+        let p = |i: usize| signed(points, i);
+
+        if wave_b / wave_a > 0.786 || p(3) <= p(1) {
+            return None;
+        }
+
+        let guidelines = vec![
+            guideline(
+                "wave B retraces 50% to 61.8% of wave A",
+                wave_b / wave_a,
+                &[0.5, 0.618],
+            ),
+            guideline(
+                "wave C equals wave A",
+                wave_c / wave_a,
+                &[0.618, 1.0, 1.618],
+            ),
+        ];
+
+        Some(wave_count(
+            WavePattern::Zigzag,
+            points,
+            &["0", "A", "B", "C"],
+            guidelines,
+        ))
+    }
+
+    fn flat(&self, points: &[Swing]) -> Option<WaveCount> {
+        let legs = legs(points)?;
+        let (wave_a, wave_b, wave_c) = (legs[0], legs[1], legs[2]);
+        let retrace_b = wave_b / wave_a;
+
+        if !(0.9..=1.382).contains(&retrace_b) || wave_c / wave_a < 0.618 {
+            return None;
+        }
+
+        let guidelines = vec![
+            guideline(
+                "wave B retraces 100% to 123.6% of wave A",
+                retrace_b,
+                &[1.0, 1.236],
+            ),
+            guideline("wave C equals wave A", wave_c / wave_a, &[1.0, 1.618]),
+        ];
+
+        Some(wave_count(
+            WavePattern::Flat,
+            points,
+            &["0", "A", "B", "C"],
+            guidelines,
+        ))
+    }
+
+    fn triangle(&self, points: &[Swing]) -> Option<WaveCount> {
+        let legs = legs(points)?;
+        let (wave_a, wave_b, wave_c, wave_d, wave_e) =
+            (legs[0], legs[1], legs[2], legs[3], legs[4]);
+
+        if wave_c >= wave_a || wave_d >= wave_b || wave_e >= wave_c {
+            return None;
+        }
+
+        let guidelines = vec![
+            guideline(
+                "wave C is 61.8% of wave A",
+                wave_c / wave_a,
+                &[0.618, 0.786],
+            ),
+            guideline(
+                "wave D is 61.8% of wave B",
+                wave_d / wave_b,
+                &[0.618, 0.786],
+            ),
+            guideline(
+                "wave E is 61.8% of wave C",
+                wave_e / wave_c,
+                &[0.618, 0.786],
+            ),
+        ];
+
+        Some(wave_count(
+            WavePattern::Triangle,
+            points,
+            &["0", "A", "B", "C", "D", "E"],
+            guidelines,
+        ))
+    }
+}
+
+/// Price of a point, negated for patterns that move down so that the rules
+/// can be written for patterns that move up.
+fn signed(points: &[Swing], i: usize) -> f32 {
+    Direction::from_swing(&points[0]).sign() * points[i].price
+}
+
+/// Price lengths of the waves between consecutive points, or `None` when a
+/// wave has no length to measure the others against.
+fn legs(points: &[Swing]) -> Option<Vec<f32>> {
+    let legs: Vec<f32> = points
+        .windows(2)
+        .map(|pair| (pair[1].price - pair[0].price).abs())
+        .collect();
+    legs.iter().all(|leg| *leg > 0.0).then_some(legs)
+}
+
+/// Scores a ratio against the nearest of the Fibonacci targets.
+fn guideline(description: &str, ratio: f32, targets: &[f32]) -> Guideline {
+    let score = targets
+        .iter()
+        .map(|target| 1.0 - (ratio - target).abs() / (target * RATIO_TOLERANCE))
+        .fold(0.0, f32::max);
+    Guideline {
+        description: description.to_string(),
+        ratio,
+        score,
+    }
+}
+
+fn wave_count(
+    pattern: WavePattern,
+    points: &[Swing],
+    labels: &[&str],
+    guidelines: Vec<Guideline>,
+) -> WaveCount {
+    let score = guidelines
+        .iter()
+        .map(|guideline| guideline.score)
+        .sum::<f32>()
+        / guidelines.len() as f32;
+    WaveCount {
+        pattern,
+        direction: Direction::from_swing(&points[0]),
+        points: points
+            .iter()
+            .zip(labels)
+            .map(|(swing, label)| PatternPoint::from_swing(label, swing))
+            .collect(),
+        guidelines,
+        score,
+        complete: points.last().is_some_and(Swing::is_confirmed),
+    }
+}
+
+fn last_index(count: &WaveCount) -> usize {
+    count.points.last().map_or(0, |point| point.index)
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::analysis::waves::{ElliottWave, WavePattern};
+    use crate::analysis::{Direction, Swing, SwingKind};
+
+    /// Alternating confirmed swings at the given prices, five bars apart,
+    /// starting with a swing of the given kind.
+    fn sample_swings(first: SwingKind, prices: &[f32]) -> Vec<Swing> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, price)| Swing {
+                kind: if (i % 2 == 0) == (first == SwingKind::High) {
+                    SwingKind::High
+                } else {
+                    SwingKind::Low
+                },
+                index: i * 5,
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i * 5),
+                price: *price,
+                confirmed_at: Some(i * 5 + 1),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_impulse() {
+        let data = sample_swings(SwingKind::Low, &[100.0, 110.0, 104.0, 120.2, 114.0, 124.0]);
+
+        let counts = ElliottWave.counts(&data);
+        let impulse = counts
+            .iter()
+            .find(|count| count.pattern == WavePattern::Impulse)
+            .unwrap();
+        assert_eq!(impulse.direction, Direction::Up);
+        assert_eq!(impulse.points[3].label, "3");
+        assert_eq!(impulse.points[3].index, 15);
+        assert_float_absolute_eq!(impulse.guidelines[1].ratio, 1.62, 1e-3);
+        assert!(impulse.score > 0.9);
+        assert!(impulse.complete);
+        assert_eq!(counts[0].pattern, WavePattern::Impulse);
+
+        // Wave 4 overlapping wave 1 breaks the rules
+        let data = sample_swings(SwingKind::Low, &[100.0, 110.0, 104.0, 120.2, 109.0, 124.0]);
+        let counts = ElliottWave.counts(&data);
+        assert!(
+            counts
+                .iter()
+                .all(|count| count.pattern != WavePattern::Impulse)
+        );
+    }
+
+    #[test]
+    fn test_corrections() {
+        let data = sample_swings(SwingKind::High, &[120.0, 110.0, 116.18, 106.18]);
+        let counts = ElliottWave.counts(&data);
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].pattern, WavePattern::Zigzag);
+        assert_eq!(counts[0].direction, Direction::Down);
+        assert_float_absolute_eq!(counts[0].score, 1.0, 1e-3);
+
+        let data = sample_swings(SwingKind::High, &[120.0, 110.0, 120.0, 110.0]);
+        let counts = ElliottWave.counts(&data);
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].pattern, WavePattern::Flat);
+
+        let data = sample_swings(SwingKind::Low, &[100.0, 120.0, 105.0, 117.0, 108.0, 114.0]);
+        let counts = ElliottWave.top_counts(&data, 1);
+        assert_eq!(counts.len(), 1);
+        assert_eq!(counts[0].pattern, WavePattern::Triangle);
+        assert_eq!(counts[0].points[5].label, "E");
+    }
+
+    #[test]
+    fn test_zero_length_waves() {
+        // Waves of no length have no ratios to score
+        for prices in [[120.0, 110.0, 110.0, 100.0], [120.0, 120.0, 120.0, 110.0]] {
+            let data = sample_swings(SwingKind::High, &prices);
+            assert!(ElliottWave.counts(&data).is_empty());
+        }
+    }
+}
@@ -4,7 +4,13 @@ use std::net::TcpListener;
 use tracing_actix_web::TracingLogger;
 
 use crate::datafeed::HistoricalData;
-use crate::routes::{get_datafeed_config, get_historical_data, symbols_handler};
+use crate::paper::PaperTrading;
+use crate::routes::{
+    get_backtest_report, get_datafeed_config, get_fibonacci_levels, get_historical_data,
+    get_monte_carlo, get_optimization, get_paper_account, get_paper_orders, get_paper_positions,
+    get_paper_sessions, get_portfolio_backtest, get_support_resistance, get_walk_forward,
+    get_wave_counts, start_paper_session, stop_paper_session, symbols_handler,
+};
 use crate::storage::Database;
 use crate::{configuration::Settings, routes::heartbeat};
 
@@ -35,6 +41,7 @@ impl Application {
 
 pub async fn run(listener: TcpListener, database: Database) -> Result<Server, anyhow::Error> {
     let historical_data = Data::new(HistoricalData::new(database.clone()));
+    let paper_trading = Data::new(PaperTrading::new(database.clone()));
     let server = HttpServer::new(move || {
         App::new()
             .wrap(TracingLogger::default())
@@ -42,12 +49,27 @@ pub async fn run(listener: TcpListener, database: Database) -> Result<Server, an
             .service(
                 web::scope("/api/v1")
                     .route("/", web::get().to(heartbeat))
+                    .route("/analysis/fibonacci", web::get().to(get_fibonacci_levels))
+                    .route("/analysis/levels", web::get().to(get_support_resistance))
+                    .route("/analysis/waves", web::get().to(get_wave_counts))
+                    .route("/backtest/monte-carlo", web::get().to(get_monte_carlo))
+                    .route("/backtest/optimize", web::get().to(get_optimization))
+                    .route("/backtest/portfolio", web::get().to(get_portfolio_backtest))
+                    .route("/backtest/report", web::get().to(get_backtest_report))
+                    .route("/backtest/walk-forward", web::get().to(get_walk_forward))
                     .route("/health", web::get().to(heartbeat))
                     .route("/data/config", web::get().to(get_datafeed_config))
                     .route("/data/historical", web::get().to(get_historical_data))
+                    .route("/paper/account", web::get().to(get_paper_account))
+                    .route("/paper/orders", web::get().to(get_paper_orders))
+                    .route("/paper/positions", web::get().to(get_paper_positions))
+                    .route("/paper/sessions", web::get().to(get_paper_sessions))
+                    .route("/paper/sessions", web::post().to(start_paper_session))
+                    .route("/paper/sessions/stop", web::post().to(stop_paper_session))
                     .route("/symbols", web::get().to(symbols_handler))
             )
             .app_data(historical_data.clone())
+            .app_data(paper_trading.clone())
     })
     .listen(listener)?
     .run();
@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{Contract, Fill, Order, OrderType, Position, Side, Trade};
+
+/// Cash, positions and orders of a simulated trading account. Amounts are in
+/// the account currency; symbols without a contract are valued one to one.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Account {
+    pub cash: f64,
+    pub contracts: BTreeMap<String, Contract>,
+    pub positions: BTreeMap<String, Position>,
+    /// Orders waiting to be filled, in the order they were submitted.
+    pub orders: Vec<Order>,
+    pub fills: Vec<Fill>,
+    pub trades: Vec<Trade>,
+    /// Net cash received from the fills of each symbol.
+    flows: BTreeMap<String, f64>,
+    next_order_id: u64,
+    /// Number, commission and profit of the fills and trades dropped by
+    /// `trim`.
+    #[serde(default)]
+    dropped_fills: usize,
+    #[serde(default)]
+    dropped_commission: f64,
+    #[serde(default)]
+    dropped_profit: f64,
+}
+
+impl Account {
+    pub fn new(cash: f64) -> Self {
+        Account {
+            cash,
+            contracts: BTreeMap::new(),
+            positions: BTreeMap::new(),
+            orders: Vec::new(),
+            fills: Vec::new(),
+            trades: Vec::new(),
+            flows: BTreeMap::new(),
+            next_order_id: 1,
+            dropped_fills: 0,
+            dropped_commission: 0.0,
+            dropped_profit: 0.0,
+        }
+    }
+
+    /// Queues an order, returning its id.
+    pub fn submit(
+        &mut self,
+        symbol: &str,
+        side: Side,
+        quantity: f32,
+        order_type: OrderType,
+        index: usize,
+    ) -> u64 {
+        let id = self.next_order_id;
+        self.next_order_id += 1;
+        self.orders.push(Order {
+            id,
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            order_type,
+            submitted_at: index,
+        });
+        id
+    }
+
+    /// Cancels an open order, returning whether it was open.
+    pub fn cancel(&mut self, id: u64) -> bool {
+        let count = self.orders.len();
+        self.orders.retain(|order| order.id != id);
+        self.orders.len() < count
+    }
+
+    /// Signed quantity held in the symbol.
+    pub fn position(&self, symbol: &str) -> f32 {
+        self.positions
+            .get(symbol)
+            .map_or(0.0, |position| position.quantity)
+    }
+
+    pub fn contract(&self, symbol: &str) -> Contract {
+        self.contracts.get(symbol).cloned().unwrap_or_default()
+    }
+
+    /// Fills an order at the price, updating cash and the position and
+    /// recording the fill and any trade it closes. The commission is in the
+    /// currency of the symbol and is recorded in the account currency.
+    pub fn fill(
+        &mut self,
+        order: &Order,
+        price: f32,
+        commission: f64,
+        index: usize,
+        timestamp: &str,
+    ) {
+        let contract = self.contract(&order.symbol);
+        let commission = commission * contract.exchange_rate;
+        let fill = Fill {
+            order_id: order.id,
+            symbol: order.symbol.clone(),
+            side: order.side,
+            quantity: order.quantity,
+            price,
+            commission,
+            index,
+            timestamp: timestamp.to_string(),
+        };
+        let flow = -((order.side.sign() * order.quantity * price) as f64 * contract.point_value())
+            - commission;
+        self.cash += flow;
+        *self.flows.entry(order.symbol.clone()).or_default() += flow;
+        let position = self.positions.entry(order.symbol.clone()).or_default();
+        if let Some(trade) = position.apply(&fill, contract.point_value()) {
+            self.trades.push(trade);
+        }
+        if position.is_flat() {
+            self.positions.remove(&order.symbol);
+        }
+        self.fills.push(fill);
+    }
+
+    /// Number of fills so far, including the dropped ones.
+    pub fn fill_count(&self) -> usize {
+        self.dropped_fills + self.fills.len()
+    }
+
+    /// Commission of all the fills so far, including the dropped ones.
+    pub fn commission(&self) -> f64 {
+        self.dropped_commission + self.fills.iter().map(|fill| fill.commission).sum::<f64>()
+    }
+
+    /// Profit of all the trades so far, including the dropped ones.
+    pub fn realized_profit(&self) -> f64 {
+        self.dropped_profit + self.trades.iter().map(|trade| trade.pnl).sum::<f64>()
+    }
+
+    /// Drops the oldest fills and trades once there are more than `max` of
+    /// them, keeping half as many, so that an account trading for a long
+    /// time stays bounded. Their commission and profit are still counted.
+    pub fn trim(&mut self, max: usize) {
+        if self.fills.len() > max {
+            let dropped = self.fills.len() - max / 2;
+            for fill in self.fills.drain(..dropped) {
+                self.dropped_commission += fill.commission;
+            }
+            self.dropped_fills += dropped;
+        }
+        if self.trades.len() > max {
+            let dropped = self.trades.len() - max / 2;
+            for trade in self.trades.drain(..dropped) {
+                self.dropped_profit += trade.pnl;
+            }
+        }
+    }
+
+    /// Cash plus the value of the positions at the given prices. Positions
+    /// without a price are valued at their average price.
+    pub fn equity(&self, prices: &BTreeMap<String, f32>) -> f64 {
+        self.cash
+            + self
+                .positions
+                .keys()
+                .map(|symbol| self.value(symbol, prices))
+                .sum::<f64>()
+    }
+
+    /// Profit and loss of a symbol so far, net of commission, with its
+    /// position valued at the given prices.
+    pub fn profit(&self, symbol: &str, prices: &BTreeMap<String, f32>) -> f64 {
+        self.flows.get(symbol).copied().unwrap_or_default() + self.value(symbol, prices)
+    }
+
+    fn value(&self, symbol: &str, prices: &BTreeMap<String, f32>) -> f64 {
+        self.positions.get(symbol).map_or(0.0, |position| {
+            let price = prices
+                .get(symbol)
+                .copied()
+                .unwrap_or(position.average_price);
+            (position.quantity * price) as f64 * self.contract(symbol).point_value()
+        })
+    }
+}
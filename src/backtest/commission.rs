@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::SymbolType;
+
+/// Commission and fees charged on a fill, in the currency of the symbol.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Commission {
+    #[serde(default)]
+    pub per_contract: f64,
+    /// Percentage of the notional value of the fill.
+    #[serde(default)]
+    pub percent: f64,
+    #[serde(default)]
+    pub minimum: f64,
+}
+
+impl Commission {
+    pub fn calculate(&self, quantity: f32, price: f32) -> f64 {
+        let quantity = quantity as f64;
+        let fee = self.per_contract * quantity + self.percent / 100.0 * quantity * price as f64;
+        fee.max(self.minimum)
+    }
+}
+
+/// A commission applying to the symbols of an exchange, of a symbol type, or
+/// of a symbol type on an exchange.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CommissionRule {
+    #[serde(default)]
+    pub exchange: Option<String>,
+    #[serde(default)]
+    pub symbol_type: Option<SymbolType>,
+    pub commission: Commission,
+}
+
+impl CommissionRule {
+    /// How specifically the rule matches a symbol, if it does. Rules for an
+    /// exchange are more specific than rules for a symbol type.
+    fn specificity(&self, exchange: &str, symbol_type: &SymbolType) -> Option<u8> {
+        let exchange = match &self.exchange {
+            Some(rule) if rule == exchange => 2,
+            Some(_) => return None,
+            None => 0,
+        };
+        let symbol_type = match &self.symbol_type {
+            Some(rule) if rule == symbol_type => 1,
+            Some(_) => return None,
+            None => 0,
+        };
+        Some(exchange + symbol_type)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct CommissionSchedule {
+    #[serde(default)]
+    pub default: Commission,
+    #[serde(default)]
+    pub rules: Vec<CommissionRule>,
+}
+
+impl CommissionSchedule {
+    /// The commission of the most specific rule matching the symbol, or the
+    /// default commission when none does.
+    pub fn commission(&self, exchange: &str, symbol_type: &SymbolType) -> Commission {
+        self.rules
+            .iter()
+            .filter_map(|rule| {
+                rule.specificity(exchange, symbol_type)
+                    .map(|specificity| (specificity, rule))
+            })
+            .max_by_key(|(specificity, _)| *specificity)
+            .map_or_else(|| self.default.clone(), |(_, rule)| rule.commission.clone())
+    }
+}
+
+/// Reads the named commission schedules from `data/commissions.json`.
+pub fn fetch_commission_schedules() -> Result<BTreeMap<String, CommissionSchedule>, String> {
+    let json = fs::read_to_string("data/commissions.json")
+        .map_err(|err| format!("Failed to read file: {}", err))?;
+    serde_json::from_str(&json)
+        .map_err(|err| format!("Failed to parse JSON from commissions.json: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::backtest::fetch_commission_schedules;
+    use crate::domain::SymbolType;
+
+    #[test]
+    fn test_commission_schedule() {
+        let schedules = fetch_commission_schedules().unwrap();
+        let standard = &schedules["standard"];
+
+        let cme = standard.commission("XCME", &SymbolType::FUTURES);
+        assert_float_absolute_eq!(cme.calculate(2.0, 5000.0), 4.5, 1e-9);
+        let futures = standard.commission("XEUR", &SymbolType::FUTURES);
+        assert_float_absolute_eq!(futures.calculate(2.0, 5000.0), 5.0, 1e-9);
+        let stock = standard.commission("XHKG", &SymbolType::STOCK);
+        assert_float_absolute_eq!(stock.calculate(100.0, 50.0), 3.0, 1e-9);
+        assert_float_absolute_eq!(stock.calculate(1000.0, 50.0), 15.0, 1e-9);
+        let default = standard.commission("XNAS", &SymbolType::STOCK);
+        assert_float_absolute_eq!(default.calculate(100.0, 200.0), 20.0, 1e-9);
+
+        let none = schedules["none"].commission("XCME", &SymbolType::FUTURES);
+        assert_eq!(none.calculate(2.0, 5000.0), 0.0);
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::BTreeMap;
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::SymbolInfo;
+
+/// How the prices of a symbol translate into the account currency.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Contract {
+    /// Value of a price move of one unit, in the currency of the symbol.
+    pub multiplier: f32,
+    pub currency: Option<String>,
+    /// Value of one unit of the currency of the symbol in the account
+    /// currency.
+    pub exchange_rate: f64,
+}
+
+impl Default for Contract {
+    fn default() -> Self {
+        Contract {
+            multiplier: 1.0,
+            currency: None,
+            exchange_rate: 1.0,
+        }
+    }
+}
+
+impl Contract {
+    /// Value of a price move of one unit in the account currency.
+    pub fn point_value(&self) -> f64 {
+        self.multiplier as f64 * self.exchange_rate
+    }
+}
+
+/// Contract multipliers by `<exchange>:<symbol>` and exchange rates into the
+/// base currency, as read from `data/contracts.json`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Contracts {
+    pub base_currency: String,
+    #[serde(default)]
+    pub exchange_rates: BTreeMap<String, f64>,
+    #[serde(default)]
+    pub multipliers: BTreeMap<String, f32>,
+}
+
+impl Contracts {
+    /// The contract of a symbol. Symbols without a multiplier have a
+    /// multiplier of 1, and symbols without a currency are quoted in the
+    /// base currency.
+    pub fn contract(&self, symbol: &SymbolInfo) -> Result<Contract, String> {
+        let multiplier = self
+            .multipliers
+            .get(&format!("{}:{}", symbol.exchange, symbol.name))
+            .copied()
+            .unwrap_or(1.0);
+        let exchange_rate = match symbol.currency_code.as_deref() {
+            None => 1.0,
+            Some(currency) if currency == self.base_currency => 1.0,
+            Some(currency) => *self.exchange_rates.get(currency).ok_or_else(|| {
+                format!(
+                    "no exchange rate from {} to {} for {}",
+                    currency, self.base_currency, symbol.name
+                )
+            })?,
+        };
+        Ok(Contract {
+            multiplier,
+            currency: symbol.currency_code.clone(),
+            exchange_rate,
+        })
+    }
+}
+
+/// Reads the contract specifications from `data/contracts.json`.
+pub fn fetch_contracts() -> Result<Contracts, String> {
+    let json = fs::read_to_string("data/contracts.json")
+        .map_err(|err| format!("Failed to read file: {}", err))?;
+    serde_json::from_str(&json)
+        .map_err(|err| format!("Failed to parse JSON from contracts.json: {}", err))
+}
@@ -0,0 +1,769 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{
+    Account, Commission, Context, Contract, Fill, FillModel, Order, PortfolioResult, Position,
+    Rejection, RiskManager, Slippage, Strategy, Trade,
+};
+use crate::domain::{Series, Tick};
+use crate::indicators::ATR;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub index: usize,
+    pub timestamp: String,
+    pub equity: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BacktestResult {
+    pub initial_capital: f64,
+    pub fills: Vec<Fill>,
+    pub trades: Vec<Trade>,
+    /// Equity marked at the close of every bar, or at every tick.
+    pub equity: Vec<EquityPoint>,
+    /// Positions still open at the end of the replay.
+    pub positions: BTreeMap<String, Position>,
+    /// Orders the risk manager rejected.
+    #[serde(default)]
+    pub rejections: Vec<Rejection>,
+}
+
+impl BacktestResult {
+    pub fn final_equity(&self) -> f64 {
+        self.equity
+            .last()
+            .map_or(self.initial_capital, |point| point.equity)
+    }
+}
+
+/// Event-driven backtest
+///
+/// Replays bars or ticks through a strategy. On every event, the orders
+/// submitted on earlier events are filled first, then the equity is marked
+/// and the strategy is called. Orders that do not fill stay open until they
+/// fill or are cancelled.
+///
+/// Fills follow the fill model, market and stop fills are slipped, and every
+/// fill is charged commission on its notional value. Symbols are valued by
+/// their contracts, one to one unless given. A risk manager, if given, sizes
+/// and vets the orders of the strategy, starting afresh on every run.
+pub struct Backtest {
+    initial_capital: f64,
+    fill_model: FillModel,
+    slippage: Slippage,
+    commission: Commission,
+    commissions: BTreeMap<String, Commission>,
+    contracts: BTreeMap<String, Contract>,
+    risk: Option<RiskManager>,
+}
+
+impl Backtest {
+    pub fn new(initial_capital: f64) -> Self {
+        Backtest {
+            initial_capital,
+            fill_model: FillModel::NextBarOpen,
+            slippage: Slippage::None,
+            commission: Commission::default(),
+            commissions: BTreeMap::new(),
+            contracts: BTreeMap::new(),
+            risk: None,
+        }
+    }
+
+    pub fn with_fill_model(mut self, fill_model: FillModel) -> Self {
+        self.fill_model = fill_model;
+        self
+    }
+
+    pub fn with_slippage(mut self, slippage: Slippage) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    pub fn with_commission(mut self, commission: Commission) -> Self {
+        self.commission = commission;
+        self
+    }
+
+    /// Charges a symbol a commission other than the default one.
+    pub fn with_symbol_commission(mut self, symbol: &str, commission: Commission) -> Self {
+        self.commissions.insert(symbol.to_string(), commission);
+        self
+    }
+
+    pub fn with_contract(mut self, symbol: &str, contract: Contract) -> Self {
+        self.contracts.insert(symbol.to_string(), contract);
+        self
+    }
+
+    /// The contract of a symbol, one to one unless given.
+    pub fn contract(&self, symbol: &str) -> Contract {
+        self.contracts.get(symbol).cloned().unwrap_or_default()
+    }
+
+    pub fn with_risk(mut self, risk: RiskManager) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Whether orders fill against the ticks of the bars, when given.
+    pub fn replays_ticks(&self) -> bool {
+        self.fill_model != FillModel::NextBarOpen
+    }
+
+    pub fn run<S: Strategy + ?Sized>(&self, strategy: &mut S, series: &Series) -> BacktestResult {
+        self.run_with_ticks(strategy, series, &[])
+    }
+
+    /// Replays the bars, filling orders against the ticks of each bar when
+    /// the fill model replays ticks. The ticks must be those the bars were
+    /// generated from, in ascending order; a bar includes the ticks up to
+    /// and including its timestamp.
+    pub fn run_with_ticks<S: Strategy + ?Sized>(
+        &self,
+        strategy: &mut S,
+        series: &Series,
+        ticks: &[Tick],
+    ) -> BacktestResult {
+        self.replay(strategy, series, 0..series.len(), ticks)
+    }
+
+    /// Replays the bars in the range only. The strategy still starts with
+    /// every bar up to the end of the range, so that indicators are warmed
+    /// up by the bars before it. Indices remain those of the series.
+    pub fn run_range<S: Strategy + ?Sized>(
+        &self,
+        strategy: &mut S,
+        series: &Series,
+        range: Range<usize>,
+    ) -> BacktestResult {
+        self.replay(strategy, series, range, &[])
+    }
+
+    fn replay<S: Strategy + ?Sized>(
+        &self,
+        strategy: &mut S,
+        series: &Series,
+        range: Range<usize>,
+        ticks: &[Tick],
+    ) -> BacktestResult {
+        let range = range.start.min(series.len())..range.end.min(series.len());
+        let mut account = self.account();
+        let mut risk = self.risk.clone();
+        let mut prices = BTreeMap::new();
+        let mut queues = BTreeMap::new();
+        let mut equity = Vec::with_capacity(range.len());
+        let volatility = match self.slippage {
+            Slippage::Volatility(period, _) => {
+                ATR.calculate_average_true_range(&series.data[..range.end], period)
+            }
+            _ => VecDeque::new(),
+        };
+        let mut remaining = match range.start.checked_sub(1) {
+            Some(previous) => {
+                let before = &series.data[previous].timestamp;
+                &ticks[ticks.partition_point(|tick| tick.timestamp <= *before)..]
+            }
+            None => ticks,
+        };
+        strategy.on_start(&series.data[..range.end]);
+
+        for (index, bar) in series
+            .data
+            .iter()
+            .enumerate()
+            .take(range.end)
+            .skip(range.start)
+        {
+            let count = remaining
+                .iter()
+                .take_while(|tick| tick.timestamp <= bar.timestamp)
+                .count();
+            let (bar_ticks, rest) = remaining.split_at(count);
+            remaining = rest;
+
+            // The volatility known before the bar opens
+            let known = index
+                .checked_sub(1)
+                .and_then(|i| volatility.get(i).copied().flatten());
+            for order in std::mem::take(&mut account.orders) {
+                let queued = queues.entry(order.id).or_insert(0.0);
+                match self.fill_model.fill_on_bar(&order, bar, bar_ticks, queued) {
+                    Some(price) => {
+                        queues.remove(&order.id);
+                        self.execute(&mut account, &order, price, known, index, &bar.timestamp)
+                    }
+                    None => account.orders.push(order),
+                }
+            }
+
+            prices.insert(series.symbol.clone(), bar.close);
+            equity.push(EquityPoint {
+                index,
+                timestamp: bar.timestamp.clone(),
+                equity: account.equity(&prices),
+            });
+
+            if let Some(risk) = risk.as_mut() {
+                risk.on_bar(&account, &series.symbol, bar);
+            }
+            let mut context =
+                Context::new(index, &series.symbol, &mut account).with_risk(risk.as_mut());
+            strategy.on_bar(&mut context, bar);
+        }
+
+        self.result(account, equity, risk)
+    }
+
+    /// Replays the bars of several symbols as one stream in time order,
+    /// trading them from one account with one strategy per symbol, made by
+    /// `strategy` for each series in turn. Bars with the same timestamp are
+    /// replayed in the order of the series, and the equity is marked once all
+    /// of them are.
+    ///
+    /// Indices of fills, trades and contexts are those of the bars in their
+    /// series, while indices of equity points count the distinct timestamps.
+    pub fn run_portfolio<S, F>(&self, series: &[Series], strategy: F) -> PortfolioResult
+    where
+        S: Strategy,
+        F: FnMut(&Series) -> S,
+    {
+        self.run_portfolio_with_ticks(series, &[], strategy)
+    }
+
+    /// Replays the bars of several symbols like `run_portfolio`, filling the
+    /// orders of each symbol against the ticks of its bars when the fill
+    /// model replays ticks. `ticks` holds the ticks of each series, in the
+    /// order of the series, as `run_with_ticks` takes them.
+    pub fn run_portfolio_with_ticks<S, F>(
+        &self,
+        series: &[Series],
+        ticks: &[Vec<Tick>],
+        mut strategy: F,
+    ) -> PortfolioResult
+    where
+        S: Strategy,
+        F: FnMut(&Series) -> S,
+    {
+        let mut account = self.account();
+        let mut risk = self.risk.clone();
+        let mut prices = BTreeMap::new();
+        let mut queues = BTreeMap::new();
+        let mut equity = Vec::new();
+        let mut profits = vec![Vec::new(); series.len()];
+        let mut strategies: Vec<S> = series
+            .iter()
+            .map(|series| {
+                let mut strategy = strategy(series);
+                strategy.on_start(&series.data);
+                strategy
+            })
+            .collect();
+        let volatility: Vec<VecDeque<Option<f32>>> = series
+            .iter()
+            .map(|series| match self.slippage {
+                Slippage::Volatility(period, _) => {
+                    ATR.calculate_average_true_range(&series.data, period)
+                }
+                _ => VecDeque::new(),
+            })
+            .collect();
+        let mut remaining: Vec<&[Tick]> = (0..series.len())
+            .map(|s| ticks.get(s).map_or(&[][..], Vec::as_slice))
+            .collect();
+
+        // Sorting is stable, so bars with the same timestamp stay in series order
+        let mut events: Vec<(usize, usize)> = series
+            .iter()
+            .enumerate()
+            .flat_map(|(s, series)| (0..series.len()).map(move |index| (s, index)))
+            .collect();
+        events.sort_by(|a, b| {
+            let timestamp = |(s, index): &(usize, usize)| &series[*s].data[*index].timestamp;
+            timestamp(a).cmp(timestamp(b))
+        });
+
+        for (n, &(s, index)) in events.iter().enumerate() {
+            let symbol = &series[s].symbol;
+            let bar = &series[s].data[index];
+            let count = remaining[s]
+                .iter()
+                .take_while(|tick| tick.timestamp <= bar.timestamp)
+                .count();
+            let (bar_ticks, rest) = remaining[s].split_at(count);
+            remaining[s] = rest;
+            let known = index
+                .checked_sub(1)
+                .and_then(|i| volatility[s].get(i).copied().flatten());
+            for order in std::mem::take(&mut account.orders) {
+                if order.symbol != *symbol {
+                    account.orders.push(order);
+                    continue;
+                }
+                let queued = queues.entry(order.id).or_insert(0.0);
+                match self.fill_model.fill_on_bar(&order, bar, bar_ticks, queued) {
+                    Some(price) => {
+                        queues.remove(&order.id);
+                        self.execute(&mut account, &order, price, known, index, &bar.timestamp)
+                    }
+                    None => account.orders.push(order),
+                }
+            }
+            prices.insert(symbol.clone(), bar.close);
+
+            if let Some(risk) = risk.as_mut() {
+                risk.on_bar(&account, symbol, bar);
+            }
+            let mut context = Context::new(index, symbol, &mut account).with_risk(risk.as_mut());
+            strategies[s].on_bar(&mut context, bar);
+
+            let is_last = events
+                .get(n + 1)
+                .is_none_or(|&(s, index)| series[s].data[index].timestamp != bar.timestamp);
+            if is_last {
+                equity.push(EquityPoint {
+                    index: equity.len(),
+                    timestamp: bar.timestamp.clone(),
+                    equity: account.equity(&prices),
+                });
+                for (profits, series) in profits.iter_mut().zip(series) {
+                    profits.push(account.profit(&series.symbol, &prices));
+                }
+            }
+        }
+
+        let symbols = series
+            .iter()
+            .map(|series| (series.symbol.clone(), account.contract(&series.symbol)))
+            .collect();
+        PortfolioResult::new(self.result(account, equity, risk), symbols, &profits)
+    }
+
+    /// Replays the ticks. Volatility slippage uses the mean absolute change
+    /// between consecutive ticks of a symbol.
+    pub fn run_ticks<S: Strategy + ?Sized>(
+        &self,
+        strategy: &mut S,
+        ticks: &[Tick],
+    ) -> BacktestResult {
+        let mut account = self.account();
+        let mut risk = self.risk.clone();
+        let mut prices: BTreeMap<String, f32> = BTreeMap::new();
+        let mut queues = BTreeMap::new();
+        let mut changes: BTreeMap<String, VecDeque<f32>> = BTreeMap::new();
+        let mut equity = Vec::with_capacity(ticks.len());
+        let period = match self.slippage {
+            Slippage::Volatility(period, _) => period,
+            _ => 0,
+        };
+
+        for (index, tick) in ticks.iter().enumerate() {
+            let known = changes
+                .get(&tick.symbol)
+                .filter(|changes| period > 0 && changes.len() == period)
+                .map(|changes| changes.iter().sum::<f32>() / period as f32);
+            for order in std::mem::take(&mut account.orders) {
+                let queued = queues.entry(order.id).or_insert(0.0);
+                let price = (order.symbol == tick.symbol)
+                    .then(|| self.fill_model.fill_on_tick(&order, tick, queued))
+                    .flatten();
+                match price {
+                    Some(price) => {
+                        queues.remove(&order.id);
+                        self.execute(&mut account, &order, price, known, index, &tick.timestamp)
+                    }
+                    None => account.orders.push(order),
+                }
+            }
+
+            if let Some(previous) = prices.insert(tick.symbol.clone(), tick.price) {
+                let changes = changes.entry(tick.symbol.clone()).or_default();
+                changes.push_back((tick.price - previous).abs());
+                if changes.len() > period {
+                    changes.pop_front();
+                }
+            }
+            equity.push(EquityPoint {
+                index,
+                timestamp: tick.timestamp.clone(),
+                equity: account.equity(&prices),
+            });
+
+            if let Some(risk) = risk.as_mut() {
+                risk.on_tick(&account, tick);
+            }
+            let mut context =
+                Context::new(index, &tick.symbol, &mut account).with_risk(risk.as_mut());
+            strategy.on_tick(&mut context, tick);
+        }
+
+        self.result(account, equity, risk)
+    }
+
+    /// Slips the price of an order and fills it, charging commission.
+    pub(crate) fn execute(
+        &self,
+        account: &mut Account,
+        order: &Order,
+        price: f32,
+        volatility: Option<f32>,
+        index: usize,
+        timestamp: &str,
+    ) {
+        let price = self.slippage.apply(order, price, volatility);
+        let multiplier = account.contract(&order.symbol).multiplier;
+        let commission = self
+            .commissions
+            .get(&order.symbol)
+            .unwrap_or(&self.commission)
+            .calculate(order.quantity, price * multiplier);
+        account.fill(order, price, commission, index, timestamp);
+    }
+
+    pub(crate) fn fill_model(&self) -> FillModel {
+        self.fill_model
+    }
+
+    /// Period over which volatility slippage measures the volatility.
+    pub(crate) fn volatility_period(&self) -> Option<usize> {
+        match self.slippage {
+            Slippage::Volatility(period, _) => Some(period),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn risk(&self) -> Option<RiskManager> {
+        self.risk.clone()
+    }
+
+    /// A fresh account with the initial capital and the contracts.
+    pub(crate) fn account(&self) -> Account {
+        let mut account = Account::new(self.initial_capital);
+        account.contracts = self.contracts.clone();
+        account
+    }
+
+    fn result(
+        &self,
+        account: Account,
+        equity: Vec<EquityPoint>,
+        risk: Option<RiskManager>,
+    ) -> BacktestResult {
+        BacktestResult {
+            initial_capital: self.initial_capital,
+            fills: account.fills,
+            trades: account.trades,
+            equity,
+            positions: account.positions,
+            rejections: risk.map(|risk| risk.rejections).unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::backtest::{
+        Backtest, Commission, Context, Contract, FillModel, IndicatorCross, OrderType, RiskManager,
+        Side, Slippage, Strategy,
+    };
+    use crate::domain::{Bar, Indicators, Interval, Resolution, Series, Tick};
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    /// Submits the scripted orders on the given bars or ticks.
+    struct Script(Vec<(usize, Side, OrderType)>);
+
+    impl Script {
+        fn submit(&self, context: &mut Context) {
+            for (index, side, order_type) in &self.0 {
+                if *index == context.index() {
+                    context.submit(*side, 1.0, *order_type);
+                }
+            }
+        }
+    }
+
+    impl Strategy for Script {
+        fn on_bar(&mut self, context: &mut Context, _bar: &Bar) {
+            self.submit(context);
+        }
+
+        fn on_tick(&mut self, context: &mut Context, _tick: &Tick) {
+            self.submit(context);
+        }
+    }
+
+    #[test]
+    fn test_run_bars() {
+        let series = Series::new(
+            "ES".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sample_bars(),
+        );
+        let mut strategy = Script(vec![
+            (0, Side::Buy, OrderType::Market),
+            (2, Side::Sell, OrderType::Limit(108.0)),
+            (5, Side::Sell, OrderType::Stop(104.0)),
+            (8, Side::Buy, OrderType::Market),
+            (11, Side::Buy, OrderType::Market),
+        ]);
+
+        let result = Backtest::new(10000.0).run(&mut strategy, &series);
+        let prices: Vec<f32> = result.fills.iter().map(|fill| fill.price).collect();
+        assert_eq!(prices, [102.0, 108.0, 104.0, 99.0]);
+        assert_eq!(result.trades.len(), 2);
+        assert_eq!(result.trades[0].pnl, 6.0);
+        assert_eq!(result.trades[1].side, Side::Sell);
+        assert_eq!(result.trades[1].pnl, 5.0);
+        assert_float_absolute_eq!(result.equity[2].equity, 10001.0, 1e-6);
+        assert_float_absolute_eq!(result.final_equity(), 10011.0, 1e-6);
+        assert!(result.positions.is_empty());
+    }
+
+    #[test]
+    fn test_run_with_costs() {
+        let series = Series::new(
+            "ES".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sample_bars(),
+        );
+        let mut strategy = Script(vec![
+            (0, Side::Buy, OrderType::Market),
+            (2, Side::Sell, OrderType::Limit(108.0)),
+            (5, Side::Sell, OrderType::Stop(104.0)),
+            (8, Side::Buy, OrderType::Market),
+        ]);
+        let commission = Commission {
+            per_contract: 1.0,
+            ..Commission::default()
+        };
+
+        let result = Backtest::new(10000.0)
+            .with_slippage(Slippage::Fixed(0.5))
+            .with_commission(commission)
+            .run(&mut strategy, &series);
+        let prices: Vec<f32> = result.fills.iter().map(|fill| fill.price).collect();
+        assert_eq!(prices, [102.5, 108.0, 103.5, 99.5]);
+        assert_eq!(result.fills[0].commission, 1.0);
+        assert_float_absolute_eq!(result.trades[0].pnl, 3.5, 1e-6);
+        assert_float_absolute_eq!(result.trades[1].pnl, 2.0, 1e-6);
+        assert_float_absolute_eq!(result.final_equity(), 10005.5, 1e-6);
+    }
+
+    #[test]
+    fn test_run_ticks() {
+        let ticks: Vec<Tick> = [100.0, 101.0, 99.0, 102.0, 98.0]
+            .iter()
+            .enumerate()
+            .map(|(i, price)| Tick {
+                timestamp: format!("2023-01-01T00:00:{:02}Z", i),
+                symbol: "ES".to_string(),
+                price: *price,
+                volume: 1.0,
+            })
+            .collect();
+        let mut strategy = Script(vec![
+            (0, Side::Buy, OrderType::Limit(99.5)),
+            (3, Side::Sell, OrderType::Market),
+        ]);
+
+        let result = Backtest::new(10000.0).run_ticks(&mut strategy, &ticks);
+        assert_eq!(result.fills[0].index, 2);
+        assert_eq!(result.trades[0].exit_price, 98.0);
+        assert_float_absolute_eq!(result.equity[3].equity, 10003.0, 1e-6);
+        assert_float_absolute_eq!(result.final_equity(), 9999.0, 1e-6);
+    }
+
+    #[test]
+    fn test_run_portfolio() {
+        let es = Series::new(
+            "ES".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sample_bars(),
+        );
+        // Trades at the same times as ES, without the first two bars
+        let hsi = Series::new(
+            "HSI".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sample_bars().split_off(2),
+        );
+        let backtest = Backtest::new(10000.0)
+            .with_contract(
+                "ES",
+                Contract {
+                    multiplier: 50.0,
+                    ..Contract::default()
+                },
+            )
+            .with_contract(
+                "HSI",
+                Contract {
+                    multiplier: 10.0,
+                    currency: Some("HKD".to_string()),
+                    exchange_rate: 0.5,
+                },
+            );
+
+        let result = backtest.run_portfolio(&[es, hsi], |series| match series.symbol.as_str() {
+            "ES" => Script(vec![
+                (0, Side::Buy, OrderType::Market),
+                (2, Side::Sell, OrderType::Market),
+            ]),
+            _ => Script(vec![
+                (0, Side::Buy, OrderType::Market),
+                (6, Side::Sell, OrderType::Market),
+            ]),
+        });
+
+        // ES: bought at 102, sold at 103; HSI: bought at 103, sold at 99
+        assert_eq!(result.result.trades.len(), 2);
+        assert_float_absolute_eq!(result.result.trades[0].pnl, 50.0, 1e-6);
+        assert_float_absolute_eq!(result.result.trades[1].pnl, -20.0, 1e-6);
+        assert_eq!(result.result.equity.len(), 12);
+        assert_eq!(result.result.equity[11].index, 11);
+        assert_float_absolute_eq!(result.result.final_equity(), 10030.0, 1e-6);
+        assert_float_absolute_eq!(result.symbols[0].net_profit, 50.0, 1e-6);
+        assert_float_absolute_eq!(result.symbols[1].net_profit, -20.0, 1e-6);
+        assert_eq!(result.symbols[1].win_rate, Some(0.0));
+        assert_eq!(result.symbols[1].contract.currency.as_deref(), Some("HKD"));
+        assert_eq!(result.correlation[0][0], Some(1.0));
+        assert_eq!(result.correlation[0][1], result.correlation[1][0]);
+    }
+
+    #[test]
+    fn test_portfolio_orders() {
+        let series = |symbol: &str| {
+            Series::new(
+                symbol.to_string(),
+                Resolution::TICK,
+                Interval::T49,
+                sample_bars(),
+            )
+        };
+        let backtest = Backtest::new(10000.0);
+        let strategy = |_: &Series| IndicatorCross::new(Indicators::SMA(3), 1.0);
+        let prices = |result: &super::PortfolioResult, symbol: &str| -> Vec<(f32, f32)> {
+            result
+                .result
+                .fills
+                .iter()
+                .filter(|fill| fill.symbol == symbol)
+                .map(|fill| (fill.quantity, fill.price))
+                .collect()
+        };
+
+        let alone = backtest.run_portfolio(&[series("ES")], strategy);
+        assert_eq!(prices(&alone, "ES").len(), 3);
+        // Cancelling the orders of one symbol leaves those of the other
+        let together = backtest.run_portfolio(&[series("ES"), series("NQ")], strategy);
+        assert_eq!(prices(&together, "ES"), prices(&alone, "ES"));
+        assert_eq!(prices(&together, "NQ"), prices(&alone, "ES"));
+    }
+
+    #[test]
+    fn test_portfolio_ticks() {
+        let series: Vec<Series> = ["ES", "NQ"]
+            .iter()
+            .map(|symbol| {
+                Series::new(
+                    symbol.to_string(),
+                    Resolution::TICK,
+                    Interval::T49,
+                    sample_bars(),
+                )
+            })
+            .collect();
+        // One tick per bar, at its close
+        let ticks: Vec<Vec<Tick>> = series
+            .iter()
+            .map(|series| {
+                series
+                    .data
+                    .iter()
+                    .map(|bar| Tick {
+                        timestamp: bar.timestamp.clone(),
+                        symbol: series.symbol.clone(),
+                        price: bar.close,
+                        volume: 49.0,
+                    })
+                    .collect()
+            })
+            .collect();
+        let backtest = Backtest::new(10000.0).with_fill_model(FillModel::TickReplay);
+        let script = |_: &Series| Script(vec![(0, Side::Buy, OrderType::Market)]);
+
+        let result = backtest.run_portfolio_with_ticks(&series, &ticks, script);
+        let prices: Vec<f32> = result.result.fills.iter().map(|fill| fill.price).collect();
+        assert_eq!(prices, [104.0, 104.0]);
+        // Without ticks, orders fill at the open of the bar
+        let result = backtest.run_portfolio(&series, script);
+        assert_eq!(result.result.fills[0].price, 102.0);
+    }
+
+    #[test]
+    fn test_run_with_risk() {
+        let series = Series::new(
+            "ES".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sample_bars(),
+        );
+        let mut strategy = Script(vec![
+            (0, Side::Buy, OrderType::Market),
+            (0, Side::Buy, OrderType::Limit(90.0)),
+            (2, Side::Buy, OrderType::Market),
+            (4, Side::Sell, OrderType::Market),
+        ]);
+        let risk = RiskManager::new()
+            .with_max_open_orders(1)
+            .with_max_symbol_exposure(1.5);
+
+        let result = Backtest::new(10000.0)
+            .with_risk(risk)
+            .run(&mut strategy, &series);
+        // The limit order exceeds the open orders, the second buy the exposure
+        assert_eq!(result.fills.len(), 2);
+        assert_eq!(result.rejections.len(), 2);
+        assert_eq!(result.rejections[0].index, 0);
+        assert!(result.rejections[0].reason.contains("open"));
+        assert_eq!(result.rejections[1].timestamp, "2023-01-01T00:02:00Z");
+        assert!(result.rejections[1].reason.contains("exposure of ES"));
+    }
+}
@@ -0,0 +1,236 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{Order, OrderType, Side};
+use crate::domain::{Bar, Tick};
+
+/// How orders fill against the replayed bars or ticks.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FillModel {
+    /// Fills on the bar after the order at the open, or at the order price
+    /// when the range of the bar reaches it.
+    NextBarOpen,
+    /// Replays the ticks of the bar after the order, filling at the first
+    /// tick that reaches the order price.
+    TickReplay,
+    /// Replays ticks like `TickReplay`, except that limit orders join the
+    /// back of a queue with the given volume ahead of them. A limit order
+    /// fills once price trades through its limit, or once more than the
+    /// volume ahead has traded at its limit. Without ticks, limit orders only
+    /// fill when the bar trades through their limit.
+    QueuePosition(f32), // volume ahead
+}
+
+/// Parses fill models of the form `next_bar_open`, `tick_replay` and
+/// `queue:500`.
+impl FromStr for FillModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        match parts.as_slice() {
+            ["next_bar_open"] => Ok(FillModel::NextBarOpen),
+            ["tick_replay"] => Ok(FillModel::TickReplay),
+            ["queue", ahead] => match ahead.parse::<f32>() {
+                Ok(ahead) if ahead.is_finite() && ahead >= 0.0 => {
+                    Ok(FillModel::QueuePosition(ahead))
+                }
+                _ => Err(format!("fill model: invalid queue volume '{}'", ahead)),
+            },
+            _ => Err(format!(
+                "fill model: expected 'next_bar_open', 'tick_replay' or 'queue:<volume>', found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for FillModel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FillModel::NextBarOpen => write!(f, "next_bar_open"),
+            FillModel::TickReplay => write!(f, "tick_replay"),
+            FillModel::QueuePosition(ahead) => write!(f, "queue:{}", ahead),
+        }
+    }
+}
+
+impl FillModel {
+    /// The price at which an order fills on a bar, given the ticks of the
+    /// bar and the volume traded at the limit of the order so far.
+    pub fn fill_on_bar(
+        &self,
+        order: &Order,
+        bar: &Bar,
+        ticks: &[Tick],
+        queued: &mut f32,
+    ) -> Option<f32> {
+        match self {
+            FillModel::NextBarOpen => order.fill_on_bar(bar),
+            _ if !ticks.is_empty() => ticks
+                .iter()
+                .find_map(|tick| self.fill_on_tick(order, tick, queued)),
+            FillModel::QueuePosition(_) => match order.order_type {
+                OrderType::Limit(limit) => trade_through(order.side, limit, bar),
+                _ => order.fill_on_bar(bar),
+            },
+            FillModel::TickReplay => order.fill_on_bar(bar),
+        }
+    }
+
+    /// The price at which an order fills on a tick, given the volume traded
+    /// at the limit of the order so far.
+    pub fn fill_on_tick(&self, order: &Order, tick: &Tick, queued: &mut f32) -> Option<f32> {
+        match (self, order.order_type) {
+            (FillModel::QueuePosition(ahead), OrderType::Limit(limit)) if tick.price == limit => {
+                *queued += tick.volume;
+                (*queued > *ahead).then_some(limit)
+            }
+            _ => order.fill_at(tick.price),
+        }
+    }
+}
+
+/// Fills a limit order only when the bar trades beyond its limit.
+fn trade_through(side: Side, limit: f32, bar: &Bar) -> Option<f32> {
+    match side {
+        Side::Buy if bar.open < limit => Some(bar.open),
+        Side::Buy if bar.low < limit => Some(limit),
+        Side::Sell if bar.open > limit => Some(bar.open),
+        Side::Sell if bar.high > limit => Some(limit),
+        _ => None,
+    }
+}
+
+/// Price concession on market and stop orders. Limit orders never fill
+/// beyond their limit and are not slipped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Slippage {
+    #[default]
+    None,
+    /// A fixed amount of price.
+    Fixed(f32),
+    /// A multiple of the volatility: the average true range of the bars, or
+    /// the mean absolute change between ticks, over a period.
+    Volatility(usize, f32), // period, multiplier
+}
+
+/// Parses slippage of the form `none`, `fixed:0.25` and `volatility:14:0.1`.
+impl FromStr for Slippage {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let number = |value: &str| match value.parse::<f32>() {
+            Ok(value) if value.is_finite() && value >= 0.0 => Ok(value),
+            _ => Err(format!("slippage: invalid amount '{}'", value)),
+        };
+        match parts.as_slice() {
+            ["none"] => Ok(Slippage::None),
+            ["fixed", amount] => Ok(Slippage::Fixed(number(amount)?)),
+            ["volatility", period, multiplier] => match period.parse::<usize>() {
+                Ok(period) if period > 0 => Ok(Slippage::Volatility(period, number(multiplier)?)),
+                _ => Err(format!("slippage: invalid period '{}'", period)),
+            },
+            _ => Err(format!(
+                "slippage: expected 'none', 'fixed:<amount>' or 'volatility:<period>:<multiplier>', found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl Slippage {
+    /// Slipped price of an order filling at `price`, given the current
+    /// volatility, if known.
+    pub fn apply(&self, order: &Order, price: f32, volatility: Option<f32>) -> f32 {
+        if let OrderType::Limit(_) = order.order_type {
+            return price;
+        }
+        let amount = match self {
+            Slippage::None => 0.0,
+            Slippage::Fixed(amount) => *amount,
+            Slippage::Volatility(_, multiplier) => multiplier * volatility.unwrap_or(0.0),
+        };
+        price + order.side.sign() * amount
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtest::{FillModel, Order, OrderType, Side, Slippage};
+    use crate::domain::Tick;
+
+    fn order(side: Side, order_type: OrderType) -> Order {
+        Order {
+            id: 1,
+            symbol: "ES".to_string(),
+            side,
+            quantity: 1.0,
+            order_type,
+            submitted_at: 0,
+        }
+    }
+
+    fn ticks(prices: &[(f32, f32)]) -> Vec<Tick> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, (price, volume))| Tick {
+                timestamp: format!("2023-01-01T00:00:{:02}Z", i),
+                symbol: "ES".to_string(),
+                price: *price,
+                volume: *volume,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_models() {
+        assert_eq!("tick_replay".parse(), Ok(FillModel::TickReplay));
+        assert_eq!("queue:500".parse(), Ok(FillModel::QueuePosition(500.0)));
+        assert!("queue:-1".parse::<FillModel>().is_err());
+        assert_eq!(FillModel::QueuePosition(500.0).to_string(), "queue:500");
+        assert_eq!(
+            "volatility:14:0.1".parse(),
+            Ok(Slippage::Volatility(14, 0.1))
+        );
+        assert!("fixed".parse::<Slippage>().is_err());
+    }
+
+    #[test]
+    fn test_queue_position() {
+        let model = FillModel::QueuePosition(5.0);
+        let limit = order(Side::Buy, OrderType::Limit(100.0));
+        let replay = ticks(&[(101.0, 3.0), (100.0, 2.0), (100.0, 2.0), (100.0, 2.0)]);
+
+        let mut queued = 0.0;
+        let fills: Vec<Option<f32>> = replay
+            .iter()
+            .map(|tick| model.fill_on_tick(&limit, tick, &mut queued))
+            .collect();
+        assert_eq!(fills, [None, None, None, Some(100.0)]);
+
+        let mut queued = 0.0;
+        let tick = &ticks(&[(99.5, 1.0)])[0];
+        assert_eq!(model.fill_on_tick(&limit, tick, &mut queued), Some(99.5));
+        assert_eq!(
+            FillModel::TickReplay.fill_on_tick(&limit, &replay[1], &mut queued),
+            Some(100.0)
+        );
+    }
+
+    #[test]
+    fn test_slippage() {
+        let market = order(Side::Sell, OrderType::Market);
+        let limit = order(Side::Sell, OrderType::Limit(100.0));
+        assert_eq!(Slippage::Fixed(0.25).apply(&market, 100.0, None), 99.75);
+        assert_eq!(Slippage::Fixed(0.25).apply(&limit, 100.0, None), 100.0);
+        assert_eq!(
+            Slippage::Volatility(14, 0.5).apply(&market, 100.0, Some(2.0)),
+            99.0
+        );
+    }
+}
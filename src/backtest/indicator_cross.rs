@@ -0,0 +1,150 @@
+use std::collections::VecDeque;
+
+use crate::backtest::{Context, OrderType, Strategy};
+use crate::domain::{Bar, Indicators};
+
+/// Indicator crossover strategy
+///
+/// Always in the market once the indicator is defined: long `quantity` while
+/// the close is above the primary line of the indicator, and short while it
+/// is below. Positions are reversed with market orders filling on the next
+/// bar. When the backtest sizes positions, each new position is sized on
+/// entry instead, and held at that size until it is reversed.
+pub struct IndicatorCross {
+    indicator: Indicators,
+    quantity: f32,
+    line: VecDeque<Option<f32>>,
+}
+
+impl IndicatorCross {
+    pub fn new(indicator: Indicators, quantity: f32) -> Self {
+        IndicatorCross {
+            indicator,
+            quantity,
+            line: VecDeque::new(),
+        }
+    }
+
+    pub fn indicator(&self) -> &Indicators {
+        &self.indicator
+    }
+}
+
+impl Strategy for IndicatorCross {
+    fn on_start(&mut self, data: &[Bar]) {
+        self.line = self.indicator.calculate(&data.to_vec()).0;
+    }
+
+    fn on_append(&mut self, data: &[Bar]) {
+        let line = self.indicator.calculate(&data.to_vec()).0;
+        self.line.push_back(line.back().copied().flatten());
+        while self.line.len() > data.len() {
+            self.line.pop_front();
+        }
+    }
+
+    fn on_bar(&mut self, context: &mut Context, bar: &Bar) {
+        let Some(Some(value)) = self.line.get(context.index()) else {
+            return;
+        };
+        let direction = if bar.close > *value {
+            1.0
+        } else if bar.close < *value {
+            -1.0
+        } else {
+            return;
+        };
+
+        context.cancel_all();
+        let position = context.position();
+        let quantity = if position * direction > 0.0 {
+            position.abs()
+        } else {
+            context.size().unwrap_or(self.quantity)
+        };
+        let change = direction * quantity - position;
+        if change > 0.0 {
+            context.buy(change, OrderType::Market);
+        } else if change < 0.0 {
+            context.sell(-change, OrderType::Market);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtest::{Backtest, IndicatorCross, RiskManager, Sizing};
+    use crate::domain::{Bar, Indicators, Interval, Resolution, Series};
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_indicator_cross() {
+        let series = Series::new(
+            "ES".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sample_bars(),
+        );
+        let mut strategy = IndicatorCross::new(Indicators::SMA(3), 1.0);
+
+        let result = Backtest::new(10000.0).run(&mut strategy, &series);
+        let fills: Vec<(usize, f32, f32)> = result
+            .fills
+            .iter()
+            .map(|fill| (fill.index, fill.quantity, fill.price))
+            .collect();
+        assert_eq!(fills, [(4, 1.0, 103.0), (7, 2.0, 107.0), (10, 2.0, 101.0)]);
+        let pnl: Vec<f64> = result.trades.iter().map(|trade| trade.pnl).collect();
+        assert_eq!(pnl, [4.0, 6.0]);
+        assert_eq!(result.positions["ES"].quantity, 1.0);
+    }
+
+    #[test]
+    fn test_indicator_cross_sized() {
+        let series = Series::new(
+            "ES".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sample_bars(),
+        );
+        let mut strategy = IndicatorCross::new(Indicators::SMA(3), 1.0);
+        let risk = RiskManager::new().with_sizing(Sizing::FixedFractional(50.0));
+
+        let result = Backtest::new(10000.0)
+            .with_risk(risk)
+            .run(&mut strategy, &series);
+        let quantities: Vec<f32> = result.fills.iter().map(|fill| fill.quantity).collect();
+        // Half the equity at the signal: 5000 at 105, 5094 at 107 and 5235 at 101
+        assert_eq!(quantities, [47.0, 94.0, 98.0]);
+        assert_eq!(result.positions["ES"].quantity, 51.0);
+    }
+}
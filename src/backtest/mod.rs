@@ -0,0 +1,35 @@
+mod account;
+mod commission;
+mod contract;
+mod engine;
+mod fill_model;
+mod indicator_cross;
+mod monte_carlo;
+mod optimizer;
+mod order;
+mod portfolio;
+mod position;
+mod random;
+mod report;
+mod risk;
+mod sizing;
+mod strategy;
+mod walk_forward;
+
+pub use account::Account;
+pub use commission::{Commission, CommissionRule, CommissionSchedule, fetch_commission_schedules};
+pub use contract::{Contract, Contracts, fetch_contracts};
+pub use engine::{Backtest, BacktestResult, EquityPoint};
+pub use fill_model::{FillModel, Slippage};
+pub use indicator_cross::IndicatorCross;
+pub use monte_carlo::{ConfidenceInterval, Distribution, MonteCarlo, MonteCarloResult, Resampling};
+pub use optimizer::{Evaluation, Objective, Optimizer, ParameterRange, Search};
+pub use order::{Fill, Order, OrderType, Side};
+pub use portfolio::{PortfolioResult, SymbolPerformance, correlation};
+pub use position::{Position, Trade};
+pub use random::Random;
+pub use report::{Drawdown, MonthlyReturn, Report, TradeExcursion};
+pub use risk::{Rejection, RiskManager};
+pub use sizing::{Sizing, kelly};
+pub use strategy::{Context, Strategy};
+pub use walk_forward::{WalkForward, WalkForwardMode, WalkForwardResult, Window, WindowResult};
@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{BacktestResult, Contract, Random};
+
+/// Confidence levels, in percent, of the reported intervals.
+const CONFIDENCE_LEVELS: [f64; 3] = [90.0, 95.0, 99.0];
+
+/// How the sequence of trades of each simulation is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Resampling {
+    /// Every trade once, in a random order.
+    Reshuffle,
+    /// As many trades as were taken, drawn at random with replacement.
+    Bootstrap,
+}
+
+impl FromStr for Resampling {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reshuffle" => Ok(Resampling::Reshuffle),
+            "bootstrap" => Ok(Resampling::Bootstrap),
+            _ => Err(format!(
+                "resampling: expected 'reshuffle' or 'bootstrap', found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Resampling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Resampling::Reshuffle => write!(f, "reshuffle"),
+            Resampling::Bootstrap => write!(f, "bootstrap"),
+        }
+    }
+}
+
+/// The range within which a share of the simulated values fell, cutting the
+/// remainder equally from both tails.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub confidence: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Distribution {
+    pub mean: f64,
+    pub median: f64,
+    pub min: f64,
+    pub max: f64,
+    pub intervals: Vec<ConfidenceInterval>,
+}
+
+impl Distribution {
+    fn new(mut values: Vec<f64>) -> Self {
+        values.sort_by(f64::total_cmp);
+        let intervals = CONFIDENCE_LEVELS
+            .iter()
+            .map(|confidence| {
+                let tail = (100.0 - confidence) / 2.0;
+                ConfidenceInterval {
+                    confidence: *confidence,
+                    lower: percentile(&values, tail),
+                    upper: percentile(&values, 100.0 - tail),
+                }
+            })
+            .collect();
+        Distribution {
+            mean: values.iter().sum::<f64>() / values.len().max(1) as f64,
+            median: percentile(&values, 50.0),
+            min: values.first().copied().unwrap_or_default(),
+            max: values.last().copied().unwrap_or_default(),
+            intervals,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MonteCarloResult {
+    pub runs: usize,
+    pub final_equity: Distribution,
+    /// Maximum drawdown as a percentage of the peak equity.
+    pub max_drawdown: Distribution,
+    /// Percentage of the simulations in which the equity fell to the ruin
+    /// level.
+    pub risk_of_ruin: f64,
+}
+
+/// Monte Carlo simulation of a backtest
+///
+/// Replays the closed trades of a backtest many times, each time in a
+/// randomly drawn sequence, to estimate how the final equity and the maximum
+/// drawdown could have turned out. Each simulation may also skip trades at
+/// random, as when signals are missed, and charge each trade extra slippage
+/// drawn uniformly up to a maximum per unit on entry and on exit. Slippage is
+/// valued by the contract of the symbol traded, one to one unless given.
+///
+/// A simulation is ruined when its equity falls to the given percentage loss
+/// of the initial capital.
+pub struct MonteCarlo {
+    runs: usize,
+    resampling: Resampling,
+    slippage: f32,
+    contracts: BTreeMap<String, Contract>,
+    skip: f64,
+    ruin: f64,
+    seed: u64,
+}
+
+impl MonteCarlo {
+    pub fn new(runs: usize, resampling: Resampling) -> Self {
+        MonteCarlo {
+            runs,
+            resampling,
+            slippage: 0.0,
+            contracts: BTreeMap::new(),
+            skip: 0.0,
+            ruin: 50.0,
+            seed: 42,
+        }
+    }
+
+    /// Maximum extra slippage per unit, on entry and on exit.
+    pub fn with_slippage(mut self, slippage: f32) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    pub fn with_contract(mut self, symbol: &str, contract: Contract) -> Self {
+        self.contracts.insert(symbol.to_string(), contract);
+        self
+    }
+
+    /// Probability of skipping each trade, between 0 and 1.
+    pub fn with_skip(mut self, skip: f64) -> Self {
+        self.skip = skip;
+        self
+    }
+
+    /// Loss of the initial capital, in percent, that counts as ruin.
+    pub fn with_ruin(mut self, ruin: f64) -> Self {
+        self.ruin = ruin;
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn run(&self, result: &BacktestResult) -> MonteCarloResult {
+        let mut random = Random::new(self.seed);
+        // Profit of each trade, and the value of a price move of one unit
+        let trades: Vec<(f64, f64)> = result
+            .trades
+            .iter()
+            .map(|trade| {
+                let point_value = self
+                    .contracts
+                    .get(&trade.symbol)
+                    .map_or(1.0, Contract::point_value);
+                (trade.pnl, trade.quantity as f64 * point_value)
+            })
+            .collect();
+        let capital = result.initial_capital;
+        let ruin_level = capital * (1.0 - self.ruin / 100.0);
+
+        let mut final_equity = Vec::with_capacity(self.runs);
+        let mut max_drawdown = Vec::with_capacity(self.runs);
+        let mut ruined = 0;
+        let mut sequence = trades.clone();
+        for _ in 0..self.runs {
+            match self.resampling {
+                Resampling::Reshuffle => shuffle(&mut sequence, &mut random),
+                Resampling::Bootstrap => {
+                    for trade in sequence.iter_mut() {
+                        *trade = trades[random.below(trades.len())];
+                    }
+                }
+            }
+
+            let mut equity = capital;
+            let mut peak = capital;
+            let mut drawdown: f64 = 0.0;
+            let mut is_ruined = false;
+            for (pnl, value) in &sequence {
+                if self.skip > 0.0 && random.next_f64() < self.skip {
+                    continue;
+                }
+                let slippage = (0..2)
+                    .map(|_| random.next_f64() * self.slippage as f64)
+                    .sum::<f64>();
+                equity += pnl - slippage * value;
+                peak = peak.max(equity);
+                drawdown = drawdown.max((peak - equity) / peak * 100.0);
+                is_ruined |= equity <= ruin_level;
+            }
+
+            final_equity.push(equity);
+            max_drawdown.push(drawdown);
+            ruined += is_ruined as usize;
+        }
+
+        MonteCarloResult {
+            runs: self.runs,
+            final_equity: Distribution::new(final_equity),
+            max_drawdown: Distribution::new(max_drawdown),
+            risk_of_ruin: ruined as f64 / self.runs.max(1) as f64 * 100.0,
+        }
+    }
+}
+
+/// Fisher-Yates shuffle.
+fn shuffle<T>(values: &mut [T], random: &mut Random) {
+    for i in (1..values.len()).rev() {
+        values.swap(i, random.below(i + 1));
+    }
+}
+
+/// Percentile of sorted values, interpolating between the nearest ranks.
+fn percentile(sorted: &[f64], percent: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = percent / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::backtest::{BacktestResult, Contract, MonteCarlo, Resampling, Side, Trade};
+
+    fn result(pnl: &[f64]) -> BacktestResult {
+        BacktestResult {
+            initial_capital: 1000.0,
+            fills: Vec::new(),
+            trades: pnl
+                .iter()
+                .enumerate()
+                .map(|(i, pnl)| Trade {
+                    symbol: "ES".to_string(),
+                    side: Side::Buy,
+                    quantity: 1.0,
+                    entry_index: i,
+                    entry_time: String::new(),
+                    entry_price: 100.0,
+                    exit_index: i + 1,
+                    exit_time: String::new(),
+                    exit_price: 100.0 + *pnl as f32,
+                    commission: 0.0,
+                    pnl: *pnl,
+                })
+                .collect(),
+            equity: Vec::new(),
+            positions: BTreeMap::new(),
+            rejections: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_reshuffle() {
+        let result = result(&[100.0, -200.0, 50.0, -100.0, 300.0]);
+        let simulation = MonteCarlo::new(500, Resampling::Reshuffle).run(&result);
+
+        // Reordering trades never changes where they add up to
+        assert_float_absolute_eq!(simulation.final_equity.min, 1150.0, 1e-9);
+        assert_float_absolute_eq!(simulation.final_equity.max, 1150.0, 1e-9);
+        // Worst case: both losses in a row before any gain
+        assert_float_absolute_eq!(simulation.max_drawdown.max, 30.0, 1e-9);
+        let interval = &simulation.max_drawdown.intervals[1];
+        assert_eq!(interval.confidence, 95.0);
+        assert!(interval.lower <= simulation.max_drawdown.median);
+        assert!(interval.upper >= simulation.max_drawdown.median);
+        assert_eq!(simulation.risk_of_ruin, 0.0);
+    }
+
+    #[test]
+    fn test_bootstrap_with_costs() {
+        let result = result(&[100.0, -200.0, 50.0, -100.0, 300.0]);
+        let bootstrap = MonteCarlo::new(1000, Resampling::Bootstrap).with_ruin(30.0);
+        let simulation = bootstrap.run(&result);
+        assert!(simulation.final_equity.min < 1150.0);
+        assert!(simulation.final_equity.max > 1150.0);
+        assert!(simulation.risk_of_ruin > 0.0);
+        assert_eq!(simulation, bootstrap.run(&result));
+
+        let costly = MonteCarlo::new(200, Resampling::Reshuffle)
+            .with_slippage(1.0)
+            .with_skip(0.2)
+            .run(&result);
+        assert!(costly.final_equity.min < costly.final_equity.max);
+        // Slippage is valued by the contract
+        let slippage = |contract: Contract| {
+            MonteCarlo::new(1, Resampling::Reshuffle)
+                .with_slippage(1.0)
+                .with_contract("ES", contract)
+                .run(&result)
+                .final_equity
+                .mean
+        };
+        let unit = slippage(Contract::default());
+        let contract = Contract {
+            multiplier: 50.0,
+            ..Contract::default()
+        };
+        assert_float_absolute_eq!(1150.0 - slippage(contract), (1150.0 - unit) * 50.0, 1e-6);
+
+        let never = MonteCarlo::new(50, Resampling::Reshuffle)
+            .with_skip(1.0)
+            .run(&result);
+        assert_eq!(never.final_equity.mean, 1000.0);
+    }
+}
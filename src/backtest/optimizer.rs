@@ -0,0 +1,535 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{Random, Report};
+
+/// Seed of randomized searches that do not specify one.
+const DEFAULT_SEED: u64 = 42;
+/// Most values a parameter may be swept across.
+pub const MAX_RANGE_VALUES: usize = 100_000;
+
+/// The values a parameter is swept across, from `start` to `end` inclusive
+/// in increments of `step`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ParameterRange {
+    pub start: f32,
+    pub end: f32,
+    pub step: f32,
+}
+
+impl ParameterRange {
+    pub fn new(start: f32, end: f32, step: f32) -> Result<Self, String> {
+        if !(start.is_finite() && end.is_finite() && step.is_finite()) {
+            return Err("parameter range: bounds must be finite".to_string());
+        }
+        if end < start {
+            return Err(format!(
+                "parameter range: end {} is below start {}",
+                end, start
+            ));
+        }
+        if step <= 0.0 {
+            return Err(format!("parameter range: step {} must be positive", step));
+        }
+        Ok(ParameterRange { start, end, step })
+    }
+
+    /// Number of values in the range, counted without building them, or
+    /// `None` when there are not between one and `MAX_RANGE_VALUES`.
+    pub fn count(&self) -> Option<usize> {
+        let count = self.raw_count();
+        (count >= 1.0 && count <= MAX_RANGE_VALUES as f64).then_some(count as usize)
+    }
+
+    /// The values of the range, at most `MAX_RANGE_VALUES` of them.
+    pub fn values(&self) -> Vec<f32> {
+        // A count that is not a number converts to zero
+        let count = self.raw_count().min(MAX_RANGE_VALUES as f64) as usize;
+        (0..count)
+            .map(|i| (self.start as f64 + i as f64 * self.step as f64) as f32)
+            .collect()
+    }
+
+    fn raw_count(&self) -> f64 {
+        ((self.end - self.start) as f64 / self.step as f64 + 1e-6).floor() + 1.0
+    }
+}
+
+/// Parses ranges of the form `10` (a fixed value), `5:20` (a step of 1) and
+/// `5:20:5`.
+impl FromStr for ParameterRange {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts = s
+            .split(':')
+            .map(|part| {
+                part.trim()
+                    .parse::<f32>()
+                    .map_err(|err| format!("parameter range: invalid value '{}': {}", part, err))
+            })
+            .collect::<Result<Vec<f32>, String>>()?;
+        match parts.as_slice() {
+            [value] => ParameterRange::new(*value, *value, 1.0),
+            [start, end] => ParameterRange::new(*start, *end, 1.0),
+            [start, end, step] => ParameterRange::new(*start, *end, *step),
+            _ => Err(format!(
+                "parameter range: expected '<value>' or '<start>:<end>[:<step>]', found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+/// How the parameter space is searched.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Search {
+    /// Every combination of the parameter values.
+    Grid,
+    /// Combinations drawn at random.
+    Random(usize, u64), // samples, seed
+    /// A genetic algorithm evolving a population of combinations, breeding
+    /// the fitter ones by tournament selection, uniform crossover and
+    /// mutation, and carrying the fittest tenth over unchanged.
+    Genetic(usize, usize, u64), // population, generations, seed
+}
+
+/// Parses searches of the form `grid`, `random:<samples>[:<seed>]` and
+/// `genetic:<population>:<generations>[:<seed>]`.
+impl FromStr for Search {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let number = |value: &str| match value.parse::<usize>() {
+            Ok(value) if value > 0 => Ok(value),
+            _ => Err(format!("search: invalid count '{}'", value)),
+        };
+        let seed = |value: Option<&&str>| match value {
+            Some(value) => value
+                .parse::<u64>()
+                .map_err(|_| format!("search: invalid seed '{}'", value)),
+            None => Ok(DEFAULT_SEED),
+        };
+        match parts.as_slice() {
+            ["grid"] => Ok(Search::Grid),
+            ["random", samples, rest @ ..] if rest.len() <= 1 => {
+                Ok(Search::Random(number(samples)?, seed(rest.first())?))
+            }
+            ["genetic", population, generations, rest @ ..] if rest.len() <= 1 => {
+                Ok(Search::Genetic(
+                    number(population)?,
+                    number(generations)?,
+                    seed(rest.first())?,
+                ))
+            }
+            _ => Err(format!(
+                "search: expected 'grid', 'random:<samples>[:<seed>]' or \
+                 'genetic:<population>:<generations>[:<seed>]', found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+/// The report metric that results are ranked by, higher being better.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Objective {
+    NetProfit,
+    Sharpe,
+    Sortino,
+    Calmar,
+    ProfitFactor,
+    Expectancy,
+}
+
+impl FromStr for Objective {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "net_profit" => Ok(Objective::NetProfit),
+            "sharpe" => Ok(Objective::Sharpe),
+            "sortino" => Ok(Objective::Sortino),
+            "calmar" => Ok(Objective::Calmar),
+            "profit_factor" => Ok(Objective::ProfitFactor),
+            "expectancy" => Ok(Objective::Expectancy),
+            _ => Err(format!("objective: unsupported objective '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Objective::NetProfit => "net_profit",
+            Objective::Sharpe => "sharpe",
+            Objective::Sortino => "sortino",
+            Objective::Calmar => "calmar",
+            Objective::ProfitFactor => "profit_factor",
+            Objective::Expectancy => "expectancy",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Objective {
+    /// The score of a report, if the metric is defined.
+    pub fn score(&self, report: &Report) -> Option<f64> {
+        match self {
+            Objective::NetProfit => Some(report.net_profit),
+            Objective::Sharpe => report.sharpe,
+            Objective::Sortino => report.sortino,
+            Objective::Calmar => report.calmar,
+            Objective::ProfitFactor => report.profit_factor,
+            Objective::Expectancy => report.expectancy,
+        }
+    }
+}
+
+/// Summary of the backtest of one combination of parameters.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Evaluation {
+    pub params: Vec<f32>,
+    /// The objective of the backtest, if defined.
+    pub score: Option<f64>,
+    pub net_profit: f64,
+    pub total_return: f64,
+    pub sharpe: Option<f64>,
+    /// Maximum drawdown as a percentage of the peak equity.
+    pub max_drawdown: f64,
+    pub profit_factor: Option<f64>,
+    pub trade_count: usize,
+}
+
+impl Evaluation {
+    pub fn new(params: Vec<f32>, objective: Objective, report: &Report) -> Self {
+        Evaluation {
+            params,
+            score: objective.score(report),
+            net_profit: report.net_profit,
+            total_return: report.total_return,
+            sharpe: report.sharpe,
+            max_drawdown: report
+                .max_drawdown
+                .as_ref()
+                .map_or(0.0, |drawdown| drawdown.percent),
+            profit_factor: report.profit_factor,
+            trade_count: report.trade_count,
+        }
+    }
+
+    fn fitness(&self) -> f64 {
+        self.score.unwrap_or(f64::NEG_INFINITY)
+    }
+}
+
+/// Parameter optimizer
+///
+/// Backtests combinations of parameters drawn from the ranges, spreading the
+/// backtests across threads, and ranks them by the objective. Combinations
+/// are identified by the index of each value in its range, so no combination
+/// is backtested twice.
+pub struct Optimizer {
+    ranges: Vec<ParameterRange>,
+    search: Search,
+    objective: Objective,
+    threads: usize,
+}
+
+impl Optimizer {
+    pub fn new(ranges: Vec<ParameterRange>, search: Search, objective: Objective) -> Self {
+        Optimizer {
+            ranges,
+            search,
+            objective,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn objective(&self) -> Objective {
+        self.objective
+    }
+
+    /// Number of combinations in the parameter space, or why the space is
+    /// too large to search. Only the counts of the ranges are computed.
+    pub fn combinations(&self) -> Result<usize, String> {
+        self.ranges.iter().try_fold(1usize, |combinations, range| {
+            let count = range.count().ok_or_else(|| {
+                format!(
+                    "parameter range {}:{}:{} must have between 1 and {} values",
+                    range.start, range.end, range.step, MAX_RANGE_VALUES
+                )
+            })?;
+            combinations
+                .checked_mul(count)
+                .ok_or_else(|| "too many parameter combinations".to_string())
+        })
+    }
+
+    /// Number of backtests the search runs, at most.
+    pub fn evaluations(&self) -> Result<usize, String> {
+        let combinations = self.combinations()?;
+        Ok(match self.search {
+            Search::Grid => combinations,
+            Search::Random(samples, _) => samples,
+            Search::Genetic(population, generations, _) => population.saturating_mul(generations),
+        })
+    }
+
+    /// Backtests the combinations chosen by the search, best first. The
+    /// backtest returns a report, or `None` when the parameters are invalid;
+    /// invalid combinations are left out of the results.
+    pub fn optimize<F>(&self, backtest: F) -> Vec<Evaluation>
+    where
+        F: Fn(&[f32]) -> Option<Report> + Sync,
+    {
+        let values: Vec<Vec<f32>> = self.ranges.iter().map(ParameterRange::values).collect();
+        let mut evaluated: BTreeMap<Vec<usize>, Option<Evaluation>> = BTreeMap::new();
+
+        match self.search {
+            Search::Grid => {
+                let genomes = (0..self.combinations().unwrap_or(0))
+                    .map(|mut n| {
+                        let mut genome = vec![0; values.len()];
+                        for (gene, values) in genome.iter_mut().zip(&values).rev() {
+                            *gene = n % values.len();
+                            n /= values.len();
+                        }
+                        genome
+                    })
+                    .collect();
+                self.evaluate(genomes, &values, &backtest, &mut evaluated);
+            }
+            Search::Random(samples, seed) => {
+                let mut random = Random::new(seed);
+                let genomes = (0..samples)
+                    .map(|_| random_genome(&values, &mut random))
+                    .collect();
+                self.evaluate(genomes, &values, &backtest, &mut evaluated);
+            }
+            Search::Genetic(population, generations, seed) => {
+                let mut random = Random::new(seed);
+                let mut genomes: Vec<Vec<usize>> = (0..population)
+                    .map(|_| random_genome(&values, &mut random))
+                    .collect();
+                for generation in 0..generations {
+                    self.evaluate(genomes.clone(), &values, &backtest, &mut evaluated);
+                    if generation + 1 == generations {
+                        break;
+                    }
+                    let fitness = |genome: &Vec<usize>| {
+                        evaluated[genome]
+                            .as_ref()
+                            .map_or(f64::NEG_INFINITY, Evaluation::fitness)
+                    };
+                    genomes.sort_by(|a, b| fitness(b).total_cmp(&fitness(a)));
+                    genomes = breed(&genomes, &values, &mut random);
+                }
+            }
+        }
+
+        let mut evaluations: Vec<Evaluation> = evaluated.into_values().flatten().collect();
+        evaluations.sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+        evaluations
+    }
+
+    /// Backtests the combinations not evaluated yet on all threads.
+    fn evaluate<F>(
+        &self,
+        genomes: Vec<Vec<usize>>,
+        values: &[Vec<f32>],
+        backtest: &F,
+        evaluated: &mut BTreeMap<Vec<usize>, Option<Evaluation>>,
+    ) where
+        F: Fn(&[f32]) -> Option<Report> + Sync,
+    {
+        let mut pending: Vec<Vec<usize>> = genomes
+            .into_iter()
+            .filter(|genome| !evaluated.contains_key(genome))
+            .collect();
+        pending.sort();
+        pending.dedup();
+
+        let next = AtomicUsize::new(0);
+        let results: Vec<(usize, Option<Evaluation>)> = thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(pending.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut results = Vec::new();
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(genome) = pending.get(i) else {
+                                break;
+                            };
+                            let params: Vec<f32> = genome
+                                .iter()
+                                .zip(values)
+                                .map(|(gene, values)| values[*gene])
+                                .collect();
+                            let evaluation = backtest(&params)
+                                .map(|report| Evaluation::new(params, self.objective, &report));
+                            results.push((i, evaluation));
+                        }
+                        results
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("optimizer worker panicked"))
+                .collect()
+        });
+
+        for (i, evaluation) in results {
+            evaluated.insert(pending[i].clone(), evaluation);
+        }
+    }
+}
+
+fn random_genome(values: &[Vec<f32>], random: &mut Random) -> Vec<usize> {
+    values
+        .iter()
+        .map(|values| random.below(values.len()))
+        .collect()
+}
+
+/// The next generation of a population sorted fittest first.
+fn breed(population: &[Vec<usize>], values: &[Vec<f32>], random: &mut Random) -> Vec<Vec<usize>> {
+    let elite = (population.len() / 10).max(1);
+    let mut next: Vec<Vec<usize>> = population[..elite].to_vec();
+    // Population is sorted, so the fitter of two candidates has the lower index
+    let tournament = |random: &mut Random| {
+        let a = random.below(population.len());
+        let b = random.below(population.len());
+        &population[a.min(b)]
+    };
+    let mutation_rate = 1.0 / values.len().max(1) as f64;
+
+    while next.len() < population.len() {
+        let mother = tournament(random);
+        let father = tournament(random);
+        let child = mother
+            .iter()
+            .zip(father)
+            .zip(values)
+            .map(|((mother, father), values)| {
+                if random.next_f64() < mutation_rate {
+                    random.below(values.len())
+                } else if random.next_f64() < 0.5 {
+                    *mother
+                } else {
+                    *father
+                }
+            })
+            .collect();
+        next.push(child);
+    }
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtest::{
+        Backtest, IndicatorCross, Objective, Optimizer, ParameterRange, Report, Search,
+    };
+    use crate::domain::{Bar, Indicators, Interval, Resolution, Series};
+
+    /// Bars whose prices follow a sine wave of the given period around 100,
+    /// with an amplitude of 10.
+    fn sine_bars(count: usize, period: f32) -> Vec<Bar> {
+        (0..count)
+            .map(|i| {
+                let price = 100.0 + 10.0 * (2.0 * std::f32::consts::PI * i as f32 / period).sin();
+                Bar {
+                    timestamp: format!("2023-01-01T{:02}:{:02}:00Z", i / 60, i % 60),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: 1.0,
+                }
+            })
+            .collect()
+    }
+
+    fn backtest(series: &Series, params: &[f32]) -> Option<Report> {
+        let indicator = Indicators::from(("sma", &params.to_vec()));
+        indicator.validate().ok()?;
+        let mut strategy = IndicatorCross::new(indicator, 1.0);
+        let result = Backtest::new(10000.0).run(&mut strategy, series);
+        Some(Report::new(&result, &series.data))
+    }
+
+    #[test]
+    fn test_parse_ranges() {
+        let range: ParameterRange = "5:20:5".parse().unwrap();
+        assert_eq!(range.values(), [5.0, 10.0, 15.0, 20.0]);
+        assert_eq!(
+            "0.1:0.3:0.1"
+                .parse::<ParameterRange>()
+                .unwrap()
+                .values()
+                .len(),
+            3
+        );
+        assert_eq!("2".parse::<ParameterRange>().unwrap().values(), [2.0]);
+        assert!("20:5".parse::<ParameterRange>().is_err());
+
+        // Counted without building the values
+        let huge: ParameterRange = "0:1000000000:0.001".parse().unwrap();
+        assert_eq!(huge.count(), None);
+        assert_eq!(range.count(), Some(4));
+        let optimizer = Optimizer::new(vec![range, huge], Search::Grid, Objective::Sharpe);
+        assert!(optimizer.combinations().is_err());
+        let optimizer = Optimizer::new(vec![range; 2], Search::Grid, Objective::Sharpe);
+        assert_eq!(optimizer.combinations(), Ok(16));
+        assert_eq!(optimizer.evaluations(), Ok(16));
+        let optimizer = Optimizer::new(vec![range], Search::Genetic(20, 5, 42), Objective::Sharpe);
+        assert_eq!(optimizer.evaluations(), Ok(100));
+        assert_eq!("genetic:20:5".parse(), Ok(Search::Genetic(20, 5, 42)));
+        assert_eq!("random:50:7".parse(), Ok(Search::Random(50, 7)));
+        assert!("random:0".parse::<Search>().is_err());
+    }
+
+    #[test]
+    fn test_optimize() {
+        let series = Series::new(
+            "ES".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sine_bars(200, 20.0),
+        );
+        let ranges = vec!["0:20:2".parse().unwrap()];
+
+        let grid = Optimizer::new(ranges.clone(), Search::Grid, Objective::NetProfit)
+            .with_threads(4)
+            .optimize(|params| backtest(&series, params));
+        // SMA(0) is invalid and left out
+        assert_eq!(grid.len(), 10);
+        assert!(grid.windows(2).all(|pair| pair[0].score >= pair[1].score));
+        let serial = Optimizer::new(ranges.clone(), Search::Grid, Objective::NetProfit)
+            .with_threads(1)
+            .optimize(|params| backtest(&series, params));
+        assert_eq!(grid, serial);
+
+        let random = Optimizer::new(ranges.clone(), Search::Random(5, 1), Objective::NetProfit);
+        let first = random.optimize(|params| backtest(&series, params));
+        assert!(first.len() <= 5);
+        assert_eq!(first, random.optimize(|params| backtest(&series, params)));
+
+        let genetic = Optimizer::new(ranges, Search::Genetic(6, 8, 3), Objective::NetProfit)
+            .optimize(|params| backtest(&series, params));
+        assert_eq!(genetic[0].params, grid[0].params);
+    }
+}
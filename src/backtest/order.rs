@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::Bar;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    /// Sign of the change in position, positive for buys.
+    pub fn sign(&self) -> f32 {
+        match self {
+            Side::Buy => 1.0,
+            Side::Sell => -1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "price", rename_all = "lowercase")]
+pub enum OrderType {
+    Market,
+    /// Fills at the price or better.
+    Limit(f32),
+    /// Becomes a market order once the price trades through the stop.
+    Stop(f32),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: u64,
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f32,
+    pub order_type: OrderType,
+    /// Index of the bar or tick on which the order was submitted.
+    pub submitted_at: usize,
+}
+
+impl Order {
+    /// The price at which the order fills on a bar, if it does. Market
+    /// orders fill at the open. Limit and stop orders fill at the open when
+    /// the bar opens through their price, and at their price when the range
+    /// of the bar reaches it.
+    pub fn fill_on_bar(&self, bar: &Bar) -> Option<f32> {
+        match (self.order_type, self.side) {
+            (OrderType::Market, _) => Some(bar.open),
+            (OrderType::Limit(price), Side::Buy) | (OrderType::Stop(price), Side::Sell) => {
+                if bar.open <= price {
+                    Some(bar.open)
+                } else if bar.low <= price {
+                    Some(price)
+                } else {
+                    None
+                }
+            }
+            (OrderType::Limit(price), Side::Sell) | (OrderType::Stop(price), Side::Buy) => {
+                if bar.open >= price {
+                    Some(bar.open)
+                } else if bar.high >= price {
+                    Some(price)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// The price at which the order fills on a trade at `price`, if it does.
+    pub fn fill_at(&self, price: f32) -> Option<f32> {
+        let fills = match (self.order_type, self.side) {
+            (OrderType::Market, _) => true,
+            (OrderType::Limit(limit), Side::Buy) | (OrderType::Stop(limit), Side::Sell) => {
+                price <= limit
+            }
+            (OrderType::Limit(limit), Side::Sell) | (OrderType::Stop(limit), Side::Buy) => {
+                price >= limit
+            }
+        };
+        fills.then_some(price)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub order_id: u64,
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f32,
+    pub price: f32,
+    pub commission: f64,
+    pub index: usize,
+    pub timestamp: String,
+}
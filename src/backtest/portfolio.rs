@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{BacktestResult, Contract};
+
+/// How one symbol of a portfolio performed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SymbolPerformance {
+    pub symbol: String,
+    pub contract: Contract,
+    /// Profit net of commission, in the account currency, including the
+    /// open position valued at the last close.
+    pub net_profit: f64,
+    pub trade_count: usize,
+    pub win_rate: Option<f64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PortfolioResult {
+    #[serde(flatten)]
+    pub result: BacktestResult,
+    pub symbols: Vec<SymbolPerformance>,
+    /// Correlation of the returns of each pair of symbols, in the order of
+    /// `symbols`. Undefined when either symbol did not change in value.
+    pub correlation: Vec<Vec<Option<f64>>>,
+}
+
+impl PortfolioResult {
+    /// Summarizes the backtest of a portfolio, given the profit of each
+    /// symbol at every equity point. Returns are the changes in profit
+    /// between equity points.
+    pub fn new(
+        result: BacktestResult,
+        symbols: Vec<(String, Contract)>,
+        profits: &[Vec<f64>],
+    ) -> Self {
+        let returns: Vec<Vec<f64>> = profits
+            .iter()
+            .map(|profits| profits.windows(2).map(|pair| pair[1] - pair[0]).collect())
+            .collect();
+        let correlation = returns
+            .iter()
+            .map(|a| returns.iter().map(|b| correlation(a, b)).collect())
+            .collect();
+
+        let symbols = symbols
+            .into_iter()
+            .zip(profits)
+            .map(|((symbol, contract), profits)| {
+                let pnl: Vec<f64> = result
+                    .trades
+                    .iter()
+                    .filter(|trade| trade.symbol == symbol)
+                    .map(|trade| trade.pnl)
+                    .collect();
+                let wins = pnl.iter().filter(|pnl| **pnl > 0.0).count();
+                SymbolPerformance {
+                    symbol,
+                    contract,
+                    net_profit: profits.last().copied().unwrap_or_default(),
+                    trade_count: pnl.len(),
+                    win_rate: (!pnl.is_empty()).then(|| wins as f64 / pnl.len() as f64 * 100.0),
+                }
+            })
+            .collect();
+
+        PortfolioResult {
+            result,
+            symbols,
+            correlation,
+        }
+    }
+}
+
+/// Pearson correlation coefficient of two series of equal length.
+pub fn correlation(a: &[f64], b: &[f64]) -> Option<f64> {
+    let n = a.len().min(b.len());
+    if n < 2 {
+        return None;
+    }
+    let mean_a = a[..n].iter().sum::<f64>() / n as f64;
+    let mean_b = b[..n].iter().sum::<f64>() / n as f64;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a[..n].iter().zip(&b[..n]) {
+        covariance += (x - mean_a) * (y - mean_b);
+        variance_a += (x - mean_a).powi(2);
+        variance_b += (y - mean_b).powi(2);
+    }
+    (variance_a > 0.0 && variance_b > 0.0).then(|| covariance / (variance_a * variance_b).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::backtest::correlation;
+
+    #[test]
+    fn test_correlation() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+        assert_float_absolute_eq!(correlation(&a, &[2.0, 4.0, 6.0, 8.0]).unwrap(), 1.0, 1e-12);
+        assert_float_absolute_eq!(correlation(&a, &[4.0, 3.0, 2.0, 1.0]).unwrap(), -1.0, 1e-12);
+        assert_float_absolute_eq!(correlation(&a, &[1.0, 3.0, 2.0, 4.0]).unwrap(), 0.8, 1e-12);
+        assert_eq!(correlation(&a, &[5.0, 5.0, 5.0, 5.0]), None);
+        assert_eq!(correlation(&a[..1], &a[..1]), None);
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{Fill, Side};
+
+/// A net position in a symbol, positive when long and negative when short.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub quantity: f32,
+    pub average_price: f32,
+    pub entry_index: usize,
+    pub entry_time: String,
+    /// Commission paid to open the position that is not yet allocated to a
+    /// trade.
+    pub commission: f64,
+}
+
+/// A round trip from opening a position to closing it, or part of it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Trade {
+    pub symbol: String,
+    /// Side of the entry, `buy` for a long trade.
+    pub side: Side,
+    pub quantity: f32,
+    pub entry_index: usize,
+    pub entry_time: String,
+    pub entry_price: f32,
+    pub exit_index: usize,
+    pub exit_time: String,
+    pub exit_price: f32,
+    /// Commission paid on entry and exit, in proportion to the quantity.
+    pub commission: f64,
+    /// Profit net of commission, in the account currency.
+    pub pnl: f64,
+}
+
+impl Position {
+    pub fn is_flat(&self) -> bool {
+        self.quantity == 0.0
+    }
+
+    /// Applies a fill to the position, returning the trade closed by it, if
+    /// any. A fill larger than the position reverses it, opening a new
+    /// position with the remainder at the fill price. The point value
+    /// converts a price move of one unit into the account currency.
+    pub fn apply(&mut self, fill: &Fill, point_value: f64) -> Option<Trade> {
+        let change = fill.side.sign() * fill.quantity;
+        if self.is_flat() || self.quantity.signum() == change.signum() {
+            if self.is_flat() {
+                self.entry_index = fill.index;
+                self.entry_time = fill.timestamp.clone();
+            }
+            let quantity = self.quantity + change;
+            self.average_price = (self.average_price * self.quantity.abs()
+                + fill.price * fill.quantity)
+                / quantity.abs();
+            self.quantity = quantity;
+            self.commission += fill.commission;
+            return None;
+        }
+
+        let closed = fill.quantity.min(self.quantity.abs());
+        let direction = self.quantity.signum();
+        let entry_commission = self.commission * closed as f64 / self.quantity.abs() as f64;
+        let exit_commission = fill.commission * closed as f64 / fill.quantity as f64;
+        let commission = entry_commission + exit_commission;
+        let trade = Trade {
+            symbol: fill.symbol.clone(),
+            side: if direction > 0.0 {
+                Side::Buy
+            } else {
+                Side::Sell
+            },
+            quantity: closed,
+            entry_index: self.entry_index,
+            entry_time: self.entry_time.clone(),
+            entry_price: self.average_price,
+            exit_index: fill.index,
+            exit_time: fill.timestamp.clone(),
+            exit_price: fill.price,
+            commission,
+            pnl: (direction * (fill.price - self.average_price) * closed) as f64 * point_value
+                - commission,
+        };
+
+        let remainder = fill.quantity - closed;
+        if remainder > 0.0 {
+            *self = Position {
+                quantity: fill.side.sign() * remainder,
+                average_price: fill.price,
+                entry_index: fill.index,
+                entry_time: fill.timestamp.clone(),
+                commission: fill.commission - exit_commission,
+            };
+        } else {
+            self.quantity += change;
+            self.commission -= entry_commission;
+            if self.is_flat() {
+                *self = Position::default();
+            }
+        }
+        Some(trade)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtest::{Fill, Position, Side};
+
+    fn fill(side: Side, quantity: f32, price: f32, index: usize) -> Fill {
+        Fill {
+            order_id: index as u64,
+            symbol: "ES".to_string(),
+            side,
+            quantity,
+            price,
+            commission: quantity as f64,
+            index,
+            timestamp: format!("2023-01-01T00:{:02}:00Z", index),
+        }
+    }
+
+    #[test]
+    fn test_apply_fills() {
+        let mut position = Position::default();
+        assert_eq!(position.apply(&fill(Side::Buy, 1.0, 100.0, 0), 1.0), None);
+        assert_eq!(position.apply(&fill(Side::Buy, 3.0, 104.0, 1), 1.0), None);
+        assert_eq!(position.quantity, 4.0);
+        assert_eq!(position.average_price, 103.0);
+
+        assert_eq!(position.commission, 4.0);
+
+        let trade = position
+            .apply(&fill(Side::Sell, 1.0, 105.0, 2), 1.0)
+            .unwrap();
+        assert_eq!(trade.side, Side::Buy);
+        assert_eq!(trade.entry_index, 0);
+        assert_eq!(trade.commission, 2.0);
+        assert_eq!(trade.pnl, 0.0);
+        assert_eq!(position.quantity, 3.0);
+
+        let trade = position
+            .apply(&fill(Side::Sell, 5.0, 101.0, 3), 1.0)
+            .unwrap();
+        assert_eq!(trade.quantity, 3.0);
+        assert_eq!(trade.commission, 6.0);
+        assert_eq!(trade.pnl, -12.0);
+        assert_eq!(position.quantity, -2.0);
+        assert_eq!(position.average_price, 101.0);
+        assert_eq!(position.entry_index, 3);
+        assert_eq!(position.commission, 2.0);
+
+        let trade = position
+            .apply(&fill(Side::Buy, 2.0, 100.0, 4), 1.0)
+            .unwrap();
+        assert_eq!(trade.side, Side::Sell);
+        assert_eq!(trade.pnl, -2.0);
+        assert!(position.is_flat());
+    }
+}
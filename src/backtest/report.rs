@@ -0,0 +1,696 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{BacktestResult, EquityPoint, Rejection, Side, Trade};
+use crate::domain::{Bar, parse_timestamp};
+
+/// Trading days in a year, used to annualize daily returns.
+const TRADING_DAYS: f64 = 252.0;
+
+/// The deepest decline of the equity from a peak.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Drawdown {
+    pub amount: f64,
+    /// Decline as a percentage of the peak equity.
+    pub percent: f64,
+    pub peak: String,
+    pub trough: String,
+    /// When the equity regained the peak, if it did.
+    pub recovery: Option<String>,
+    /// Bars or ticks from the peak to the recovery, or to the end of the
+    /// replay when the equity did not recover.
+    pub duration: usize,
+}
+
+/// A closed trade with its maximum adverse and favourable excursions, the
+/// furthest price moved against and in favour of the trade while it was open.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TradeExcursion {
+    #[serde(flatten)]
+    pub trade: Trade,
+    pub mae: f32,
+    pub mfe: f32,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MonthlyReturn {
+    pub year: i32,
+    pub month: u32,
+    pub percent: f64,
+}
+
+/// Performance report of a backtest
+///
+/// Returns are in percent. Sharpe and Sortino ratios annualize the returns
+/// between the closing equity of consecutive days, assuming a risk-free rate
+/// of zero. Ratios that would divide by zero are omitted.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    pub initial_capital: f64,
+    pub final_equity: f64,
+    pub net_profit: f64,
+    pub total_return: f64,
+    pub cagr: Option<f64>,
+    pub sharpe: Option<f64>,
+    pub sortino: Option<f64>,
+    /// CAGR over the maximum drawdown.
+    pub calmar: Option<f64>,
+    pub max_drawdown: Option<Drawdown>,
+    /// Longest time under water, in bars or ticks.
+    pub max_drawdown_duration: usize,
+    pub trade_count: usize,
+    pub win_rate: Option<f64>,
+    /// Gross profit over gross loss.
+    pub profit_factor: Option<f64>,
+    /// Average net profit per trade.
+    pub expectancy: Option<f64>,
+    pub average_win: Option<f64>,
+    pub average_loss: Option<f64>,
+    /// Percentage of bars or ticks with an open position.
+    pub exposure: f64,
+    pub monthly_returns: Vec<MonthlyReturn>,
+    pub trades: Vec<TradeExcursion>,
+    pub equity: Vec<EquityPoint>,
+    /// Orders the risk manager rejected, with their reasons.
+    pub rejections: Vec<Rejection>,
+}
+
+impl Report {
+    /// Reports on a backtest of the given bars. Excursions are measured on
+    /// the range of the bars a trade was open; without bars, e.g. for a tick
+    /// replay, they are measured on the entry and exit prices.
+    pub fn new(result: &BacktestResult, data: &[Bar]) -> Self {
+        let initial_capital = result.initial_capital;
+        let final_equity = result.final_equity();
+        let net_profit = final_equity - initial_capital;
+        let total_return = percent(final_equity, initial_capital);
+
+        let cagr = years(&result.equity)
+            .filter(|years| *years > 0.0 && final_equity > 0.0)
+            .map(|years| ((final_equity / initial_capital).powf(1.0 / years) - 1.0) * 100.0);
+        let returns = daily_returns(initial_capital, &result.equity);
+        let (max_drawdown, max_drawdown_duration) = drawdowns(initial_capital, &result.equity);
+        let calmar = match (cagr, &max_drawdown) {
+            (Some(cagr), Some(drawdown)) if drawdown.percent > 0.0 => Some(cagr / drawdown.percent),
+            _ => None,
+        };
+
+        let pnl: Vec<f64> = result.trades.iter().map(|trade| trade.pnl).collect();
+        let wins: Vec<f64> = pnl.iter().copied().filter(|pnl| *pnl > 0.0).collect();
+        let losses: Vec<f64> = pnl.iter().copied().filter(|pnl| *pnl < 0.0).collect();
+        let gross_loss = -losses.iter().sum::<f64>();
+
+        Report {
+            initial_capital,
+            final_equity,
+            net_profit,
+            total_return,
+            cagr,
+            sharpe: sharpe(&returns),
+            sortino: sortino(&returns),
+            calmar,
+            max_drawdown,
+            max_drawdown_duration,
+            trade_count: pnl.len(),
+            win_rate: (!pnl.is_empty()).then(|| wins.len() as f64 / pnl.len() as f64 * 100.0),
+            profit_factor: (gross_loss > 0.0).then(|| wins.iter().sum::<f64>() / gross_loss),
+            expectancy: mean(&pnl),
+            average_win: mean(&wins),
+            average_loss: mean(&losses),
+            exposure: exposure(result),
+            monthly_returns: monthly_returns(initial_capital, &result.equity),
+            trades: result
+                .trades
+                .iter()
+                .map(|trade| excursion(trade, data))
+                .collect(),
+            equity: result.equity.clone(),
+            rejections: result.rejections.clone(),
+        }
+    }
+
+    /// A self-contained HTML page with the metrics, the equity curve, the
+    /// monthly returns, the trades and any rejected orders.
+    pub fn to_html(&self, title: &str) -> String {
+        let mut html = String::new();
+        let title = escape(title);
+        let _ = write!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+        );
+
+        let drawdown = self.max_drawdown.as_ref();
+        let metrics = [
+            ("Initial capital", money(self.initial_capital)),
+            ("Final equity", money(self.final_equity)),
+            ("Net profit", money(self.net_profit)),
+            ("Total return", format!("{:.2}%", self.total_return)),
+            ("CAGR", optional(self.cagr, "%")),
+            ("Sharpe ratio", optional(self.sharpe, "")),
+            ("Sortino ratio", optional(self.sortino, "")),
+            ("Calmar ratio", optional(self.calmar, "")),
+            (
+                "Max drawdown",
+                drawdown.map_or("-".to_string(), |drawdown| {
+                    format!("{} ({:.2}%)", money(-drawdown.amount), -drawdown.percent)
+                }),
+            ),
+            (
+                "Max drawdown duration",
+                format!("{} bars", self.max_drawdown_duration),
+            ),
+            ("Trades", self.trade_count.to_string()),
+            ("Win rate", optional(self.win_rate, "%")),
+            ("Profit factor", optional(self.profit_factor, "")),
+            ("Expectancy", optional(self.expectancy, "")),
+            ("Average win", optional(self.average_win, "")),
+            ("Average loss", optional(self.average_loss, "")),
+            ("Exposure", format!("{:.2}%", self.exposure)),
+            ("Rejected orders", self.rejections.len().to_string()),
+        ];
+        html.push_str("<h2>Summary</h2>\n<table class=\"metrics\">\n");
+        for (name, value) in metrics {
+            let _ = writeln!(html, "<tr><th>{name}</th><td>{value}</td></tr>");
+        }
+        html.push_str("</table>\n");
+
+        html.push_str("<h2>Equity</h2>\n");
+        html.push_str(&equity_chart(&self.equity));
+
+        html.push_str("<h2>Monthly returns</h2>\n<table>\n<tr><th>Year</th>");
+        for month in MONTHS {
+            let _ = write!(html, "<th>{month}</th>");
+        }
+        html.push_str("<th>Year</th></tr>\n");
+        let mut years: BTreeMap<i32, [Option<f64>; 12]> = BTreeMap::new();
+        for monthly in &self.monthly_returns {
+            years.entry(monthly.year).or_default()[monthly.month as usize - 1] =
+                Some(monthly.percent);
+        }
+        for (year, months) in years {
+            let _ = write!(html, "<tr><th>{year}</th>");
+            for percent in months {
+                html.push_str(&return_cell(percent));
+            }
+            let compounded = months
+                .iter()
+                .flatten()
+                .fold(1.0, |total, percent| total * (1.0 + percent / 100.0));
+            html.push_str(&return_cell(Some((compounded - 1.0) * 100.0)));
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+
+        html.push_str(
+            "<h2>Trades</h2>\n<table>\n<tr><th>Side</th><th>Quantity</th><th>Entry</th>\
+             <th>Entry price</th><th>Exit</th><th>Exit price</th><th>Commission</th>\
+             <th>MAE</th><th>MFE</th><th>P&amp;L</th></tr>\n",
+        );
+        for excursion in &self.trades {
+            let trade = &excursion.trade;
+            let side = match trade.side {
+                Side::Buy => "Long",
+                Side::Sell => "Short",
+            };
+            let _ = writeln!(
+                html,
+                "<tr><td>{side}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td>\
+                 <td>{:.2}</td><td>{}</td><td>{}</td>{}</tr>",
+                trade.quantity,
+                escape(&trade.entry_time),
+                trade.entry_price,
+                escape(&trade.exit_time),
+                trade.exit_price,
+                trade.commission,
+                excursion.mae,
+                excursion.mfe,
+                money_cell(trade.pnl),
+            );
+        }
+        html.push_str("</table>\n");
+
+        if !self.rejections.is_empty() {
+            html.push_str(
+                "<h2>Rejected orders</h2>\n<table>\n<tr><th>Time</th><th>Symbol</th>\
+                 <th>Side</th><th>Quantity</th><th>Reason</th></tr>\n",
+            );
+            for rejection in &self.rejections {
+                let _ = writeln!(
+                    html,
+                    "<tr><td>{}</td><td>{}</td><td>{:?}</td><td>{}</td><td>{}</td></tr>",
+                    escape(&rejection.timestamp),
+                    escape(&rejection.symbol),
+                    rejection.side,
+                    rejection.quantity,
+                    escape(&rejection.reason),
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+    table{border-collapse:collapse;margin-bottom:2em;font-size:0.9em}\
+    th,td{border:1px solid #ddd;padding:4px 8px;text-align:right}\
+    table.metrics th{text-align:left}\
+    .gain{color:#1a7f37}.loss{color:#cf222e}\
+    svg{background:#fafafa;border:1px solid #ddd;margin-bottom:2em}";
+
+fn percent(value: f64, base: f64) -> f64 {
+    (value / base - 1.0) * 100.0
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+}
+
+/// Years between the first and the last equity point.
+fn years(equity: &[EquityPoint]) -> Option<f64> {
+    let first = parse_timestamp(&equity.first()?.timestamp)?;
+    let last = parse_timestamp(&equity.last()?.timestamp)?;
+    Some((last - first).num_seconds() as f64 / (365.25 * 86400.0))
+}
+
+/// Closing equity of every period, keyed by the period a timestamp falls in.
+fn closing_equity<K: Ord>(
+    equity: &[EquityPoint],
+    period: impl Fn(NaiveDate) -> K,
+) -> BTreeMap<K, f64> {
+    equity
+        .iter()
+        .filter_map(|point| {
+            parse_timestamp(&point.timestamp).map(|time| (period(time.date()), point.equity))
+        })
+        .collect()
+}
+
+/// Returns between the closing equity of consecutive days, the first day
+/// measured from the initial capital.
+fn daily_returns(initial_capital: f64, equity: &[EquityPoint]) -> Vec<f64> {
+    let mut previous = initial_capital;
+    closing_equity(equity, |date| date)
+        .into_values()
+        .map(|close| {
+            let change = close / previous - 1.0;
+            previous = close;
+            change
+        })
+        .collect()
+}
+
+fn monthly_returns(initial_capital: f64, equity: &[EquityPoint]) -> Vec<MonthlyReturn> {
+    let mut previous = initial_capital;
+    closing_equity(equity, |date| (date.year(), date.month()))
+        .into_iter()
+        .map(|((year, month), close)| {
+            let percent = percent(close, previous);
+            previous = close;
+            MonthlyReturn {
+                year,
+                month,
+                percent,
+            }
+        })
+        .collect()
+}
+
+fn sharpe(returns: &[f64]) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = mean(returns)?;
+    let variance =
+        returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() as f64 - 1.0);
+    let deviation = variance.sqrt();
+    (deviation > 0.0).then(|| mean / deviation * TRADING_DAYS.sqrt())
+}
+
+/// Like the Sharpe ratio, but only penalizes the deviation of losing days.
+fn sortino(returns: &[f64]) -> Option<f64> {
+    if returns.len() < 2 {
+        return None;
+    }
+    let mean = mean(returns)?;
+    let downside = returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / returns.len() as f64;
+    let deviation = downside.sqrt();
+    (deviation > 0.0).then(|| mean / deviation * TRADING_DAYS.sqrt())
+}
+
+/// The deepest drawdown and the longest time under water.
+fn drawdowns(initial_capital: f64, equity: &[EquityPoint]) -> (Option<Drawdown>, usize) {
+    let mut max_drawdown: Option<Drawdown> = None;
+    let mut longest = 0;
+    let mut peak = initial_capital;
+    let mut peak_at = 0;
+    let mut current: Option<Drawdown> = None;
+
+    for (i, point) in equity.iter().enumerate() {
+        if point.equity >= peak {
+            if let Some(mut drawdown) = current.take() {
+                drawdown.recovery = Some(point.timestamp.clone());
+                drawdown.duration = i - peak_at;
+                settle(&mut max_drawdown, drawdown);
+                longest = longest.max(i - peak_at);
+            }
+            peak = point.equity;
+            peak_at = i;
+            continue;
+        }
+
+        let amount = peak - point.equity;
+        let drawdown = current.get_or_insert_with(|| Drawdown {
+            amount: 0.0,
+            percent: 0.0,
+            peak: equity[peak_at].timestamp.clone(),
+            trough: point.timestamp.clone(),
+            recovery: None,
+            duration: 0,
+        });
+        if amount > drawdown.amount {
+            drawdown.amount = amount;
+            drawdown.percent = amount / peak * 100.0;
+            drawdown.trough = point.timestamp.clone();
+        }
+    }
+
+    if let Some(mut drawdown) = current {
+        drawdown.duration = equity.len() - 1 - peak_at;
+        longest = longest.max(drawdown.duration);
+        settle(&mut max_drawdown, drawdown);
+    }
+    (max_drawdown, longest)
+}
+
+/// Keeps the deeper of two drawdowns.
+fn settle(max_drawdown: &mut Option<Drawdown>, drawdown: Drawdown) {
+    if max_drawdown
+        .as_ref()
+        .is_none_or(|max| drawdown.percent > max.percent)
+    {
+        *max_drawdown = Some(drawdown);
+    }
+}
+
+/// Percentage of the bars or ticks on which a position was held, from the
+/// fill that opened it up to the fill that closed it. Fills are placed on
+/// the equity points by index, or by time when the equity points are not
+/// those of the bars of the fills, as in a portfolio.
+fn exposure(result: &BacktestResult) -> f64 {
+    let count = result.equity.len();
+    if count == 0 {
+        return 0.0;
+    }
+    let mut exposed = vec![false; count];
+    let held = result
+        .trades
+        .iter()
+        .map(|trade| {
+            (
+                point(result, trade.entry_index, &trade.entry_time),
+                point(result, trade.exit_index, &trade.exit_time),
+            )
+        })
+        .chain(result.positions.values().map(|position| {
+            (
+                point(result, position.entry_index, &position.entry_time),
+                count,
+            )
+        }));
+    for (entry, exit) in held {
+        for flag in exposed.iter_mut().take(exit).skip(entry) {
+            *flag = true;
+        }
+    }
+    exposed.iter().filter(|flag| **flag).count() as f64 / count as f64 * 100.0
+}
+
+/// Index of the equity point of a fill.
+fn point(result: &BacktestResult, index: usize, timestamp: &str) -> usize {
+    match result.equity.get(index) {
+        Some(point) if point.timestamp == timestamp => index,
+        _ => result
+            .equity
+            .partition_point(|point| point.timestamp.as_str() < timestamp),
+    }
+}
+
+fn excursion(trade: &Trade, data: &[Bar]) -> TradeExcursion {
+    let (low, high) = data
+        .get(trade.entry_index..=trade.exit_index)
+        .unwrap_or_default()
+        .iter()
+        .fold(
+            (
+                trade.entry_price.min(trade.exit_price),
+                trade.entry_price.max(trade.exit_price),
+            ),
+            |(low, high), bar| (low.min(bar.low), high.max(bar.high)),
+        );
+    let (mae, mfe) = match trade.side {
+        Side::Buy => (trade.entry_price - low, high - trade.entry_price),
+        Side::Sell => (high - trade.entry_price, trade.entry_price - low),
+    };
+    TradeExcursion {
+        trade: trade.clone(),
+        mae,
+        mfe,
+    }
+}
+
+/// An SVG line chart of the equity, shading the drawdowns below it.
+fn equity_chart(equity: &[EquityPoint]) -> String {
+    const WIDTH: f64 = 900.0;
+    const HEIGHT: f64 = 300.0;
+    if equity.len() < 2 {
+        return "<p>Not enough equity points to chart.</p>\n".to_string();
+    }
+
+    let (low, high) = equity
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(low, high), point| {
+            (low.min(point.equity), high.max(point.equity))
+        });
+    let range = if high > low { high - low } else { 1.0 };
+    let x = |i: usize| i as f64 / (equity.len() - 1) as f64 * WIDTH;
+    let y = |value: f64| HEIGHT - (value - low) / range * HEIGHT;
+
+    let mut line = String::new();
+    let mut peaks = String::new();
+    let mut peak = f64::MIN;
+    for (i, point) in equity.iter().enumerate() {
+        peak = peak.max(point.equity);
+        let _ = write!(line, "{:.1},{:.1} ", x(i), y(point.equity));
+        let _ = write!(peaks, "{:.1},{:.1} ", x(i), y(peak));
+    }
+    let troughs: String = equity
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(i, point)| format!("{:.1},{:.1} ", x(i), y(point.equity)))
+        .collect();
+
+    format!(
+        "<svg viewBox=\"0 0 {WIDTH} {HEIGHT}\" width=\"{WIDTH}\" height=\"{HEIGHT}\">\n\
+         <polygon points=\"{peaks}{troughs}\" fill=\"#cf222e\" fill-opacity=\"0.2\"/>\n\
+         <polyline points=\"{line}\" fill=\"none\" stroke=\"#0969da\" stroke-width=\"1.5\"/>\n\
+         </svg>\n<p>{} to {}, equity {} to {}</p>\n",
+        escape(&equity[0].timestamp),
+        escape(&equity[equity.len() - 1].timestamp),
+        money(low),
+        money(high),
+    )
+}
+
+fn money(value: f64) -> String {
+    format!("{:.2}", value)
+}
+
+fn money_cell(value: f64) -> String {
+    let class = if value < 0.0 { "loss" } else { "gain" };
+    format!("<td class=\"{class}\">{}</td>", money(value))
+}
+
+fn return_cell(percent: Option<f64>) -> String {
+    match percent {
+        Some(percent) if percent < 0.0 => format!("<td class=\"loss\">{:.2}%</td>", percent),
+        Some(percent) => format!("<td class=\"gain\">{:.2}%</td>", percent),
+        None => "<td></td>".to_string(),
+    }
+}
+
+fn optional(value: Option<f64>, unit: &str) -> String {
+    value.map_or("-".to_string(), |value| format!("{:.2}{}", value, unit))
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::backtest::{BacktestResult, EquityPoint, Report, Side, Trade};
+    use crate::domain::Bar;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    /// Dates of the equity points of the sample result.
+    const DATES: [&str; 5] = [
+        "2023-01-30",
+        "2023-01-31",
+        "2023-02-01",
+        "2023-02-02",
+        "2023-02-03",
+    ];
+
+    fn trade(side: Side, entry: (usize, f32), exit: (usize, f32), pnl: f64) -> Trade {
+        Trade {
+            symbol: "ES".to_string(),
+            side,
+            quantity: 1.0,
+            entry_index: entry.0,
+            entry_time: format!("{}T21:00:00Z", DATES[entry.0]),
+            entry_price: entry.1,
+            exit_index: exit.0,
+            exit_time: format!("{}T21:00:00Z", DATES[exit.0]),
+            exit_price: exit.1,
+            commission: 0.0,
+            pnl,
+        }
+    }
+
+    fn sample_result() -> BacktestResult {
+        let equity = DATES
+            .iter()
+            .zip([10100.0, 10200.0, 9690.0, 9900.0, 10300.0])
+            .enumerate()
+            .map(|(index, (date, equity))| EquityPoint {
+                index,
+                timestamp: format!("{}T21:00:00Z", date),
+                equity,
+            })
+            .collect();
+
+        BacktestResult {
+            initial_capital: 10000.0,
+            fills: Vec::new(),
+            trades: vec![
+                trade(Side::Buy, (0, 100.0), (2, 104.0), 400.0),
+                trade(Side::Sell, (3, 104.0), (4, 105.0), -100.0),
+            ],
+            equity,
+            positions: BTreeMap::new(),
+            rejections: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_report() {
+        let report = Report::new(&sample_result(), &sample_bars());
+
+        assert_float_absolute_eq!(report.net_profit, 300.0, 1e-9);
+        assert_float_absolute_eq!(report.total_return, 3.0, 1e-9);
+        let drawdown = report.max_drawdown.clone().unwrap();
+        assert_float_absolute_eq!(drawdown.amount, 510.0, 1e-9);
+        assert_float_absolute_eq!(drawdown.percent, 5.0, 1e-9);
+        assert_eq!(drawdown.peak, "2023-01-31T21:00:00Z");
+        assert_eq!(drawdown.recovery.as_deref(), Some("2023-02-03T21:00:00Z"));
+        assert_eq!(report.max_drawdown_duration, 3);
+        assert!(report.sharpe.unwrap() > 0.0);
+        assert!(report.sortino.unwrap() > report.sharpe.unwrap());
+        assert!(report.cagr.unwrap() > 100.0);
+        assert_float_absolute_eq!(report.calmar.unwrap(), report.cagr.unwrap() / 5.0, 1e-9);
+
+        assert_eq!(report.trade_count, 2);
+        assert_eq!(report.win_rate, Some(50.0));
+        assert_eq!(report.profit_factor, Some(4.0));
+        assert_eq!(report.expectancy, Some(150.0));
+        assert_float_absolute_eq!(report.exposure, 60.0, 1e-9);
+        assert_eq!(report.trades[0].mae, 5.0);
+        assert_eq!(report.trades[0].mfe, 7.0);
+        assert_eq!(report.trades[1].mae, 4.0);
+        assert_eq!(report.trades[1].mfe, 4.0);
+
+        let months: Vec<(u32, f64)> = report
+            .monthly_returns
+            .iter()
+            .map(|monthly| (monthly.month, monthly.percent))
+            .collect();
+        assert_eq!(months.len(), 2);
+        assert_float_absolute_eq!(months[0].1, 2.0, 1e-9);
+        assert_float_absolute_eq!(months[1].1, 100.0 / 102.0, 1e-9);
+    }
+
+    #[test]
+    fn test_portfolio_exposure() {
+        // The indices of a portfolio are those of the bars of each symbol,
+        // here of a symbol trading from the third equity point
+        let mut result = sample_result();
+        result.trades = vec![Trade {
+            entry_index: 0,
+            exit_index: 1,
+            ..trade(Side::Buy, (2, 100.0), (3, 101.0), 100.0)
+        }];
+        let report = Report::new(&result, &[]);
+        assert_float_absolute_eq!(report.exposure, 20.0, 1e-9);
+    }
+
+    #[test]
+    fn test_report_html() {
+        let report = Report::new(&sample_result(), &[]);
+        assert_eq!(report.trades[0].mae, 0.0);
+        assert_eq!(report.trades[0].mfe, 4.0);
+
+        let html = report.to_html("ES <AMA>");
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<title>ES &lt;AMA&gt;</title>"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("<td class=\"loss\">-100.00</td>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+}
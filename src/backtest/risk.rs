@@ -0,0 +1,364 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{Account, Side, Sizing};
+use crate::domain::{Bar, Tick};
+
+/// An order the risk manager blocked, and why.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rejection {
+    /// Index of the bar or tick on which the order was submitted.
+    pub index: usize,
+    pub timestamp: String,
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f32,
+    pub reason: String,
+}
+
+/// Position sizing and pre-trade risk checks
+///
+/// Follows the prices of the traded symbols and the equity of the account,
+/// and checks every order before it is queued. Orders are rejected when too
+/// many orders are already open, and orders that would increase the exposure
+/// of the account are rejected when they would take the exposure of their
+/// symbol, or the gross exposure of the account, above a percentage of the
+/// equity. Exposure counts open orders as if they had filled.
+///
+/// Once the equity falls by the daily loss limit from where it stood at the
+/// first event of a day, trading halts until the next day: only orders that
+/// reduce the exposure are accepted. Days are the dates of the timestamps.
+///
+/// Rejections are logged and kept with their reasons for auditing.
+#[derive(Clone, Debug, Default)]
+pub struct RiskManager {
+    sizing: Option<Sizing>,
+    max_symbol_exposure: Option<f64>,
+    max_total_exposure: Option<f64>,
+    daily_loss_limit: Option<f64>,
+    max_open_orders: Option<usize>,
+    prices: BTreeMap<String, f32>,
+    /// Latest true ranges, or absolute changes between ticks, of each symbol.
+    ranges: BTreeMap<String, VecDeque<f32>>,
+    timestamp: String,
+    day: String,
+    day_start_equity: f64,
+    halted: bool,
+    pub rejections: Vec<Rejection>,
+}
+
+impl RiskManager {
+    pub fn new() -> Self {
+        RiskManager::default()
+    }
+
+    pub fn with_sizing(mut self, sizing: Sizing) -> Self {
+        self.sizing = Some(sizing);
+        self
+    }
+
+    /// Largest exposure of a symbol, as a percentage of the equity.
+    pub fn with_max_symbol_exposure(mut self, percent: f64) -> Self {
+        self.max_symbol_exposure = Some(percent);
+        self
+    }
+
+    /// Largest gross exposure of the account, as a percentage of the equity.
+    pub fn with_max_total_exposure(mut self, percent: f64) -> Self {
+        self.max_total_exposure = Some(percent);
+        self
+    }
+
+    /// Loss in a day, as a percentage of the equity at its start, at which
+    /// trading halts.
+    pub fn with_daily_loss_limit(mut self, percent: f64) -> Self {
+        self.daily_loss_limit = Some(percent);
+        self
+    }
+
+    pub fn with_max_open_orders(mut self, count: usize) -> Self {
+        self.max_open_orders = Some(count);
+        self
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    /// Follows a bar of a symbol. Call on every bar before the strategy.
+    pub fn on_bar(&mut self, account: &Account, symbol: &str, bar: &Bar) {
+        let range = match self.prices.get(symbol) {
+            Some(close) => bar.high.max(*close) - bar.low.min(*close),
+            None => bar.high - bar.low,
+        };
+        self.update(account, symbol, bar.close, range, &bar.timestamp);
+    }
+
+    /// Follows a tick. Call on every tick before the strategy.
+    pub fn on_tick(&mut self, account: &Account, tick: &Tick) {
+        if let Some(previous) = self.prices.get(&tick.symbol) {
+            let change = (tick.price - previous).abs();
+            self.update(account, &tick.symbol, tick.price, change, &tick.timestamp);
+        } else {
+            self.prices.insert(tick.symbol.clone(), tick.price);
+            self.mark(account, &tick.timestamp);
+        }
+    }
+
+    fn update(&mut self, account: &Account, symbol: &str, price: f32, range: f32, timestamp: &str) {
+        self.prices.insert(symbol.to_string(), price);
+        if let Some(period) = self.sizing.and_then(|sizing| sizing.period()) {
+            let ranges = self.ranges.entry(symbol.to_string()).or_default();
+            ranges.push_back(range);
+            if ranges.len() > period {
+                ranges.pop_front();
+            }
+        }
+        self.mark(account, timestamp);
+    }
+
+    /// Starts a new day on the first event of a date, and halts trading once
+    /// the daily loss limit is reached.
+    fn mark(&mut self, account: &Account, timestamp: &str) {
+        self.timestamp = timestamp.to_string();
+        let equity = account.equity(&self.prices);
+        let day = timestamp.get(..10).unwrap_or(timestamp);
+        if day != self.day {
+            self.day = day.to_string();
+            self.day_start_equity = equity;
+            self.halted = false;
+        }
+        if let Some(limit) = self.daily_loss_limit
+            && !self.halted
+            && equity <= self.day_start_equity * (1.0 - limit / 100.0)
+        {
+            self.halted = true;
+            tracing::warn!(
+                "trading halted on {}: equity of {:.2} is down {}% or more from {:.2}",
+                self.day,
+                equity,
+                limit,
+                self.day_start_equity
+            );
+        }
+    }
+
+    /// Average true range of the symbol over the period of the sizing, or
+    /// the mean absolute change between its ticks.
+    pub fn volatility(&self, symbol: &str) -> Option<f32> {
+        let period = self.sizing?.period()?;
+        self.ranges
+            .get(symbol)
+            .filter(|ranges| ranges.len() == period)
+            .map(|ranges| ranges.iter().sum::<f32>() / period as f32)
+    }
+
+    /// Units of the symbol to hold according to the sizing, if there is one.
+    pub fn size(&self, account: &Account, symbol: &str) -> Option<f32> {
+        let sizing = self.sizing?;
+        let price = *self.prices.get(symbol)?;
+        Some(sizing.quantity(
+            account.equity(&self.prices),
+            price,
+            account.contract(symbol).point_value(),
+            self.volatility(symbol),
+            &account.trades,
+        ))
+    }
+
+    /// Checks an order before it is queued, recording and logging it when
+    /// it is rejected.
+    pub fn check(
+        &mut self,
+        account: &Account,
+        symbol: &str,
+        side: Side,
+        quantity: f32,
+        index: usize,
+    ) -> Result<(), String> {
+        self.reason(account, symbol, side, quantity)
+            .map_err(|reason| {
+                tracing::warn!(
+                    "rejected {:?} order for {} {} at {}: {}",
+                    side,
+                    quantity,
+                    symbol,
+                    self.timestamp,
+                    reason
+                );
+                self.rejections.push(Rejection {
+                    index,
+                    timestamp: self.timestamp.clone(),
+                    symbol: symbol.to_string(),
+                    side,
+                    quantity,
+                    reason: reason.clone(),
+                });
+                reason
+            })
+    }
+
+    fn reason(
+        &self,
+        account: &Account,
+        symbol: &str,
+        side: Side,
+        quantity: f32,
+    ) -> Result<(), String> {
+        if !quantity.is_finite() || quantity <= 0.0 {
+            return Err("quantity must be a positive number".to_string());
+        }
+        if let Some(max) = self.max_open_orders
+            && account.orders.len() >= max
+        {
+            return Err(format!(
+                "{} orders are open, at most {} are allowed",
+                account.orders.len(),
+                max
+            ));
+        }
+
+        // Exposure in units, counting open orders as filled
+        let mut exposure: BTreeMap<&str, f32> = account
+            .positions
+            .iter()
+            .map(|(symbol, position)| (symbol.as_str(), position.quantity))
+            .collect();
+        for order in &account.orders {
+            *exposure.entry(&order.symbol).or_default() += order.side.sign() * order.quantity;
+        }
+        let before = exposure.get(symbol).copied().unwrap_or_default();
+        let after = before + side.sign() * quantity;
+        if after.abs() <= before.abs() {
+            return Ok(());
+        }
+        if self.halted {
+            return Err(format!(
+                "trading is halted for the day after the daily loss limit of {}% was reached",
+                self.daily_loss_limit.unwrap_or_default()
+            ));
+        }
+        exposure.insert(symbol, after);
+
+        let equity = account.equity(&self.prices);
+        let value = |symbol: &str, quantity: f32| -> Result<f64, String> {
+            let price = self
+                .prices
+                .get(symbol)
+                .ok_or_else(|| format!("no price to value the exposure of {}", symbol))?;
+            Ok((quantity * price).abs() as f64 * account.contract(symbol).point_value())
+        };
+        if let Some(max) = self.max_symbol_exposure {
+            let value = value(symbol, after)?;
+            if value > equity * max / 100.0 {
+                return Err(format!(
+                    "exposure of {} would be {:.2}, above {}% of the equity of {:.2}",
+                    symbol, value, max, equity
+                ));
+            }
+        }
+        if let Some(max) = self.max_total_exposure {
+            let mut total = 0.0;
+            for (symbol, quantity) in exposure {
+                total += value(symbol, quantity)?;
+            }
+            if total > equity * max / 100.0 {
+                return Err(format!(
+                    "gross exposure would be {:.2}, above {}% of the equity of {:.2}",
+                    total, max, equity
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtest::{Account, Contract, OrderType, RiskManager, Side, Sizing};
+    use crate::domain::Bar;
+
+    fn bar(timestamp: &str, close: f32) -> Bar {
+        Bar {
+            timestamp: timestamp.to_string(),
+            open: close,
+            high: close + 1.0,
+            low: close - 1.0,
+            close,
+            volume: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_exposure_caps() {
+        let mut account = Account::new(10000.0);
+        account.contracts.insert(
+            "ES".to_string(),
+            Contract {
+                multiplier: 50.0,
+                ..Contract::default()
+            },
+        );
+        let mut risk = RiskManager::new()
+            .with_max_symbol_exposure(100.0)
+            .with_max_total_exposure(150.0)
+            .with_max_open_orders(2);
+        risk.on_bar(&account, "ES", &bar("2023-01-02T10:00:00Z", 100.0));
+        risk.on_bar(&account, "NQ", &bar("2023-01-02T10:00:00Z", 1000.0));
+
+        // 2 ES are worth 10000, 3 are worth 15000
+        assert!(risk.check(&account, "ES", Side::Buy, 2.0, 0).is_ok());
+        account.submit("ES", Side::Buy, 2.0, OrderType::Market, 0);
+        assert!(risk.check(&account, "ES", Side::Buy, 1.0, 0).is_err());
+        // Reducing the exposure is always allowed
+        assert!(risk.check(&account, "ES", Side::Sell, 3.0, 0).is_ok());
+        assert!(risk.check(&account, "NQ", Side::Sell, 5.0, 0).is_ok());
+        assert!(risk.check(&account, "NQ", Side::Sell, 6.0, 0).is_err());
+        account.submit("NQ", Side::Sell, 5.0, OrderType::Market, 0);
+        assert!(risk.check(&account, "NQ", Side::Buy, 1.0, 0).is_err());
+
+        assert_eq!(risk.rejections.len(), 3);
+        assert_eq!(risk.rejections[0].symbol, "ES");
+        assert!(risk.rejections[0].reason.contains("exposure of ES"));
+        assert!(risk.rejections[1].reason.contains("gross exposure"));
+        assert!(risk.rejections[2].reason.contains("2 orders are open"));
+        assert_eq!(risk.rejections[2].timestamp, "2023-01-02T10:00:00Z");
+    }
+
+    #[test]
+    fn test_daily_loss_limit() {
+        let mut account = Account::new(10000.0);
+        account.submit("ES", Side::Buy, 100.0, OrderType::Market, 0);
+        let order = account.orders.remove(0);
+        account.fill(&order, 100.0, 0.0, 0, "2023-01-02T10:00:00Z");
+        let mut risk = RiskManager::new().with_daily_loss_limit(5.0);
+
+        risk.on_bar(&account, "ES", &bar("2023-01-02T10:00:00Z", 100.0));
+        risk.on_bar(&account, "ES", &bar("2023-01-02T11:00:00Z", 96.0));
+        assert!(!risk.is_halted());
+        risk.on_bar(&account, "ES", &bar("2023-01-02T12:00:00Z", 95.0));
+        assert!(risk.is_halted());
+        assert!(risk.check(&account, "ES", Side::Buy, 1.0, 2).is_err());
+        assert!(risk.check(&account, "ES", Side::Sell, 100.0, 2).is_ok());
+
+        // A new day starts from the equity at its first bar
+        risk.on_bar(&account, "ES", &bar("2023-01-03T10:00:00Z", 94.0));
+        assert!(!risk.is_halted());
+        assert!(risk.check(&account, "ES", Side::Buy, 1.0, 3).is_ok());
+    }
+
+    #[test]
+    fn test_size() {
+        let account = Account::new(10000.0);
+        let mut risk = RiskManager::new().with_sizing(Sizing::Volatility(2, 1.0));
+        risk.on_bar(&account, "ES", &bar("2023-01-02T10:00:00Z", 100.0));
+        assert_eq!(risk.size(&account, "ES"), Some(0.0));
+        // True ranges of 2 and 4 (from the previous close of 100)
+        risk.on_bar(&account, "ES", &bar("2023-01-02T11:00:00Z", 103.0));
+        assert_eq!(risk.volatility("ES"), Some(3.0));
+        assert_eq!(risk.size(&account, "ES"), Some(33.0));
+        assert_eq!(risk.size(&account, "NQ"), None);
+        assert_eq!(RiskManager::new().size(&account, "ES"), None);
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::Trade;
+
+/// How many units a strategy trades, given the equity of the account.
+/// Quantities are rounded down to whole units.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Sizing {
+    /// Holds a position worth a percentage of the equity.
+    FixedFractional(f64), // percent
+    /// Holds a position that gains or loses a percentage of the equity on a
+    /// move of one average true range over the period.
+    Volatility(usize, f64), // period, percent
+    /// Holds a position worth a fraction of the Kelly percentage of the
+    /// equity, estimated from the closed trades. Until there are both wins
+    /// and losses, holds a position worth the given percentage instead.
+    Kelly(f64, f64), // fraction, percent
+}
+
+/// Parses sizing of the form `fixed_fractional:50`, `volatility:14:1` and
+/// `kelly:0.5:10`.
+impl FromStr for Sizing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let number = |value: &str| match value.parse::<f64>() {
+            Ok(value) if value.is_finite() && value > 0.0 => Ok(value),
+            _ => Err(format!("sizing: invalid amount '{}'", value)),
+        };
+        match parts.as_slice() {
+            ["fixed_fractional", percent] => Ok(Sizing::FixedFractional(number(percent)?)),
+            ["volatility", period, percent] => match period.parse::<usize>() {
+                Ok(period) if period > 0 => Ok(Sizing::Volatility(period, number(percent)?)),
+                _ => Err(format!("sizing: invalid period '{}'", period)),
+            },
+            ["kelly", fraction, percent] => Ok(Sizing::Kelly(number(fraction)?, number(percent)?)),
+            _ => Err(format!(
+                "sizing: expected 'fixed_fractional:<percent>', 'volatility:<period>:<percent>' or 'kelly:<fraction>:<percent>', found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Sizing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Sizing::FixedFractional(percent) => write!(f, "fixed_fractional:{}", percent),
+            Sizing::Volatility(period, percent) => write!(f, "volatility:{}:{}", period, percent),
+            Sizing::Kelly(fraction, percent) => write!(f, "kelly:{}:{}", fraction, percent),
+        }
+    }
+}
+
+impl Sizing {
+    /// Period of the average true range the sizing needs, if any.
+    pub fn period(&self) -> Option<usize> {
+        match self {
+            Sizing::Volatility(period, _) => Some(*period),
+            _ => None,
+        }
+    }
+
+    /// Units to hold of a symbol at `price`, whose price moves are worth
+    /// `point_value` each in the account currency. Volatility sizing holds
+    /// nothing until the volatility is known.
+    pub fn quantity(
+        &self,
+        equity: f64,
+        price: f32,
+        point_value: f64,
+        volatility: Option<f32>,
+        trades: &[Trade],
+    ) -> f32 {
+        let notional = price as f64 * point_value;
+        let quantity = match self {
+            Sizing::FixedFractional(percent) => equity * percent / 100.0 / notional,
+            Sizing::Volatility(_, percent) => match volatility {
+                Some(volatility) => equity * percent / 100.0 / (volatility as f64 * point_value),
+                None => 0.0,
+            },
+            Sizing::Kelly(fraction, percent) => {
+                let share = kelly(trades).map_or(percent / 100.0, |kelly| kelly * fraction);
+                equity * share / notional
+            }
+        };
+        if quantity.is_finite() && quantity > 0.0 {
+            quantity.floor() as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Kelly percentage of the trades, as a share between 0 and 1: the win rate
+/// less the loss rate over the ratio of the average win to the average loss.
+pub fn kelly(trades: &[Trade]) -> Option<f64> {
+    let (wins, losses): (Vec<f64>, Vec<f64>) = trades
+        .iter()
+        .map(|trade| trade.pnl)
+        .filter(|pnl| *pnl != 0.0)
+        .partition(|pnl| *pnl > 0.0);
+    if wins.is_empty() || losses.is_empty() {
+        return None;
+    }
+    let win_rate = wins.len() as f64 / (wins.len() + losses.len()) as f64;
+    let average_win = wins.iter().sum::<f64>() / wins.len() as f64;
+    let average_loss = -losses.iter().sum::<f64>() / losses.len() as f64;
+    let kelly = win_rate - (1.0 - win_rate) / (average_win / average_loss);
+    Some(kelly.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::backtest::{Side, Sizing, Trade, kelly};
+
+    fn trades(pnl: &[f64]) -> Vec<Trade> {
+        pnl.iter()
+            .map(|pnl| Trade {
+                symbol: "ES".to_string(),
+                side: Side::Buy,
+                quantity: 1.0,
+                entry_index: 0,
+                entry_time: String::new(),
+                entry_price: 100.0,
+                exit_index: 1,
+                exit_time: String::new(),
+                exit_price: 100.0 + *pnl as f32,
+                commission: 0.0,
+                pnl: *pnl,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            "volatility:14:1".parse::<Sizing>(),
+            Ok(Sizing::Volatility(14, 1.0))
+        );
+        let kelly: Sizing = "kelly:0.5:10".parse().unwrap();
+        assert_eq!(kelly.to_string(), "kelly:0.5:10");
+        assert!("fixed_fractional:-1".parse::<Sizing>().is_err());
+        assert!("volatility:0:1".parse::<Sizing>().is_err());
+        assert!("martingale".parse::<Sizing>().is_err());
+    }
+
+    #[test]
+    fn test_quantity() {
+        let fixed = Sizing::FixedFractional(50.0);
+        // 50000 of equity in contracts worth 50 × 100
+        assert_eq!(fixed.quantity(100000.0, 100.0, 50.0, None, &[]), 10.0);
+        assert_eq!(fixed.quantity(100000.0, 110.0, 50.0, None, &[]), 9.0);
+
+        let volatility = Sizing::Volatility(14, 1.0);
+        // 1000 of equity at risk per move of 2.5 worth 50 each
+        assert_eq!(
+            volatility.quantity(100000.0, 100.0, 50.0, Some(2.5), &[]),
+            8.0
+        );
+        assert_eq!(volatility.quantity(100000.0, 100.0, 50.0, None, &[]), 0.0);
+
+        let kelly = Sizing::Kelly(0.5, 10.0);
+        assert_eq!(kelly.quantity(100000.0, 100.0, 1.0, None, &[]), 100.0);
+        // Kelly of 0.6 - 0.4 / 2 = 0.4, halved
+        let history = trades(&[20.0, 20.0, 20.0, -10.0, -10.0]);
+        assert_eq!(kelly.quantity(100000.0, 100.0, 1.0, None, &history), 200.0);
+    }
+
+    #[test]
+    fn test_kelly() {
+        assert_float_absolute_eq!(kelly(&trades(&[20.0, -10.0])).unwrap(), 0.25, 1e-12);
+        assert_eq!(kelly(&trades(&[10.0, -20.0])), Some(0.0));
+        assert_eq!(kelly(&trades(&[10.0, 0.0])), None);
+    }
+}
@@ -0,0 +1,108 @@
+use crate::backtest::{Account, Order, OrderType, RiskManager, Side};
+use crate::domain::{Bar, Tick};
+
+/// A trading strategy driven by market events. Orders submitted through the
+/// context fill on later events, never on the event that produced them.
+pub trait Strategy {
+    /// Called once with every bar before a bar replay starts, e.g. to
+    /// calculate indicators. Values at a bar must only depend on the bars up
+    /// to it.
+    fn on_start(&mut self, _data: &[Bar]) {}
+
+    /// Called with the latest bars when a bar is added after the start, e.g.
+    /// when paper trading, before `on_bar` is called with it. The new bar is
+    /// the last one, and bars are indexed from the first one given.
+    fn on_append(&mut self, _data: &[Bar]) {}
+
+    fn on_bar(&mut self, _context: &mut Context, _bar: &Bar) {}
+
+    fn on_tick(&mut self, _context: &mut Context, _tick: &Tick) {}
+}
+
+/// The account of a strategy at the event being replayed, and the risk
+/// manager vetting its orders, if any.
+pub struct Context<'a> {
+    index: usize,
+    symbol: &'a str,
+    account: &'a mut Account,
+    risk: Option<&'a mut RiskManager>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(index: usize, symbol: &'a str, account: &'a mut Account) -> Self {
+        Context {
+            index,
+            symbol,
+            account,
+            risk: None,
+        }
+    }
+
+    pub fn with_risk(mut self, risk: Option<&'a mut RiskManager>) -> Self {
+        self.risk = risk;
+        self
+    }
+
+    /// Index of the bar or tick being replayed.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn symbol(&self) -> &str {
+        self.symbol
+    }
+
+    /// Signed quantity held in the symbol, positive when long.
+    pub fn position(&self) -> f32 {
+        self.account.position(self.symbol)
+    }
+
+    pub fn cash(&self) -> f64 {
+        self.account.cash
+    }
+
+    /// Orders of the symbol waiting to be filled. Symbols traded together
+    /// share the account, but not their orders.
+    pub fn open_orders(&self) -> impl Iterator<Item = &Order> {
+        self.account
+            .orders
+            .iter()
+            .filter(|order| order.symbol == self.symbol)
+    }
+
+    /// Units of the symbol to hold according to the sizing of the risk
+    /// manager, if there is one.
+    pub fn size(&self) -> Option<f32> {
+        self.risk.as_ref()?.size(self.account, self.symbol)
+    }
+
+    pub fn buy(&mut self, quantity: f32, order_type: OrderType) -> Option<u64> {
+        self.submit(Side::Buy, quantity, order_type)
+    }
+
+    pub fn sell(&mut self, quantity: f32, order_type: OrderType) -> Option<u64> {
+        self.submit(Side::Sell, quantity, order_type)
+    }
+
+    /// Queues an order, returning its id, unless the risk manager rejects it.
+    pub fn submit(&mut self, side: Side, quantity: f32, order_type: OrderType) -> Option<u64> {
+        if let Some(risk) = self.risk.as_mut() {
+            risk.check(self.account, self.symbol, side, quantity, self.index)
+                .ok()?;
+        }
+        Some(
+            self.account
+                .submit(self.symbol, side, quantity, order_type, self.index),
+        )
+    }
+
+    pub fn cancel(&mut self, id: u64) -> bool {
+        self.account.cancel(id)
+    }
+
+    /// Cancels the open orders of the symbol.
+    pub fn cancel_all(&mut self) {
+        let symbol = self.symbol;
+        self.account.orders.retain(|order| order.symbol != symbol);
+    }
+}
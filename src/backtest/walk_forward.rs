@@ -0,0 +1,324 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{
+    BacktestResult, EquityPoint, Evaluation, Optimizer, Position, Rejection, Report, Trade,
+};
+
+/// How in-sample windows advance through the bars.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WalkForwardMode {
+    /// In-sample windows all start at the first bar and grow by the length
+    /// of an out-of-sample window.
+    Anchored,
+    /// In-sample windows keep their length and slide forward by the length
+    /// of an out-of-sample window.
+    Rolling,
+}
+
+impl FromStr for WalkForwardMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "anchored" => Ok(WalkForwardMode::Anchored),
+            "rolling" => Ok(WalkForwardMode::Rolling),
+            _ => Err(format!(
+                "walk forward: expected 'anchored' or 'rolling', found '{}'",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for WalkForwardMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WalkForwardMode::Anchored => write!(f, "anchored"),
+            WalkForwardMode::Rolling => write!(f, "rolling"),
+        }
+    }
+}
+
+/// An in-sample window of bars and the out-of-sample window following it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Window {
+    pub in_sample: Range<usize>,
+    pub out_of_sample: Range<usize>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WindowResult {
+    #[serde(flatten)]
+    pub window: Window,
+    /// The best parameters in sample and their in-sample performance.
+    pub in_sample_best: Evaluation,
+    /// The performance of the best parameters out of sample.
+    pub out_of_sample: Evaluation,
+    pub efficiency: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WalkForwardResult {
+    pub windows: Vec<WindowResult>,
+    /// Out-of-sample equity of the windows joined end to end.
+    pub equity: Vec<EquityPoint>,
+    pub trades: Vec<Trade>,
+    /// Positions still open at the end of the last window.
+    pub positions: BTreeMap<String, Position>,
+    /// Orders the risk manager rejected out of sample.
+    pub rejections: Vec<Rejection>,
+    pub efficiency: Option<f64>,
+}
+
+impl WalkForwardResult {
+    /// The stitched out-of-sample backtest, e.g. to report on.
+    pub fn out_of_sample(&self, initial_capital: f64) -> BacktestResult {
+        BacktestResult {
+            initial_capital,
+            fills: Vec::new(),
+            trades: self.trades.clone(),
+            equity: self.equity.clone(),
+            positions: self.positions.clone(),
+            rejections: self.rejections.clone(),
+        }
+    }
+}
+
+/// Walk-forward analysis
+///
+/// Optimizes the parameters on each in-sample window and backtests the best
+/// of them on the out-of-sample window that follows. The out-of-sample equity
+/// curves are stitched by adding up the profit and loss of the windows, each
+/// window starting flat with the initial capital. Positions still open at the
+/// end of a window are valued at its last close.
+///
+/// Walk-forward efficiency is the profit per bar out of sample over the
+/// profit per bar of the best parameters in sample. It is only defined when
+/// the in-sample profit is positive; efficiencies well below 1 are a sign of
+/// overfitting.
+pub struct WalkForward {
+    mode: WalkForwardMode,
+    in_sample: usize,
+    out_of_sample: usize,
+}
+
+impl WalkForward {
+    pub fn new(mode: WalkForwardMode, in_sample: usize, out_of_sample: usize) -> Self {
+        WalkForward {
+            mode,
+            in_sample,
+            out_of_sample,
+        }
+    }
+
+    /// The windows over a number of bars. The last out-of-sample window is
+    /// cut short by the end of the bars.
+    pub fn windows(&self, len: usize) -> Vec<Window> {
+        if self.in_sample == 0 || self.out_of_sample == 0 {
+            return Vec::new();
+        }
+        (0..)
+            .map(|i| {
+                let end = self.in_sample + i * self.out_of_sample;
+                let start = match self.mode {
+                    WalkForwardMode::Anchored => 0,
+                    WalkForwardMode::Rolling => end - self.in_sample,
+                };
+                Window {
+                    in_sample: start..end,
+                    out_of_sample: end..(end + self.out_of_sample).min(len),
+                }
+            })
+            .take_while(|window| window.in_sample.end < len)
+            .collect()
+    }
+
+    /// Runs the analysis over a number of bars. The backtest runs the
+    /// parameters over a range of the bars, returning `None` when the
+    /// parameters are invalid.
+    pub fn run<F>(
+        &self,
+        optimizer: &Optimizer,
+        len: usize,
+        backtest: F,
+    ) -> Result<WalkForwardResult, String>
+    where
+        F: Fn(&[f32], Range<usize>) -> Option<BacktestResult> + Sync,
+    {
+        let windows = self.windows(len);
+        if windows.is_empty() {
+            return Err(format!(
+                "walk forward: {} bars do not fit an in-sample window of {} and an out-of-sample window of {}",
+                len, self.in_sample, self.out_of_sample
+            ));
+        }
+
+        let objective = optimizer.objective();
+        let mut results = Vec::with_capacity(windows.len());
+        let mut equity = Vec::new();
+        let mut trades = Vec::new();
+        let mut positions = BTreeMap::new();
+        let mut rejections = Vec::new();
+        let (mut in_sample_profit, mut out_of_sample_profit) = (0.0, 0.0);
+
+        for window in windows {
+            let evaluations = optimizer.optimize(|params| {
+                let result = backtest(params, window.in_sample.clone())?;
+                Some(Report::new(&result, &[]))
+            });
+            let Some(best) = evaluations.into_iter().next() else {
+                return Err(format!(
+                    "walk forward: no valid parameters in sample {:?}",
+                    window.in_sample
+                ));
+            };
+            let result = backtest(&best.params, window.out_of_sample.clone()).ok_or_else(|| {
+                format!(
+                    "walk forward: parameters {:?} failed out of sample {:?}",
+                    best.params, window.out_of_sample
+                )
+            })?;
+            let report = Report::new(&result, &[]);
+
+            let efficiency = efficiency(
+                best.net_profit / window.in_sample.len() as f64,
+                report.net_profit / window.out_of_sample.len() as f64,
+            );
+            equity.extend(result.equity.iter().map(|point| EquityPoint {
+                equity: point.equity + out_of_sample_profit,
+                ..point.clone()
+            }));
+            in_sample_profit += best.net_profit;
+            out_of_sample_profit += report.net_profit;
+            trades.extend(result.trades);
+            rejections.extend(result.rejections);
+            positions = result.positions;
+
+            results.push(WindowResult {
+                out_of_sample: Evaluation::new(best.params.clone(), objective, &report),
+                in_sample_best: best,
+                efficiency,
+                window,
+            });
+        }
+
+        let in_sample_bars: usize = results.iter().map(|r| r.window.in_sample.len()).sum();
+        let out_of_sample_bars: usize = results.iter().map(|r| r.window.out_of_sample.len()).sum();
+        Ok(WalkForwardResult {
+            efficiency: efficiency(
+                in_sample_profit / in_sample_bars as f64,
+                out_of_sample_profit / out_of_sample_bars as f64,
+            ),
+            windows: results,
+            equity,
+            trades,
+            positions,
+            rejections,
+        })
+    }
+}
+
+fn efficiency(in_sample_rate: f64, out_of_sample_rate: f64) -> Option<f64> {
+    (in_sample_rate > 0.0).then(|| out_of_sample_rate / in_sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtest::{
+        Backtest, IndicatorCross, Objective, Optimizer, Search, WalkForward, WalkForwardMode,
+        Window,
+    };
+    use crate::domain::{Bar, Indicators, Interval, Resolution, Series};
+
+    /// Bars whose prices follow a sine wave of the given period around 100,
+    /// with an amplitude of 10.
+    fn sine_bars(count: usize, period: f32) -> Vec<Bar> {
+        (0..count)
+            .map(|i| {
+                let price = 100.0 + 10.0 * (2.0 * std::f32::consts::PI * i as f32 / period).sin();
+                Bar {
+                    timestamp: format!("2023-01-01T{:02}:{:02}:00Z", i / 60, i % 60),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: 1.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_windows() {
+        let rolling = WalkForward::new(WalkForwardMode::Rolling, 10, 4).windows(25);
+        assert_eq!(
+            rolling,
+            [
+                Window {
+                    in_sample: 0..10,
+                    out_of_sample: 10..14
+                },
+                Window {
+                    in_sample: 4..14,
+                    out_of_sample: 14..18
+                },
+                Window {
+                    in_sample: 8..18,
+                    out_of_sample: 18..22
+                },
+                Window {
+                    in_sample: 12..22,
+                    out_of_sample: 22..25
+                },
+            ]
+        );
+        let anchored = WalkForward::new(WalkForwardMode::Anchored, 10, 4).windows(25);
+        assert_eq!(anchored[3].in_sample, 0..22);
+        assert!(
+            WalkForward::new(WalkForwardMode::Rolling, 10, 4)
+                .windows(10)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_walk_forward() {
+        let series = Series::new(
+            "ES".to_string(),
+            Resolution::TICK,
+            Interval::T49,
+            sine_bars(300, 20.0),
+        );
+        let optimizer = Optimizer::new(
+            vec!["2:20:2".parse().unwrap()],
+            Search::Grid,
+            Objective::NetProfit,
+        );
+
+        let result = WalkForward::new(WalkForwardMode::Rolling, 100, 50)
+            .run(&optimizer, series.len(), |params, range| {
+                let indicator = Indicators::from(("sma", &params.to_vec()));
+                let mut strategy = IndicatorCross::new(indicator, 1.0);
+                Some(Backtest::new(10000.0).run_range(&mut strategy, &series, range))
+            })
+            .unwrap();
+
+        assert_eq!(result.windows.len(), 4);
+        assert_eq!(result.equity.len(), 200);
+        assert_eq!(result.equity[0].index, 100);
+        let profit: f64 = result
+            .windows
+            .iter()
+            .map(|window| window.out_of_sample.net_profit)
+            .sum();
+        assert!((result.equity[199].equity - 10000.0 - profit).abs() < 1e-6);
+        assert!(result.trades.iter().all(|trade| trade.entry_index >= 100));
+        // A pure sine wave is as regular out of sample as in sample
+        assert!(result.efficiency.unwrap() > 0.5);
+    }
+}
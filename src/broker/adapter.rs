@@ -0,0 +1,45 @@
+use crate::backtest::{Context, Fill, Order, OrderType, Side};
+use crate::broker::{AccountState, OrderEvent, PositionState};
+use crate::domain::Tick;
+
+/// A broker executing the orders of strategies
+///
+/// Strategies trade through the same `Context` whichever broker executes
+/// their orders, so that a strategy paper traded on the simulated broker
+/// trades the same way through a live broker. Order ids are assigned by the
+/// broker and stay the same when an order is replaced.
+pub trait Broker {
+    /// Follows the market with a tick, returning the fills received since
+    /// the last tick.
+    fn on_tick(&mut self, tick: &Tick) -> Vec<Fill>;
+
+    /// Calls a strategy with the account, sending the orders it submits or
+    /// cancels to the broker.
+    fn trade<F>(&mut self, symbol: &str, index: usize, strategy: F)
+    where
+        F: FnOnce(&mut Context);
+
+    /// Submits an order, returning its id, or why it was rejected.
+    fn submit(
+        &mut self,
+        symbol: &str,
+        side: Side,
+        quantity: f32,
+        order_type: OrderType,
+    ) -> Result<u64, String>;
+
+    fn cancel(&mut self, id: u64) -> Result<(), String>;
+
+    /// Changes the quantity and type of an open order.
+    fn replace(&mut self, id: u64, quantity: f32, order_type: OrderType) -> Result<(), String>;
+
+    /// Orders waiting to be filled, in the order they were submitted.
+    fn orders(&self) -> Vec<Order>;
+
+    /// The latest changes in the status of orders, oldest first.
+    fn events(&self) -> &[OrderEvent];
+
+    fn positions(&self) -> Vec<PositionState>;
+
+    fn state(&self) -> AccountState;
+}
@@ -0,0 +1,1090 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+use chrono::{NaiveDateTime, Utc};
+use serde::Deserialize;
+
+use crate::backtest::{Account, Context, Fill, Order, OrderType, RiskManager, Side};
+use crate::broker::message::{
+    ACCOUNT, BEGIN_SEQ_NO, CL_ORD_ID, COMMISSION, CXL_REJ_RESPONSE_TO, ENCRYPT_METHOD, END_SEQ_NO,
+    EXEC_TYPE, GAP_FILL_FLAG, HEART_BT_INT, LAST_PX, LAST_QTY, LEAVES_QTY, MSG_SEQ_NUM, NEW_SEQ_NO,
+    ORD_STATUS, ORD_TYPE, ORDER_QTY, ORIG_CL_ORD_ID, POSS_DUP_FLAG, PRICE, RESET_SEQ_NUM_FLAG,
+    SIDE, STOP_PX, SYMBOL, TEST_REQ_ID, TEXT, TRANSACT_TIME, timestamp,
+};
+use crate::broker::{
+    AccountState, Broker, MAX_HISTORY, Message, OrderEvent, OrderStatus, PositionState,
+};
+use crate::domain::Tick;
+
+/// How long to wait for the acceptor to answer a logon.
+const LOGON_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct FixConfig {
+    pub host: String,
+    pub port: u16,
+    pub sender_comp_id: String,
+    pub target_comp_id: String,
+    /// Account to book the orders to, if the acceptor requires one.
+    pub account: Option<String>,
+    /// Seconds between heartbeats.
+    pub heartbeat_interval: u64,
+}
+
+/// FIX 4.4 initiator
+///
+/// Logs on to the acceptor of a broker and routes orders to it as new order
+/// singles, cancel requests and cancel/replace requests. The orders,
+/// positions and profit of the account follow the execution reports of the
+/// acceptor, which are handled whenever the broker is polled. Order ids are
+/// sent as client order ids, with a suffix for each cancel or replace
+/// request as every request needs an id of its own.
+///
+/// The socket does not block once logged on: messages waiting for the
+/// socket are kept in order and written as it accepts them, on every send
+/// and poll.
+///
+/// Messages are not stored: sequence numbers are reset on logon, and resend
+/// requests are answered with a sequence reset. Messages received out of
+/// sequence are skipped: duplicates of messages already handled, and
+/// messages after a gap until the acceptor has resent the gap. Only the
+/// latest events, fills and trades are kept.
+pub struct FixBroker {
+    config: FixConfig,
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    /// Bytes of the messages sent that the socket has yet to accept.
+    outgoing: Vec<u8>,
+    seq_num: u64,
+    /// Sequence number expected of the next message received.
+    expected: u64,
+    /// Sequence number of the message after which a gap was found, while it
+    /// is being resent.
+    resending: Option<u64>,
+    last_sent: Instant,
+    logged_on: bool,
+    requests: u64,
+    account: Account,
+    risk: Option<RiskManager>,
+    prices: BTreeMap<String, f32>,
+    /// Orders live at the acceptor, by id.
+    open: BTreeMap<u64, Order>,
+    /// Client order id of the last accepted request on each live order.
+    cl_ord_ids: BTreeMap<u64, String>,
+    /// Orders with a cancel request waiting for an answer.
+    cancelling: BTreeSet<u64>,
+    /// Orders with a replace request waiting for an answer, as requested.
+    replacing: BTreeMap<u64, Order>,
+    fills: Vec<Fill>,
+    ticks: usize,
+    timestamp: String,
+    events: Vec<OrderEvent>,
+}
+
+impl FixBroker {
+    /// Connects and logs on to the acceptor, trading for the account.
+    pub fn connect(config: FixConfig, account: Account) -> Result<Self, String> {
+        let stream = TcpStream::connect((config.host.as_str(), config.port)).map_err(|err| {
+            format!(
+                "failed to connect to {}:{}: {}",
+                config.host, config.port, err
+            )
+        })?;
+        stream
+            .set_read_timeout(Some(LOGON_TIMEOUT))
+            .map_err(|err| err.to_string())?;
+        let mut broker = FixBroker {
+            config,
+            stream,
+            buffer: Vec::new(),
+            outgoing: Vec::new(),
+            seq_num: 1,
+            expected: 1,
+            resending: None,
+            last_sent: Instant::now(),
+            logged_on: false,
+            requests: 0,
+            account,
+            risk: None,
+            prices: BTreeMap::new(),
+            open: BTreeMap::new(),
+            cl_ord_ids: BTreeMap::new(),
+            cancelling: BTreeSet::new(),
+            replacing: BTreeMap::new(),
+            fills: Vec::new(),
+            ticks: 0,
+            timestamp: String::new(),
+            events: Vec::new(),
+        };
+
+        let heartbeat_interval = broker.config.heartbeat_interval;
+        broker.send(
+            Message::new("A")
+                .with_field(ENCRYPT_METHOD, 0)
+                .with_field(HEART_BT_INT, heartbeat_interval)
+                .with_field(RESET_SEQ_NUM_FLAG, "Y"),
+        )?;
+        let logon = broker.receive()?;
+        match logon.msg_type() {
+            "A" => broker.expected = logon.parse::<u64>(MSG_SEQ_NUM).unwrap_or(1) + 1,
+            "5" => {
+                return Err(format!(
+                    "logon refused: {}",
+                    logon.get(TEXT).unwrap_or_default()
+                ));
+            }
+            other => return Err(format!("expected a logon, received message type {}", other)),
+        }
+        broker
+            .stream
+            .set_nonblocking(true)
+            .map_err(|err| err.to_string())?;
+        broker.logged_on = true;
+        Ok(broker)
+    }
+
+    pub fn with_risk(mut self, risk: RiskManager) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    pub fn account(&self) -> &Account {
+        &self.account
+    }
+
+    pub fn is_logged_on(&self) -> bool {
+        self.logged_on
+    }
+
+    /// Handles the messages received from the acceptor, and sends a
+    /// heartbeat when one is due.
+    pub fn poll(&mut self) -> Result<(), String> {
+        self.flush()?;
+        let mut chunk = [0; 4096];
+        let closed = loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => break true,
+                Ok(count) => self.buffer.extend_from_slice(&chunk[..count]),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break false,
+                Err(err) => return Err(format!("failed to receive: {}", err)),
+            }
+        };
+        // Messages received before the connection closed are still handled
+        while let Some(message) = Message::read(&mut self.buffer)? {
+            if self.in_sequence(&message)? {
+                self.handle(&message)?;
+            }
+        }
+        if closed {
+            self.logged_on = false;
+            return Err("connection closed by the acceptor".to_string());
+        }
+
+        let interval = Duration::from_secs(self.config.heartbeat_interval);
+        if self.logged_on && self.last_sent.elapsed() >= interval {
+            self.send(Message::new("0"))?;
+        }
+        Ok(())
+    }
+
+    pub fn logout(&mut self) -> Result<(), String> {
+        self.logged_on = false;
+        self.send(Message::new("5"))
+    }
+
+    /// Blocks until a message is received.
+    fn receive(&mut self) -> Result<Message, String> {
+        let mut chunk = [0; 4096];
+        loop {
+            if let Some(message) = Message::read(&mut self.buffer)? {
+                return Ok(message);
+            }
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err("connection closed by the acceptor".to_string()),
+                Ok(count) => self.buffer.extend_from_slice(&chunk[..count]),
+                Err(err) => return Err(format!("failed to receive: {}", err)),
+            }
+        }
+    }
+
+    fn send(&mut self, message: Message) -> Result<(), String> {
+        let message = message.with_header(
+            &self.config.sender_comp_id,
+            &self.config.target_comp_id,
+            self.seq_num,
+        );
+        // The message counts as sent once queued, as part of it may go out
+        self.outgoing.extend(message.encode());
+        self.seq_num += 1;
+        self.last_sent = Instant::now();
+        self.flush()
+    }
+
+    /// Writes as much of the messages sent as the socket accepts.
+    fn flush(&mut self) -> Result<(), String> {
+        while !self.outgoing.is_empty() {
+            match self.stream.write(&self.outgoing) {
+                Ok(0) => return Err("connection closed by the acceptor".to_string()),
+                Ok(count) => {
+                    self.outgoing.drain(..count);
+                }
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) if err.kind() == ErrorKind::Interrupted => (),
+                Err(err) => return Err(format!("failed to send: {}", err)),
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the sequence number of a message, returning whether it is the
+    /// next one and should be handled. Asks for a gap to be resent.
+    fn in_sequence(&mut self, message: &Message) -> Result<bool, String> {
+        let gap_fill = message.get(GAP_FILL_FLAG) == Some("Y");
+        if message.msg_type() == "4" && !gap_fill {
+            // A reset sets the next number whatever the number of the message
+            if let Some(next) = message.parse(NEW_SEQ_NO) {
+                self.expected = next;
+            }
+            return Ok(false);
+        }
+
+        let seq_num: u64 = message
+            .parse(MSG_SEQ_NUM)
+            .ok_or("message has no sequence number")?;
+        if seq_num < self.expected {
+            if message.get(POSS_DUP_FLAG) == Some("Y") {
+                return Ok(false);
+            }
+            let err = format!(
+                "sequence number {} is lower than {} expected",
+                seq_num, self.expected
+            );
+            self.logged_on = false;
+            self.send(Message::new("5").with_field(TEXT, &err))?;
+            return Err(err);
+        }
+        if seq_num > self.expected {
+            if self.resending.is_none() {
+                let expected = self.expected;
+                self.send(
+                    Message::new("2")
+                        .with_field(BEGIN_SEQ_NO, expected)
+                        .with_field(END_SEQ_NO, 0),
+                )?;
+                self.resending = Some(seq_num);
+            }
+            return Ok(false);
+        }
+
+        self.expected += 1;
+        // A gap fill skips the administrative messages it replaces
+        if gap_fill && let Some(next) = message.parse::<u64>(NEW_SEQ_NO) {
+            self.expected = next.max(self.expected);
+        }
+        if self.resending.is_some_and(|last| self.expected > last) {
+            self.resending = None;
+        }
+        Ok(message.msg_type() != "4")
+    }
+
+    fn handle(&mut self, message: &Message) -> Result<(), String> {
+        match message.msg_type() {
+            "0" | "A" => (),
+            "1" => {
+                let id = message.get(TEST_REQ_ID).unwrap_or_default().to_string();
+                self.send(Message::new("0").with_field(TEST_REQ_ID, id))?;
+            }
+            "2" => {
+                tracing::warn!(
+                    "resend requested from {}, resetting the sequence instead",
+                    message.get(BEGIN_SEQ_NO).unwrap_or_default()
+                );
+                let next = self.seq_num + 1;
+                self.send(
+                    Message::new("4")
+                        .with_field(GAP_FILL_FLAG, "N")
+                        .with_field(NEW_SEQ_NO, next),
+                )?;
+            }
+            "3" => tracing::warn!(
+                "message rejected by the acceptor: {}",
+                message.get(TEXT).unwrap_or_default()
+            ),
+            "5" => {
+                // Answer a logout unless it answers ours
+                if self.logged_on {
+                    self.logged_on = false;
+                    self.send(Message::new("5"))?;
+                }
+            }
+            "8" => self.execution_report(message),
+            "9" => self.cancel_reject(message),
+            other => tracing::warn!("ignored message of type {}", other),
+        }
+        Ok(())
+    }
+
+    fn execution_report(&mut self, report: &Message) {
+        let Some(order) = order_id(report).and_then(|id| self.open.get(&id).cloned()) else {
+            tracing::warn!(
+                "execution report for unknown order {}",
+                report.get(CL_ORD_ID).unwrap_or_default()
+            );
+            return;
+        };
+        let timestamp = report
+            .get(TRANSACT_TIME)
+            .and_then(|time| NaiveDateTime::parse_from_str(time, "%Y%m%d-%H:%M:%S%.f").ok())
+            .map_or(self.timestamp.clone(), |time| {
+                time.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string()
+            });
+
+        let event = match report.get(EXEC_TYPE).unwrap_or_default() {
+            "0" => OrderEvent::new(&order, OrderStatus::Submitted, &timestamp),
+            "F" => {
+                let fill = Order {
+                    quantity: report.parse(LAST_QTY).unwrap_or_default(),
+                    ..order.clone()
+                };
+                let price = report.parse(LAST_PX).unwrap_or_default();
+                let commission = report.parse(COMMISSION).unwrap_or_default();
+                self.account
+                    .fill(&fill, price, commission, self.ticks, &timestamp);
+                self.fills.extend(self.account.fills.last().cloned());
+
+                let leaves = report
+                    .parse(LEAVES_QTY)
+                    .unwrap_or(order.quantity - fill.quantity);
+                let status = if report.get(ORD_STATUS) == Some("2") || leaves <= 0.0 {
+                    self.remove(order.id);
+                    OrderStatus::Filled
+                } else {
+                    self.update(Order {
+                        quantity: leaves,
+                        ..order
+                    });
+                    OrderStatus::PartiallyFilled
+                };
+                OrderEvent {
+                    price: Some(price),
+                    ..OrderEvent::new(&fill, status, &timestamp)
+                }
+            }
+            "5" => {
+                let requested = self.replacing.remove(&order.id).unwrap_or(order);
+                let replaced = Order {
+                    quantity: report
+                        .parse(LEAVES_QTY)
+                        .or(report.parse(ORDER_QTY))
+                        .unwrap_or(requested.quantity),
+                    ..requested
+                };
+                if let Some(cl_ord_id) = report.get(CL_ORD_ID) {
+                    self.cl_ord_ids.insert(replaced.id, cl_ord_id.to_string());
+                }
+                self.update(replaced.clone());
+                OrderEvent::new(&replaced, OrderStatus::Replaced, &timestamp)
+            }
+            // Cancelled or expired
+            "4" | "C" => {
+                self.remove(order.id);
+                OrderEvent::new(&order, OrderStatus::Cancelled, &timestamp)
+            }
+            "8" => {
+                self.remove(order.id);
+                OrderEvent {
+                    reason: report.get(TEXT).map(str::to_string),
+                    ..OrderEvent::new(&order, OrderStatus::Rejected, &timestamp)
+                }
+            }
+            _ => return,
+        };
+        self.events.push(event);
+    }
+
+    /// Restores an order whose cancel or replace request was rejected.
+    fn cancel_reject(&mut self, reject: &Message) {
+        let Some(order) = order_id(reject).and_then(|id| self.open.get(&id).cloned()) else {
+            return;
+        };
+        if reject.get(CXL_REJ_RESPONSE_TO) == Some("2") {
+            self.replacing.remove(&order.id);
+        } else {
+            self.cancelling.remove(&order.id);
+        }
+        self.update(order.clone());
+        self.events.push(OrderEvent {
+            reason: reject.get(TEXT).map(str::to_string),
+            ..OrderEvent::new(&order, OrderStatus::Rejected, &self.timestamp)
+        });
+    }
+
+    /// Updates a live order, and the open order of the account unless it
+    /// is being cancelled.
+    fn update(&mut self, order: Order) {
+        if !self.cancelling.contains(&order.id) {
+            match self
+                .account
+                .orders
+                .iter_mut()
+                .find(|open| open.id == order.id)
+            {
+                Some(open) => *open = order.clone(),
+                None => self.account.orders.push(order.clone()),
+            }
+        }
+        self.open.insert(order.id, order);
+    }
+
+    fn remove(&mut self, id: u64) {
+        self.account.cancel(id);
+        self.open.remove(&id);
+        self.cl_ord_ids.remove(&id);
+        self.cancelling.remove(&id);
+        self.replacing.remove(&id);
+    }
+
+    /// Sends the orders the strategy submitted or cancelled, and records the
+    /// orders rejected by the risk manager.
+    fn route(&mut self) {
+        if let Some(risk) = self.risk.as_mut() {
+            self.events.extend(
+                risk.rejections
+                    .drain(..)
+                    .map(|rejection| OrderEvent::rejected(&rejection)),
+            );
+        }
+
+        let cancelled: Vec<u64> = self
+            .open
+            .keys()
+            .filter(|id| !self.account.orders.iter().any(|order| order.id == **id))
+            .filter(|id| !self.cancelling.contains(id))
+            .copied()
+            .collect();
+        for id in cancelled {
+            if let Err(err) = self.cancel_request(id) {
+                tracing::warn!("failed to cancel order {}: {}", id, err);
+            }
+        }
+
+        let submitted: Vec<Order> = self
+            .account
+            .orders
+            .iter()
+            .filter(|order| !self.open.contains_key(&order.id))
+            .cloned()
+            .collect();
+        for order in submitted {
+            if let Err(err) = self.new_order(&order) {
+                tracing::warn!("failed to submit order {}: {}", order.id, err);
+                self.account.cancel(order.id);
+                self.events.push(OrderEvent {
+                    reason: Some(err),
+                    ..OrderEvent::new(&order, OrderStatus::Rejected, &self.timestamp)
+                });
+            }
+        }
+    }
+
+    fn new_order(&mut self, order: &Order) -> Result<(), String> {
+        let mut message = Message::new("D").with_field(CL_ORD_ID, order.id);
+        if let Some(account) = &self.config.account {
+            message = message.with_field(ACCOUNT, account);
+        }
+        let message = message
+            .with_field(SYMBOL, &order.symbol)
+            .with_field(SIDE, side(order.side))
+            .with_field(TRANSACT_TIME, timestamp(Utc::now()))
+            .with_field(ORDER_QTY, order.quantity);
+        self.send(with_order_type(message, order.order_type))?;
+        self.open.insert(order.id, order.clone());
+        self.cl_ord_ids.insert(order.id, order.id.to_string());
+        Ok(())
+    }
+
+    fn cancel_request(&mut self, id: u64) -> Result<(), String> {
+        let (Some(order), Some(cl_ord_id)) = (self.open.get(&id), self.cl_ord_ids.get(&id)) else {
+            return Err(format!("order {} is not live", id));
+        };
+        self.requests += 1;
+        let message = Message::new("F")
+            .with_field(ORIG_CL_ORD_ID, cl_ord_id)
+            .with_field(CL_ORD_ID, format!("{}-{}", id, self.requests))
+            .with_field(SYMBOL, &order.symbol)
+            .with_field(SIDE, side(order.side))
+            .with_field(TRANSACT_TIME, timestamp(Utc::now()))
+            .with_field(ORDER_QTY, order.quantity);
+        self.send(message)?;
+        self.cancelling.insert(id);
+        Ok(())
+    }
+
+    fn replace_request(&mut self, order: &Order) -> Result<(), String> {
+        let Some(cl_ord_id) = self.cl_ord_ids.get(&order.id) else {
+            return Err(format!("order {} is not live", order.id));
+        };
+        self.requests += 1;
+        let mut message = Message::new("G")
+            .with_field(ORIG_CL_ORD_ID, cl_ord_id)
+            .with_field(CL_ORD_ID, format!("{}-{}", order.id, self.requests));
+        if let Some(account) = &self.config.account {
+            message = message.with_field(ACCOUNT, account);
+        }
+        let message = message
+            .with_field(SYMBOL, &order.symbol)
+            .with_field(SIDE, side(order.side))
+            .with_field(TRANSACT_TIME, timestamp(Utc::now()))
+            .with_field(ORDER_QTY, order.quantity);
+        self.send(with_order_type(message, order.order_type))?;
+        self.replacing.insert(order.id, order.clone());
+        Ok(())
+    }
+}
+
+impl Broker for FixBroker {
+    /// Follows the prices, and handles the messages of the acceptor.
+    fn on_tick(&mut self, tick: &Tick) -> Vec<Fill> {
+        self.ticks += 1;
+        self.timestamp = tick.timestamp.clone();
+        self.prices.insert(tick.symbol.clone(), tick.price);
+        if let Err(err) = self.poll() {
+            tracing::warn!("fix session {}: {}", self.config.sender_comp_id, err);
+        }
+        if let Some(risk) = self.risk.as_mut() {
+            risk.on_tick(&self.account, tick);
+        }
+        OrderEvent::trim(&mut self.events);
+        self.account.trim(MAX_HISTORY);
+        std::mem::take(&mut self.fills)
+    }
+
+    fn trade<F>(&mut self, symbol: &str, index: usize, strategy: F)
+    where
+        F: FnOnce(&mut Context),
+    {
+        let mut context =
+            Context::new(index, symbol, &mut self.account).with_risk(self.risk.as_mut());
+        strategy(&mut context);
+        self.route();
+    }
+
+    fn submit(
+        &mut self,
+        symbol: &str,
+        side: Side,
+        quantity: f32,
+        order_type: OrderType,
+    ) -> Result<u64, String> {
+        let mut id = None;
+        let index = self.ticks;
+        self.trade(symbol, index, |context| {
+            id = context.submit(side, quantity, order_type);
+        });
+        id.filter(|id| self.open.contains_key(id)).ok_or_else(|| {
+            self.events
+                .last()
+                .and_then(|event| event.reason.clone())
+                .unwrap_or_else(|| "order rejected".to_string())
+        })
+    }
+
+    fn cancel(&mut self, id: u64) -> Result<(), String> {
+        if !self.account.cancel(id) {
+            return Err(format!("order {} is not open", id));
+        }
+        self.cancel_request(id)
+    }
+
+    /// Requests to replace an open order, which the account shows as
+    /// replaced unless the acceptor rejects the request. Orders replaced
+    /// with a larger quantity are checked by the risk manager again.
+    fn replace(&mut self, id: u64, quantity: f32, order_type: OrderType) -> Result<(), String> {
+        let Some(position) = self.account.orders.iter().position(|order| order.id == id) else {
+            return Err(format!("order {} is not open", id));
+        };
+        let order = self.account.orders.remove(position);
+        if let Some(risk) = self.risk.as_mut()
+            && let Err(err) = risk.check(
+                &self.account,
+                &order.symbol,
+                order.side,
+                quantity,
+                self.ticks,
+            )
+        {
+            self.account.orders.insert(position, order);
+            self.route();
+            return Err(err);
+        }
+
+        let replaced = Order {
+            quantity,
+            order_type,
+            ..order.clone()
+        };
+        let result = self.replace_request(&replaced);
+        let order = if result.is_ok() { replaced } else { order };
+        self.account.orders.insert(position, order);
+        result
+    }
+
+    fn orders(&self) -> Vec<Order> {
+        self.account.orders.clone()
+    }
+
+    fn events(&self) -> &[OrderEvent] {
+        &self.events
+    }
+
+    fn positions(&self) -> Vec<PositionState> {
+        PositionState::from_account(&self.account, &self.prices)
+    }
+
+    fn state(&self) -> AccountState {
+        let halted = self.risk.as_ref().is_some_and(|risk| risk.is_halted());
+        AccountState::new(&self.timestamp, &self.account, &self.prices, halted)
+    }
+}
+
+/// Id of the order of a message, from its client order id.
+fn order_id(message: &Message) -> Option<u64> {
+    message.get(CL_ORD_ID)?.split('-').next()?.parse().ok()
+}
+
+fn side(side: Side) -> u8 {
+    match side {
+        Side::Buy => 1,
+        Side::Sell => 2,
+    }
+}
+
+fn with_order_type(message: Message, order_type: OrderType) -> Message {
+    match order_type {
+        OrderType::Market => message.with_field(ORD_TYPE, 1),
+        OrderType::Limit(price) => message.with_field(ORD_TYPE, 2).with_field(PRICE, price),
+        OrderType::Stop(price) => message.with_field(ORD_TYPE, 3).with_field(STOP_PX, price),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    use crate::backtest::{Account, OrderType, Side};
+    use crate::broker::{Broker, FixBroker, FixConfig, Message, OrderStatus};
+
+    /// Stands in for the acceptor of a broker.
+    struct Acceptor {
+        stream: TcpStream,
+        buffer: Vec<u8>,
+        seq_num: u64,
+    }
+
+    impl Acceptor {
+        fn receive(&mut self) -> Message {
+            let mut chunk = [0; 4096];
+            loop {
+                if let Some(message) = Message::read(&mut self.buffer).unwrap() {
+                    return message;
+                }
+                let count = self.stream.read(&mut chunk).unwrap();
+                self.buffer.extend_from_slice(&chunk[..count]);
+            }
+        }
+
+        fn send(&mut self, message: Message) {
+            let message = message.with_header("BROKER", "CLIENT", self.seq_num);
+            self.stream.write_all(&message.encode()).unwrap();
+            self.seq_num += 1;
+        }
+
+        /// An execution report on the order of a request.
+        fn report(&self, request: &Message, exec_type: &str, ord_status: &str) -> Message {
+            Message::new("8")
+                .with_field(11, request.get(11).unwrap())
+                .with_field(55, request.get(55).unwrap())
+                .with_field(54, request.get(54).unwrap())
+                .with_field(150, exec_type)
+                .with_field(39, ord_status)
+                .with_field(60, "20230102-10:00:00.000")
+        }
+    }
+
+    fn config(port: u16) -> FixConfig {
+        FixConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            sender_comp_id: "CLIENT".to_string(),
+            target_comp_id: "BROKER".to_string(),
+            account: Some("FUND".to_string()),
+            heartbeat_interval: 30,
+        }
+    }
+
+    /// Connects a broker to an acceptor that logs it on, then follows the
+    /// script.
+    fn session<F>(script: F) -> (FixBroker, thread::JoinHandle<()>)
+    where
+        F: FnOnce(&mut Acceptor) + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let acceptor = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut acceptor = Acceptor {
+                stream,
+                buffer: Vec::new(),
+                seq_num: 1,
+            };
+            let logon = acceptor.receive();
+            assert_eq!((logon.msg_type(), logon.get(141)), ("A", Some("Y")));
+            acceptor.send(Message::new("A").with_field(98, 0).with_field(108, 30));
+            script(&mut acceptor);
+        });
+        let broker = FixBroker::connect(config(port), Account::new(10000.0)).unwrap();
+        (broker, acceptor)
+    }
+
+    fn wait(broker: &mut FixBroker, events: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while broker.events().len() < events {
+            assert!(Instant::now() < deadline, "no answer from the acceptor");
+            broker.poll().unwrap();
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Polls the broker until the acceptor is done with its script.
+    fn finish(broker: &mut FixBroker, acceptor: thread::JoinHandle<()>) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !acceptor.is_finished() {
+            assert!(Instant::now() < deadline, "acceptor did not finish");
+            // The connection closes as the acceptor finishes
+            if let Err(err) = broker.poll() {
+                assert!(acceptor.is_finished(), "{}", err);
+            }
+            thread::sleep(Duration::from_millis(5));
+        }
+        acceptor.join().unwrap();
+    }
+
+    #[test]
+    fn test_fix_broker() {
+        let (mut broker, acceptor) = session(|acceptor| {
+            // A market order filled in two parts
+            let order = acceptor.receive();
+            assert_eq!(order.msg_type(), "D");
+            assert_eq!(order.get(1), Some("FUND"));
+            assert_eq!((order.get(38), order.get(40)), (Some("2"), Some("1")));
+            let report = acceptor.report(&order, "0", "0").with_field(151, 2);
+            acceptor.send(report);
+            for (price, leaves, status) in [(100, 1, "1"), (101, 0, "2")] {
+                let report = acceptor
+                    .report(&order, "F", status)
+                    .with_field(32, 1)
+                    .with_field(31, price)
+                    .with_field(151, leaves)
+                    .with_field(12, 1.25);
+                acceptor.send(report);
+            }
+
+            // A limit order, replaced, then cancelled
+            let order = acceptor.receive();
+            assert_eq!((order.get(11), order.get(44)), (Some("2"), Some("110")));
+            let report = acceptor.report(&order, "0", "0");
+            acceptor.send(report);
+            let replace = acceptor.receive();
+            assert_eq!(replace.msg_type(), "G");
+            assert_eq!((replace.get(41), replace.get(11)), (Some("2"), Some("2-1")));
+            assert_eq!((replace.get(38), replace.get(44)), (Some("2"), Some("105")));
+            let report = acceptor.report(&replace, "5", "5").with_field(151, 2);
+            acceptor.send(report);
+            let cancel = acceptor.receive();
+            assert_eq!(cancel.msg_type(), "F");
+            assert_eq!((cancel.get(41), cancel.get(11)), (Some("2-1"), Some("2-2")));
+            acceptor.send(Message::new("1").with_field(112, "PING"));
+            let report = acceptor.report(&cancel, "4", "4");
+            acceptor.send(report);
+
+            let heartbeat = acceptor.receive();
+            assert_eq!(
+                (heartbeat.msg_type(), heartbeat.get(112)),
+                ("0", Some("PING"))
+            );
+            assert_eq!(acceptor.receive().msg_type(), "5");
+        });
+        assert!(broker.is_logged_on());
+
+        broker
+            .submit("ES", Side::Buy, 2.0, OrderType::Market)
+            .unwrap();
+        wait(&mut broker, 3);
+        let fills = &broker.account().fills;
+        assert_eq!(fills.len(), 2);
+        assert_eq!(fills[1].timestamp, "2023-01-02T10:00:00.000000Z");
+        assert_eq!(broker.positions()[0].quantity, 2.0);
+        assert_eq!(broker.positions()[0].average_price, 100.5);
+        assert_eq!(broker.state().cash, 10000.0 - 100.0 - 101.0 - 2.5);
+
+        let id = broker
+            .submit("ES", Side::Sell, 1.0, OrderType::Limit(110.0))
+            .unwrap();
+        wait(&mut broker, 4);
+        broker.replace(id, 2.0, OrderType::Limit(105.0)).unwrap();
+        wait(&mut broker, 5);
+        assert_eq!(broker.orders()[0].order_type, OrderType::Limit(105.0));
+        broker.cancel(id).unwrap();
+        assert!(broker.orders().is_empty());
+        assert!(broker.cancel(id).is_err());
+        wait(&mut broker, 6);
+
+        let statuses: Vec<OrderStatus> = broker.events().iter().map(|e| e.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                OrderStatus::Submitted,
+                OrderStatus::PartiallyFilled,
+                OrderStatus::Filled,
+                OrderStatus::Submitted,
+                OrderStatus::Replaced,
+                OrderStatus::Cancelled,
+            ]
+        );
+        broker.logout().unwrap();
+        acceptor.join().unwrap();
+    }
+
+    #[test]
+    fn test_sequence() {
+        let (mut broker, acceptor) = session(|acceptor| {
+            let order = acceptor.receive();
+            let fill = acceptor
+                .report(&order, "F", "2")
+                .with_field(32, 1)
+                .with_field(31, 100)
+                .with_field(151, 0);
+            acceptor.send(fill.clone());
+            // Redelivered, and not applied again
+            acceptor.seq_num -= 1;
+            acceptor.send(fill.with_field(43, "Y"));
+
+            // Messages 3 and 4 are lost
+            acceptor.seq_num += 2;
+            acceptor.send(Message::new("1").with_field(112, "LOST"));
+            let resend = acceptor.receive();
+            assert_eq!(resend.msg_type(), "2");
+            assert_eq!((resend.get(7), resend.get(16)), (Some("3"), Some("0")));
+
+            // Resent as a gap fill up to the message after the gap
+            acceptor.seq_num = 3;
+            let gap_fill = Message::new("4")
+                .with_field(43, "Y")
+                .with_field(123, "Y")
+                .with_field(36, 6);
+            acceptor.send(gap_fill);
+            acceptor.seq_num = 6;
+            acceptor.send(Message::new("1").with_field(112, "PING"));
+            let heartbeat = acceptor.receive();
+            assert_eq!(
+                (heartbeat.msg_type(), heartbeat.get(112)),
+                ("0", Some("PING"))
+            );
+        });
+
+        broker
+            .submit("ES", Side::Buy, 1.0, OrderType::Market)
+            .unwrap();
+        finish(&mut broker, acceptor);
+        assert_eq!(broker.account().fills.len(), 1);
+        assert_eq!(broker.positions()[0].quantity, 1.0);
+    }
+
+    #[test]
+    fn test_full_socket() {
+        let (ready, start) = mpsc::channel();
+        let (mut broker, acceptor) = session(move |acceptor| {
+            start.recv().unwrap();
+            for seq_num in 2..2 + 10_000 {
+                let message = acceptor.receive();
+                assert_eq!(message.parse(34), Some(seq_num));
+            }
+        });
+
+        // Sends more than the socket buffers while the acceptor is not reading
+        let text = "x".repeat(1000);
+        for _ in 0..10_000 {
+            broker
+                .send(Message::new("0").with_field(58, &text))
+                .unwrap();
+        }
+        assert!(!broker.outgoing.is_empty());
+        ready.send(()).unwrap();
+        finish(&mut broker, acceptor);
+        assert!(broker.outgoing.is_empty());
+    }
+
+    #[test]
+    fn test_logon_refused() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let acceptor = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut acceptor = Acceptor {
+                stream,
+                buffer: Vec::new(),
+                seq_num: 1,
+            };
+            acceptor.receive();
+            acceptor.send(Message::new("5").with_field(58, "unknown sender"));
+        });
+        let result = FixBroker::connect(config(port), Account::new(10000.0));
+        assert_eq!(
+            result.err(),
+            Some("logon refused: unknown sender".to_string())
+        );
+        acceptor.join().unwrap();
+    }
+
+    #[test]
+    fn test_session_messages() {
+        let (mut broker, acceptor) = session(|acceptor| {
+            acceptor.send(Message::new("0"));
+            acceptor.send(
+                Message::new("3")
+                    .with_field(45, 1)
+                    .with_field(58, "bad tag"),
+            );
+            acceptor.send(Message::new("j").with_field(58, "not supported"));
+
+            // Resends are answered with a reset to the next number
+            acceptor.send(Message::new("2").with_field(7, 1).with_field(16, 0));
+            let reset = acceptor.receive();
+            assert_eq!((reset.msg_type(), reset.get(123)), ("4", Some("N")));
+            assert_eq!(
+                reset.parse::<u64>(36),
+                reset.parse::<u64>(34).map(|n| n + 1)
+            );
+
+            acceptor.send(Message::new("1").with_field(112, "PING"));
+            let heartbeat = acceptor.receive();
+            assert_eq!(
+                (heartbeat.msg_type(), heartbeat.get(112)),
+                ("0", Some("PING"))
+            );
+            assert_eq!(heartbeat.parse::<u64>(34), reset.parse::<u64>(36));
+        });
+        finish(&mut broker, acceptor);
+        assert!(broker.is_logged_on());
+    }
+
+    #[test]
+    fn test_heartbeat() {
+        let (mut broker, acceptor) = session(|acceptor| {
+            let heartbeat = acceptor.receive();
+            assert_eq!((heartbeat.msg_type(), heartbeat.get(112)), ("0", None));
+        });
+        broker.config.heartbeat_interval = 0;
+        finish(&mut broker, acceptor);
+    }
+
+    #[test]
+    fn test_logout() {
+        let (mut broker, acceptor) = session(|acceptor| {
+            acceptor.send(Message::new("5"));
+            assert_eq!(acceptor.receive().msg_type(), "5");
+        });
+        finish(&mut broker, acceptor);
+        assert!(!broker.is_logged_on());
+    }
+
+    #[test]
+    fn test_malformed_frame() {
+        let (mut broker, acceptor) = session(|acceptor| {
+            let message = Message::new("0").with_header("BROKER", "CLIENT", 2);
+            let mut bytes = message.encode();
+            let checksum = bytes.len() - 2;
+            bytes[checksum] = if bytes[checksum] == b'0' { b'1' } else { b'0' };
+            acceptor.stream.write_all(&bytes).unwrap();
+            // Keeps the connection open until the broker has read the frame
+            acceptor.receive();
+        });
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let err = loop {
+            assert!(Instant::now() < deadline, "malformed frame not detected");
+            if let Err(err) = broker.poll() {
+                break err;
+            }
+            thread::sleep(Duration::from_millis(5));
+        };
+        assert!(err.starts_with("checksum is"), "{}", err);
+        broker.logout().unwrap();
+        acceptor.join().unwrap();
+    }
+
+    #[test]
+    fn test_rejects() {
+        let (mut broker, acceptor) = session(|acceptor| {
+            let order = acceptor.receive();
+            let report = acceptor
+                .report(&order, "8", "8")
+                .with_field(58, "unknown symbol");
+            acceptor.send(report);
+
+            let order = acceptor.receive();
+            let report = acceptor.report(&order, "0", "0");
+            acceptor.send(report);
+            for (response_to, text) in [("2", "too late to replace"), ("1", "too late to cancel")] {
+                let request = acceptor.receive();
+                let reject = Message::new("9")
+                    .with_field(11, request.get(11).unwrap())
+                    .with_field(41, request.get(41).unwrap())
+                    .with_field(39, 0)
+                    .with_field(434, response_to)
+                    .with_field(58, text);
+                acceptor.send(reject);
+            }
+            assert_eq!(acceptor.receive().msg_type(), "5");
+        });
+
+        broker
+            .submit("XX", Side::Buy, 1.0, OrderType::Market)
+            .unwrap();
+        wait(&mut broker, 1);
+        assert_eq!(broker.events()[0].status, OrderStatus::Rejected);
+        assert_eq!(broker.events()[0].reason.as_deref(), Some("unknown symbol"));
+        assert!(broker.orders().is_empty());
+
+        let id = broker
+            .submit("ES", Side::Buy, 1.0, OrderType::Limit(90.0))
+            .unwrap();
+        wait(&mut broker, 2);
+        broker.replace(id, 2.0, OrderType::Limit(95.0)).unwrap();
+        assert_eq!(broker.orders()[0].order_type, OrderType::Limit(95.0));
+        wait(&mut broker, 3);
+        assert_eq!(broker.orders()[0].order_type, OrderType::Limit(90.0));
+        assert_eq!(broker.orders()[0].quantity, 1.0);
+
+        broker.cancel(id).unwrap();
+        assert!(broker.orders().is_empty());
+        wait(&mut broker, 4);
+        assert_eq!(broker.orders()[0].id, id);
+        let reasons: Vec<Option<&str>> = broker.events()[2..]
+            .iter()
+            .map(|event| event.reason.as_deref())
+            .collect();
+        assert_eq!(
+            reasons,
+            [Some("too late to replace"), Some("too late to cancel")]
+        );
+        broker.logout().unwrap();
+        finish(&mut broker, acceptor);
+    }
+}
@@ -0,0 +1,258 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+const BEGIN_STRING: &str = "FIX.4.4";
+/// Separates the fields of a message.
+const SOH: u8 = 0x01;
+
+// Tags of the fields used by the initiator
+pub(crate) const ACCOUNT: u32 = 1;
+pub(crate) const BEGIN_SEQ_NO: u32 = 7;
+pub(crate) const CL_ORD_ID: u32 = 11;
+pub(crate) const COMMISSION: u32 = 12;
+pub(crate) const END_SEQ_NO: u32 = 16;
+pub(crate) const LAST_PX: u32 = 31;
+pub(crate) const LAST_QTY: u32 = 32;
+pub(crate) const MSG_SEQ_NUM: u32 = 34;
+pub(crate) const NEW_SEQ_NO: u32 = 36;
+pub(crate) const ORDER_QTY: u32 = 38;
+pub(crate) const ORD_STATUS: u32 = 39;
+pub(crate) const ORD_TYPE: u32 = 40;
+pub(crate) const ORIG_CL_ORD_ID: u32 = 41;
+pub(crate) const POSS_DUP_FLAG: u32 = 43;
+pub(crate) const PRICE: u32 = 44;
+pub(crate) const SENDER_COMP_ID: u32 = 49;
+pub(crate) const SENDING_TIME: u32 = 52;
+pub(crate) const SIDE: u32 = 54;
+pub(crate) const SYMBOL: u32 = 55;
+pub(crate) const TARGET_COMP_ID: u32 = 56;
+pub(crate) const TEXT: u32 = 58;
+pub(crate) const TRANSACT_TIME: u32 = 60;
+pub(crate) const ENCRYPT_METHOD: u32 = 98;
+pub(crate) const STOP_PX: u32 = 99;
+pub(crate) const HEART_BT_INT: u32 = 108;
+pub(crate) const TEST_REQ_ID: u32 = 112;
+pub(crate) const GAP_FILL_FLAG: u32 = 123;
+pub(crate) const RESET_SEQ_NUM_FLAG: u32 = 141;
+pub(crate) const EXEC_TYPE: u32 = 150;
+pub(crate) const LEAVES_QTY: u32 = 151;
+pub(crate) const CXL_REJ_RESPONSE_TO: u32 = 434;
+
+/// A FIX 4.4 message
+///
+/// Holds the type and the fields of a message in order. The begin string,
+/// body length and checksum are added when the message is encoded and
+/// checked when it is decoded.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    msg_type: String,
+    fields: Vec<(u32, String)>,
+}
+
+impl Message {
+    pub fn new(msg_type: &str) -> Self {
+        Message {
+            msg_type: msg_type.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with_field(mut self, tag: u32, value: impl ToString) -> Self {
+        self.fields.push((tag, value.to_string()));
+        self
+    }
+
+    /// Puts the standard header before the other fields.
+    pub fn with_header(mut self, sender: &str, target: &str, seq_num: u64) -> Self {
+        let header = [
+            (SENDER_COMP_ID, sender.to_string()),
+            (TARGET_COMP_ID, target.to_string()),
+            (MSG_SEQ_NUM, seq_num.to_string()),
+            (SENDING_TIME, timestamp(Utc::now())),
+        ];
+        self.fields.splice(0..0, header);
+        self
+    }
+
+    pub fn msg_type(&self) -> &str {
+        &self.msg_type
+    }
+
+    /// Value of the first field with the tag.
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, _)| *field == tag)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Value of the first field with the tag, if it parses.
+    pub fn parse<T: FromStr>(&self, tag: u32) -> Option<T> {
+        self.get(tag)?.parse().ok()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = format!("35={}\x01", self.msg_type);
+        for (tag, value) in &self.fields {
+            body.push_str(&format!("{}={}\x01", tag, value));
+        }
+        let mut bytes = format!("8={}\x019={}\x01{}", BEGIN_STRING, body.len(), body).into_bytes();
+        let checksum = checksum(&bytes);
+        bytes.extend(format!("10={:03}\x01", checksum).into_bytes());
+        bytes
+    }
+
+    /// Decodes a complete message, checking its body length and checksum.
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        let text = std::str::from_utf8(bytes).map_err(|err| format!("invalid message: {}", err))?;
+        let text = text
+            .strip_suffix('\x01')
+            .ok_or("message does not end with a separator")?;
+        let mut fields = Vec::new();
+        for field in text.split('\x01') {
+            let (tag, value) = field
+                .split_once('=')
+                .ok_or_else(|| format!("invalid field {}", field))?;
+            let tag: u32 = tag.parse().map_err(|_| format!("invalid tag {}", tag))?;
+            fields.push((tag, value.to_string()));
+        }
+
+        match fields.first() {
+            Some((8, begin)) if begin == BEGIN_STRING => (),
+            _ => return Err(format!("message does not begin with 8={}", BEGIN_STRING)),
+        }
+        let length = match fields.get(1) {
+            Some((9, length)) => length
+                .parse::<usize>()
+                .map_err(|_| format!("invalid body length {}", length))?,
+            _ => return Err("message has no body length".to_string()),
+        };
+        let Some((10, expected)) = fields.last() else {
+            return Err("message has no checksum".to_string());
+        };
+        // The body runs from after the body length to before the checksum
+        let trailer = bytes.len() - expected.len() - 4;
+        let header = format!("8={}\x019={}\x01", BEGIN_STRING, fields[1].1).len();
+        if trailer.checked_sub(header) != Some(length) {
+            return Err(format!(
+                "body length is {}, not {}",
+                trailer.saturating_sub(header),
+                length
+            ));
+        }
+        let checksum = checksum(&bytes[..trailer]);
+        if expected.parse() != Ok(checksum) {
+            return Err(format!("checksum is {:03}, not {}", checksum, expected));
+        }
+
+        let msg_type = match fields.get(2) {
+            Some((35, msg_type)) => msg_type.clone(),
+            _ => return Err("message has no type".to_string()),
+        };
+        Ok(Message {
+            msg_type,
+            fields: fields[3..fields.len() - 1].to_vec(),
+        })
+    }
+
+    /// Takes the first message off the front of the bytes received, if it
+    /// is complete. A message without a body length is dropped up to the
+    /// start of the next message.
+    pub fn read(buffer: &mut Vec<u8>) -> Result<Option<Self>, String> {
+        let mut separators = buffer
+            .iter()
+            .enumerate()
+            .filter(|(_, byte)| **byte == SOH)
+            .map(|(index, _)| index);
+        let (Some(first), Some(second)) = (separators.next(), separators.next()) else {
+            return Ok(None);
+        };
+        let length = std::str::from_utf8(&buffer[first + 1..second])
+            .ok()
+            .and_then(|field| field.strip_prefix("9="))
+            .and_then(|length| length.parse::<usize>().ok());
+        let Some(length) = length else {
+            let next = buffer
+                .windows(5)
+                .skip(1)
+                .position(|bytes| bytes == b"8=FIX")
+                .map_or(buffer.len(), |position| position + 1);
+            buffer.drain(..next);
+            return Err("message has no body length".to_string());
+        };
+        // The checksum field is always 10=nnn
+        let end = second + 1 + length + 7;
+        if buffer.len() < end {
+            return Ok(None);
+        }
+        let bytes: Vec<u8> = buffer.drain(..end).collect();
+        Message::decode(&bytes).map(Some)
+    }
+}
+
+/// Time in the format of FIX timestamps, in UTC with milliseconds.
+pub fn timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%d-%H:%M:%S%.3f").to_string()
+}
+
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().map(|byte| *byte as u32).sum::<u32>() % 256
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::broker::Message;
+
+    fn heartbeat() -> Message {
+        Message::new("0")
+            .with_field(49, "CLIENT")
+            .with_field(56, "BROKER")
+            .with_field(34, 1)
+            .with_field(52, "20230102-10:00:00.000")
+    }
+
+    #[test]
+    fn test_encode() {
+        let bytes = heartbeat().encode();
+        assert_eq!(
+            String::from_utf8(bytes.clone())
+                .unwrap()
+                .replace('\x01', "|"),
+            "8=FIX.4.4|9=55|35=0|49=CLIENT|56=BROKER|34=1|52=20230102-10:00:00.000|10=053|"
+        );
+        let message = Message::decode(&bytes).unwrap();
+        assert_eq!(message, heartbeat());
+        assert_eq!(message.get(56), Some("BROKER"));
+        assert_eq!(message.parse::<u64>(34), Some(1));
+
+        let corrupted = String::from_utf8(bytes)
+            .unwrap()
+            .replace("BROKER", "BROKEN");
+        assert!(Message::decode(corrupted.as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_read() {
+        let mut buffer = heartbeat().encode();
+        buffer.extend(Message::new("1").with_field(112, "TEST").encode());
+        let mut received = buffer.split_off(20);
+        assert_eq!(Message::read(&mut buffer), Ok(None));
+
+        buffer.append(&mut received);
+        assert_eq!(Message::read(&mut buffer), Ok(Some(heartbeat())));
+        let message = Message::read(&mut buffer).unwrap().unwrap();
+        assert_eq!((message.msg_type(), message.get(112)), ("1", Some("TEST")));
+        assert!(buffer.is_empty());
+        assert_eq!(Message::read(&mut buffer), Ok(None));
+
+        // A message without a body length is dropped up to the next one
+        let mut buffer = b"8=FIX.4.4\x0135=0\x0110=000\x01".to_vec();
+        buffer.extend(heartbeat().encode());
+        assert!(Message::read(&mut buffer).is_err());
+        assert_eq!(Message::read(&mut buffer), Ok(Some(heartbeat())));
+        let mut buffer = b"8=FIX.4.4\x0135=0\x01".to_vec();
+        assert!(Message::read(&mut buffer).is_err());
+        assert!(buffer.is_empty());
+    }
+}
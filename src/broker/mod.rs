@@ -0,0 +1,9 @@
+mod adapter;
+mod fix;
+mod message;
+mod state;
+
+pub use adapter::Broker;
+pub use fix::{FixBroker, FixConfig};
+pub use message::Message;
+pub use state::{AccountState, MAX_HISTORY, OrderEvent, OrderStatus, PositionState};
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::backtest::{Account, Order, Rejection, Side};
+
+/// Most events a broker keeps, and fills and trades its account keeps, so
+/// that a session running for a long time stays bounded. Once there are more,
+/// the oldest are dropped until half as many are left.
+pub const MAX_HISTORY: usize = 10000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OrderStatus {
+    Submitted,
+    PartiallyFilled,
+    Filled,
+    Replaced,
+    Cancelled,
+    Rejected,
+}
+
+/// A change in the status of an order. Orders rejected before reaching the
+/// broker have no id.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OrderEvent {
+    pub order_id: Option<u64>,
+    pub symbol: String,
+    pub side: Side,
+    /// Quantity of the order, or of the fill for fills.
+    pub quantity: f32,
+    pub status: OrderStatus,
+    /// Price of the fill, for fills.
+    pub price: Option<f32>,
+    pub timestamp: String,
+    /// Why the order was rejected, for rejected orders.
+    pub reason: Option<String>,
+}
+
+impl OrderEvent {
+    /// Drops the oldest events once there are more than `MAX_HISTORY`,
+    /// returning how many were dropped.
+    pub fn trim(events: &mut Vec<OrderEvent>) -> usize {
+        if events.len() <= MAX_HISTORY {
+            return 0;
+        }
+        let dropped = events.len() - MAX_HISTORY / 2;
+        events.drain(..dropped);
+        dropped
+    }
+
+    pub fn new(order: &Order, status: OrderStatus, timestamp: &str) -> Self {
+        OrderEvent {
+            order_id: Some(order.id),
+            symbol: order.symbol.clone(),
+            side: order.side,
+            quantity: order.quantity,
+            status,
+            price: None,
+            timestamp: timestamp.to_string(),
+            reason: None,
+        }
+    }
+
+    /// The event of an order rejected by a risk manager.
+    pub fn rejected(rejection: &Rejection) -> Self {
+        OrderEvent {
+            order_id: None,
+            symbol: rejection.symbol.clone(),
+            side: rejection.side,
+            quantity: rejection.quantity,
+            status: OrderStatus::Rejected,
+            price: None,
+            timestamp: rejection.timestamp.clone(),
+            reason: Some(rejection.reason.clone()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PositionState {
+    pub symbol: String,
+    pub quantity: f32,
+    pub average_price: f32,
+    /// Last traded price, if any.
+    pub price: Option<f32>,
+    pub unrealized_profit: f64,
+}
+
+impl PositionState {
+    /// The positions of an account, valued at the last traded prices.
+    pub fn from_account(account: &Account, prices: &BTreeMap<String, f32>) -> Vec<Self> {
+        account
+            .positions
+            .iter()
+            .map(|(symbol, position)| {
+                let price = prices.get(symbol).copied();
+                PositionState {
+                    symbol: symbol.clone(),
+                    quantity: position.quantity,
+                    average_price: position.average_price,
+                    price,
+                    unrealized_profit: price.map_or(0.0, |price| {
+                        ((price - position.average_price) * position.quantity) as f64
+                            * account.contract(symbol).point_value()
+                    }),
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AccountState {
+    pub timestamp: String,
+    pub cash: f64,
+    pub equity: f64,
+    /// Profit of the closed trades, net of commission.
+    pub realized_profit: f64,
+    pub unrealized_profit: f64,
+    pub commission: f64,
+    pub open_orders: usize,
+    pub halted: bool,
+}
+
+impl AccountState {
+    /// The state of an account, valued at the last traded prices.
+    pub fn new(
+        timestamp: &str,
+        account: &Account,
+        prices: &BTreeMap<String, f32>,
+        halted: bool,
+    ) -> Self {
+        AccountState {
+            timestamp: timestamp.to_string(),
+            cash: account.cash,
+            equity: account.equity(prices),
+            realized_profit: account.realized_profit(),
+            unrealized_profit: PositionState::from_account(account, prices)
+                .iter()
+                .map(|position| position.unrealized_profit)
+                .sum(),
+            commission: account.commission(),
+            open_orders: account.orders.len(),
+            halted,
+        }
+    }
+}
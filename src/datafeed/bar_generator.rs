@@ -25,6 +25,8 @@ pub fn generate_from_ticks(mut ticks: &mut Vec<Tick>, interval: &Interval) -> Ve
     for tick in ticks.iter_mut().skip(1) {
         // keep count of volume
         count = count + tick.volume;
+        high = high.max(tick.price);
+        low = low.min(tick.price);
 
         while count >= bar_interval {
             count = count - bar_interval;
@@ -56,52 +58,73 @@ pub async fn generate_bars(
     from: &String,
     to: &Option<String>,
 ) -> Result<Vec<Bar>, anyhow::Error> {
-    let mut bars = Vec::new();
     let mut result: QueryResult<Tick> =
         fetch_ticks(&database, &symbol, &exchange, &from, &to, &None, &None)
             .await
             .expect("Failed to generate bars");
 
-    // TODO - handle case where no ticks are returned
     // TODO - remove multiple ticks with same timestamp
     // TODO - handle case where ticks are not in order
-    if result.data.is_empty() {
-        return Ok(bars);
-    }
+    Ok(generate_from_ticks(&mut result.data, interval))
+}
 
-    let bar_interval = interval.as_usize() as f32;
-    let first_tick = get_first_tick(&mut result.data);
-    let first_price = first_tick.as_ref().map_or(0.0, |tick| tick.price);
-    let mut open = first_price;
-    let mut high = first_price;
-    let mut low = first_price;
-    let mut count = first_tick.as_ref().map_or(0.0, |tick| tick.volume);
+/// Builds bars of an interval from ticks as they arrive, the way
+/// `generate_from_ticks` builds them from a batch: a bar closes on the tick
+/// that takes its volume to the interval, and the first tick only opens the
+/// first bar.
+pub struct BarBuilder {
+    interval: f32,
+    bar: Option<Bar>,
+    count: f32,
+}
 
-    // We skip the first tick since it is already used to set open, high, low
-    for tick in result.data.iter_mut().skip(1) {
-        // keep count of volume
-        count = count + tick.volume;
+impl BarBuilder {
+    pub fn new(interval: &Interval) -> Self {
+        BarBuilder {
+            interval: interval.as_usize() as f32,
+            bar: None,
+            count: 0.0,
+        }
+    }
 
-        while count >= bar_interval {
-            count = count - bar_interval;
-            let bar = Bar {
+    /// Adds a tick, returning the bars it closes.
+    pub fn push(&mut self, tick: &Tick) -> Vec<Bar> {
+        let mut bars = Vec::new();
+        let Some(bar) = self.bar.as_mut() else {
+            self.bar = Some(Bar {
                 timestamp: tick.timestamp.clone(),
-                open,
-                high,
-                low,
+                open: tick.price,
+                high: tick.price,
+                low: tick.price,
                 close: tick.price,
-                volume: bar_interval,
+                volume: 0.0,
+            });
+            self.count = tick.volume;
+            return bars;
+        };
+
+        bar.high = bar.high.max(tick.price);
+        bar.low = bar.low.min(tick.price);
+        self.count += tick.volume;
+        while self.count >= self.interval {
+            self.count -= self.interval;
+            bars.push(Bar {
+                timestamp: tick.timestamp.clone(),
+                close: tick.price,
+                volume: self.interval,
+                ..bar.clone()
+            });
+            *bar = Bar {
+                timestamp: tick.timestamp.clone(),
+                open: tick.price,
+                high: tick.price,
+                low: tick.price,
+                close: tick.price,
+                volume: 0.0,
             };
-            bars.push(bar);
-
-            // reset values
-            open = tick.price;
-            high = tick.price;
-            low = tick.price;
         }
+        bars
     }
-
-    Ok(bars)
 }
 
 fn get_first_tick(ticks: &mut Vec<Tick>) -> Option<Tick> {
@@ -110,3 +133,62 @@ fn get_first_tick(ticks: &mut Vec<Tick>) -> Option<Tick> {
     }
     Some(ticks.first().cloned().unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::datafeed::{BarBuilder, generate_from_ticks};
+    use crate::domain::{Interval, Tick};
+
+    #[test]
+    fn test_bar_builder() {
+        let mut builder = BarBuilder::new(&Interval::T49);
+        let tick = |second: usize, price: f32, volume: f32| Tick {
+            timestamp: format!("2023-01-01T00:00:{:02}Z", second),
+            symbol: "ES".to_string(),
+            price,
+            volume,
+        };
+
+        assert!(builder.push(&tick(0, 100.0, 10.0)).is_empty());
+        assert!(builder.push(&tick(1, 103.0, 20.0)).is_empty());
+        let bars = builder.push(&tick(2, 101.0, 20.0));
+        assert_eq!(bars.len(), 1);
+        assert_eq!(
+            (bars[0].open, bars[0].high, bars[0].low, bars[0].close),
+            (100.0, 103.0, 100.0, 101.0)
+        );
+        assert_eq!(bars[0].timestamp, "2023-01-01T00:00:02Z");
+        // One tick may close several bars
+        let bars = builder.push(&tick(3, 99.0, 100.0));
+        assert_eq!(bars.len(), 2);
+        assert_eq!((bars[1].open, bars[1].close), (99.0, 99.0));
+    }
+
+    #[test]
+    fn test_generate_from_ticks() {
+        let prices = [
+            100.0, 103.0, 97.0, 101.0, 99.0, 104.0, 98.0, 102.0, 96.0, 100.0,
+        ];
+        let volumes = [10.0, 20.0, 30.0, 5.0, 60.0, 10.0, 40.0, 100.0, 1.0, 49.0];
+        let mut ticks: Vec<Tick> = prices
+            .iter()
+            .zip(volumes)
+            .enumerate()
+            .map(|(second, (price, volume))| Tick {
+                timestamp: format!("2023-01-01T00:00:{:02}Z", second),
+                symbol: "ES".to_string(),
+                price: *price,
+                volume,
+            })
+            .collect();
+
+        let mut builder = BarBuilder::new(&Interval::T49);
+        let built: Vec<_> = ticks.iter().flat_map(|tick| builder.push(tick)).collect();
+        let bars = generate_from_ticks(&mut ticks, &Interval::T49);
+        assert_eq!(bars, built);
+        assert_eq!(
+            (bars[0].open, bars[0].high, bars[0].low, bars[0].close),
+            (100.0, 103.0, 97.0, 97.0)
+        );
+    }
+}
@@ -1,7 +1,10 @@
 use chrono::NaiveDateTime;
 
 use crate::datafeed::generate_from_ticks;
-use crate::domain::{Bar, EnhancedSeries, Indicators, Interval, Resolution, Series, Tick};
+use crate::domain::{
+    Bar, EnhancedSeries, Indicators, Interval, Resolution, Series, Tick, parse_timestamp,
+    required_lookback, required_lookback_start,
+};
 use crate::storage::{Database, Error, QueryResult};
 
 #[derive(Clone)]
@@ -24,6 +27,7 @@ impl HistoricalData {
         to: &Option<NaiveDateTime>,
         limit_lower: &Option<usize>,
         limit_upper: &Option<usize>,
+        lookback: &Option<usize>,
         indicators: &Option<Vec<Indicators>>,
     ) -> Result<EnhancedSeries, Error> {
         match resolution {
@@ -32,6 +36,13 @@ impl HistoricalData {
                 let to_str = to
                     .as_ref()
                     .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+                // Unless the caller asks for a specific lookback, fetch just
+                // enough bars before `from` to warm up the requested indicators.
+                let lookback = lookback.or_else(|| {
+                    indicators
+                        .as_ref()
+                        .map(|indicators| required_lookback(indicators))
+                });
 
                 fetch_enhanced_series(
                     &self.database,
@@ -43,6 +54,7 @@ impl HistoricalData {
                     &to_str,
                     limit_lower,
                     limit_upper,
+                    &lookback,
                     indicators,
                 )
                 .await
@@ -50,6 +62,63 @@ impl HistoricalData {
             _ => Err(Error::UnsupportedResolution),
         }
     }
+
+    /// The bars before `from` that warm up the indicators, e.g. for a
+    /// strategy to start trading live ticks on.
+    pub async fn fetch_warmup_bars(
+        &self,
+        symbol: &str,
+        exchange: &str,
+        interval: &Interval,
+        from: &NaiveDateTime,
+        indicators: &[Indicators],
+    ) -> Result<Vec<Bar>, Error> {
+        let from = from.format("%Y-%m-%d %H:%M:%S").to_string();
+        let mut ticks = fetch_warmup_ticks(
+            &self.database,
+            symbol,
+            exchange,
+            interval,
+            &from,
+            required_lookback(indicators),
+            indicators,
+        )
+        .await?;
+        Ok(generate_from_ticks(&mut ticks, interval))
+    }
+
+    /// Fetches the raw ticks of a symbol in ascending order, e.g. to replay
+    /// them through a backtest.
+    pub async fn fetch_ticks(
+        &self,
+        symbol: &String,
+        exchange: &String,
+        from: &NaiveDateTime,
+        to: &Option<NaiveDateTime>,
+        limit_lower: &Option<usize>,
+        limit_upper: &Option<usize>,
+    ) -> Result<Vec<Tick>, Error> {
+        let from_str = from.format("%Y-%m-%d %H:%M:%S").to_string();
+        let to_str = to
+            .as_ref()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string());
+        let result = fetch_ticks(
+            &self.database,
+            symbol,
+            exchange,
+            &from_str,
+            &to_str,
+            limit_lower,
+            limit_upper,
+        )
+        .await
+        .map_err(Error::DatabaseError)?;
+
+        if result.data.is_empty() {
+            return Err(Error::NoDataFound);
+        }
+        Ok(result.data)
+    }
 }
 
 pub async fn fetch_enhanced_series<'a>(
@@ -62,9 +131,10 @@ pub async fn fetch_enhanced_series<'a>(
     to: &'a Option<String>,
     limit_lower: &'a Option<usize>,
     limit_upper: &'a Option<usize>,
+    lookback: &'a Option<usize>,
     indicators: &'a Option<Vec<Indicators>>,
 ) -> Result<EnhancedSeries, Error> {
-    let mut result: QueryResult<Tick> = fetch_ticks(
+    let result: QueryResult<Tick> = fetch_ticks(
         &database,
         &symbol,
         &exchange,
@@ -80,17 +150,160 @@ pub async fn fetch_enhanced_series<'a>(
         return Err(Error::NoDataFound);
     }
 
-    let series = Series::new(
-        symbol.clone(),
-        resolution.clone(),
-        interval.clone(),
-        generate_from_ticks(&mut result.data, &interval),
+    // Prepend ticks from before `from` so indicators are warmed up by the
+    // first bar of the requested window. Bars that close before the window
+    // are dropped once the indicators have been calculated.
+    let mut ticks = fetch_warmup_ticks(
+        database,
+        symbol,
+        exchange,
+        interval,
+        from,
+        lookback.unwrap_or(0),
+        indicators.as_deref().unwrap_or(&[]),
     )
-    .enhance_with(indicators.as_ref().unwrap_or(&vec![]));
+    .await?;
+    let window_start = result.data[0].timestamp.clone();
+    ticks.extend(result.data);
+
+    let bars = generate_from_ticks(&mut ticks, &interval);
+    let warmup_bars = bars
+        .iter()
+        .take_while(|bar| bar.timestamp < window_start)
+        .count();
+
+    let series = Series::new(symbol.clone(), resolution.clone(), interval.clone(), bars)
+        .enhance_with(indicators.as_ref().unwrap_or(&vec![]))
+        .skip_bars(warmup_bars);
 
     Ok(series)
 }
 
+/// Fetches the ticks before `from` that warm up the indicators, in
+/// ascending order: enough for `lookback` bars, or every tick since the
+/// anchor of indicators accumulating from one, such as a session VWAP.
+pub async fn fetch_warmup_ticks(
+    database: &Database,
+    symbol: &str,
+    exchange: &str,
+    interval: &Interval,
+    from: &str,
+    lookback: usize,
+    indicators: &[Indicators],
+) -> Result<Vec<Tick>, Error> {
+    let ticks = match lookback {
+        0 => Vec::new(),
+        bars => fetch_lookback_ticks(database, symbol, exchange, interval, from, bars).await?,
+    };
+    let start = NaiveDateTime::parse_from_str(from, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .and_then(|from| required_lookback_start(indicators, from));
+    match start {
+        Some(start)
+            if ticks
+                .first()
+                .and_then(|tick| parse_timestamp(&tick.timestamp))
+                .is_none_or(|first| first > start) =>
+        {
+            let start = start.format("%Y-%m-%d %H:%M:%S").to_string();
+            fetch_ticks_since(database, symbol, exchange, &start, from).await
+        }
+        _ => Ok(ticks),
+    }
+}
+
+/// Number of ticks fetched at a time while looking back.
+const LOOKBACK_PAGE_SIZE: usize = 10000;
+
+/// Fetches the ticks immediately preceding `from`, in ascending order, with
+/// enough volume to generate `bars` bars of the given interval. Returns fewer
+/// ticks when the table does not go back far enough.
+///
+/// Ticks are fetched a page at a time, newest first, until there is enough
+/// volume.
+pub async fn fetch_lookback_ticks(
+    database: &Database,
+    symbol: &str,
+    exchange: &str,
+    interval: &Interval,
+    from: &str,
+    bars: usize,
+) -> Result<Vec<Tick>, Error> {
+    let table_name = get_table_name(&exchange.to_string(), &Resolution::TICK);
+    let query = format!(
+        "SELECT * FROM {} WHERE symbol = '{}' AND timestamp < '{}' ORDER BY timestamp DESC",
+        table_name,
+        symbol.to_uppercase(),
+        from
+    );
+
+    let required_volume = (bars * interval.as_usize()) as f32;
+    let mut volume = 0.0;
+    let mut ticks = Vec::new();
+    while volume < required_volume {
+        let offset = ticks.len();
+        let result: QueryResult<Tick> = database
+            .exec::<Tick>(
+                query.as_str(),
+                &Some(offset),
+                &Some(offset + LOOKBACK_PAGE_SIZE),
+            )
+            .await?;
+        let fetched = result.data.len();
+        for tick in result.data {
+            if volume >= required_volume {
+                break;
+            }
+            volume += tick.volume;
+            ticks.push(tick);
+        }
+        if fetched < LOOKBACK_PAGE_SIZE {
+            break;
+        }
+    }
+    ticks.reverse();
+
+    Ok(ticks)
+}
+
+/// Fetches the ticks from `start` up to `from`, in ascending order, a page
+/// at a time.
+pub async fn fetch_ticks_since(
+    database: &Database,
+    symbol: &str,
+    exchange: &str,
+    start: &str,
+    from: &str,
+) -> Result<Vec<Tick>, Error> {
+    let table_name = get_table_name(&exchange.to_string(), &Resolution::TICK);
+    let query = format!(
+        "SELECT * FROM {} WHERE symbol = '{}' AND timestamp >= '{}' AND timestamp < '{}' ORDER BY timestamp",
+        table_name,
+        symbol.to_uppercase(),
+        start,
+        from
+    );
+
+    let mut ticks = Vec::new();
+    loop {
+        let offset = ticks.len();
+        let result: QueryResult<Tick> = database
+            .exec::<Tick>(
+                query.as_str(),
+                &Some(offset),
+                &Some(offset + LOOKBACK_PAGE_SIZE),
+            )
+            .await?;
+        let fetched = result.data.len();
+        ticks.extend(result.data);
+        if fetched < LOOKBACK_PAGE_SIZE {
+            break;
+        }
+    }
+
+    Ok(ticks)
+}
+
 pub async fn fetch_bars(
     database: &Database,
     symbol: &String,
@@ -149,7 +362,7 @@ pub async fn fetch_ticks(
         .unwrap())
 }
 
-fn get_table_name(exchange: &String, resolution: &Resolution) -> String {
+pub(crate) fn get_table_name(exchange: &String, resolution: &Resolution) -> String {
     let mut table_name = exchange.to_lowercase();
     let resolution = resolution.to_string().to_lowercase();
     table_name.push_str("_");
@@ -0,0 +1,144 @@
+use crate::{
+    datafeed::get_table_name,
+    domain::{Resolution, Tick},
+    storage::{Database, Error, QueryResult},
+};
+
+/// Ticks of a symbol as they are stored, polled from the tick table of its
+/// exchange. Each poll returns the ticks after the last one returned, in
+/// ascending order.
+///
+/// Ticks stored after a poll may have the timestamp of the last tick
+/// returned, so polls start from that timestamp and skip the ticks returned
+/// with it, which are stored first.
+///
+/// Each poll returns at most `POLL_LIMIT` ticks, so that a feed following
+/// ticks from long ago pages through them.
+pub struct LiveTicks {
+    symbol: String,
+    exchange: String,
+    last: String,
+    /// Number of ticks returned with the last timestamp, none for all the
+    /// ticks at `from`.
+    seen: Option<usize>,
+}
+
+/// Most ticks returned by a poll.
+pub const POLL_LIMIT: usize = 10000;
+
+impl LiveTicks {
+    /// Follows the ticks after `from`, a timestamp such as
+    /// `2023-01-01T00:00:00.000000Z`.
+    pub fn new(symbol: &str, exchange: &str, from: &str) -> Self {
+        LiveTicks {
+            symbol: symbol.to_string(),
+            exchange: exchange.to_string(),
+            last: from.to_string(),
+            seen: None,
+        }
+    }
+
+    pub async fn poll(&mut self, database: &Database) -> Result<Vec<Tick>, Error> {
+        let table_name = get_table_name(&self.exchange, &Resolution::TICK);
+        let query = format!(
+            "SELECT * FROM {} WHERE symbol = '{}' AND timestamp >= '{}' ORDER BY timestamp",
+            table_name,
+            self.symbol.to_uppercase(),
+            self.last
+        );
+        // The ticks already returned with the last timestamp come first
+        let limit = self.seen.unwrap_or(0) + POLL_LIMIT;
+        let result: QueryResult<Tick> = database
+            .exec::<Tick>(query.as_str(), &Some(0), &Some(limit))
+            .await?;
+        Ok(self.unseen(result.data))
+    }
+
+    /// Drops the ticks already returned from ticks starting at the last
+    /// timestamp, and moves past the others.
+    fn unseen(&mut self, mut ticks: Vec<Tick>) -> Vec<Tick> {
+        let repeated = ticks
+            .iter()
+            .take_while(|tick| tick.timestamp == self.last)
+            .count();
+        let seen = self.seen.map_or(repeated, |seen| seen.min(repeated));
+        ticks.drain(..seen);
+
+        if let Some(tick) = ticks.last() {
+            let at_last = ticks
+                .iter()
+                .rev()
+                .take_while(|other| other.timestamp == tick.timestamp)
+                .count();
+            if tick.timestamp == self.last {
+                self.seen = Some(seen + at_last);
+            } else {
+                self.last = tick.timestamp.clone();
+                self.seen = Some(at_last);
+            }
+        }
+        ticks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::datafeed::LiveTicks;
+    use crate::domain::Tick;
+
+    fn ticks(timestamps: &[(&str, f32)]) -> Vec<Tick> {
+        timestamps
+            .iter()
+            .map(|(timestamp, price)| Tick {
+                timestamp: timestamp.to_string(),
+                symbol: "ES".to_string(),
+                price: *price,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    fn prices(ticks: &[Tick]) -> Vec<f32> {
+        ticks.iter().map(|tick| tick.price).collect()
+    }
+
+    #[test]
+    fn test_unseen() {
+        let mut live = LiveTicks::new("ES", "CME", "2023-01-01T00:00:00.000000Z");
+        // Ticks at `from` are not followed
+        let polled = live.unseen(ticks(&[
+            ("2023-01-01T00:00:00.000000Z", 1.0),
+            ("2023-01-01T00:00:01.000000Z", 2.0),
+            ("2023-01-01T00:00:01.000000Z", 3.0),
+        ]));
+        assert_eq!(prices(&polled), [2.0, 3.0]);
+
+        // A tick stored later with the last timestamp
+        let polled = live.unseen(ticks(&[
+            ("2023-01-01T00:00:01.000000Z", 2.0),
+            ("2023-01-01T00:00:01.000000Z", 3.0),
+            ("2023-01-01T00:00:01.000000Z", 4.0),
+        ]));
+        assert_eq!(prices(&polled), [4.0]);
+        let polled = live.unseen(ticks(&[
+            ("2023-01-01T00:00:01.000000Z", 2.0),
+            ("2023-01-01T00:00:01.000000Z", 3.0),
+            ("2023-01-01T00:00:01.000000Z", 4.0),
+        ]));
+        assert!(polled.is_empty());
+
+        let polled = live.unseen(ticks(&[
+            ("2023-01-01T00:00:01.000000Z", 2.0),
+            ("2023-01-01T00:00:01.000000Z", 3.0),
+            ("2023-01-01T00:00:01.000000Z", 4.0),
+            ("2023-01-01T00:00:01.000000Z", 5.0),
+            ("2023-01-01T00:00:02.000000Z", 6.0),
+        ]));
+        assert_eq!(prices(&polled), [5.0, 6.0]);
+        let polled = live.unseen(ticks(&[
+            ("2023-01-01T00:00:02.000000Z", 6.0),
+            ("2023-01-01T00:00:03.000000Z", 7.0),
+        ]));
+        assert_eq!(prices(&polled), [7.0]);
+    }
+}
@@ -1,5 +1,7 @@
 mod bar_generator;
 mod historical;
+mod live;
 
 pub use bar_generator::*;
 pub use historical::*;
+pub use live::*;
@@ -1,3 +1,4 @@
+use chrono::{DateTime, NaiveDateTime};
 use serde::{Deserialize, Serialize};
 
 /// Data structure for aggregated financial data, representing a single
@@ -11,3 +12,19 @@ pub struct Bar {
     pub close: f32,
     pub volume: f32,
 }
+
+impl Bar {
+    /// The bar timestamp in UTC, if it can be parsed.
+    pub fn datetime(&self) -> Option<NaiveDateTime> {
+        parse_timestamp(&self.timestamp)
+    }
+}
+
+/// Parses an RFC 3339 timestamp, e.g. `2024-12-05T20:30:00.000000Z` as
+/// returned by QuestDB, or a timestamp without an offset, as UTC.
+pub fn parse_timestamp(timestamp: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|datetime| datetime.naive_utc())
+        .or_else(|_| timestamp.parse::<NaiveDateTime>())
+        .ok()
+}
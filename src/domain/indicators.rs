@@ -1,26 +1,91 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::vec;
 
 use crate::domain::Bar;
-use crate::indicators::{AMA, SMA, TMA, XAverage};
+use crate::indicators::{
+    ADX, ALMA, AMA, ATR, AdaptiveSMA, Aroon, BollingerBands, CCI, CandlestickPattern, Candlesticks,
+    ChaikinMoneyFlow, ChaikinOscillator, DEMA, DominantCycle, DonchianChannels, FisherTransform,
+    HMA, InstantaneousTrendline, KeltnerChannels, MACD, MAMA, MFI, OBV, ParabolicSAR, RSI,
+    RoofingFilter, SMA, Stochastic, SuperSmoother, SuperTrend, T3, TEMA, TMA, VIDYA, VWAP,
+    VWAPAnchor, WMA, WilliamsR, XAverage,
+};
 
+/// Indicator calculations produce one value per bar. Bars that precede the
+/// indicator's warm-up period are `None`, serialized as JSON `null`.
 pub trait Calculation {
-    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<f32>;
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>>;
+
+    /// Calculates the primary line together with any secondary lines, such as
+    /// the MACD signal and histogram. Single line indicators have none.
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        (self.calculate(data, params), Lines::new())
+    }
 }
 
+/// Lookback for path dependent indicators, such as the Parabolic SAR, whose
+/// state only resets on a trend reversal.
+const PATH_DEPENDENT_LOOKBACK: usize = 100;
+
+/// Indicator values, one per bar.
+pub type Line = VecDeque<Option<f32>>;
+
+/// Secondary indicator lines keyed by name.
+pub type Lines = BTreeMap<String, Line>;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Indicator {
     pub name: String,
     pub params: Vec<f32>,
-    pub data: VecDeque<f32>,
+    pub data: VecDeque<Option<f32>>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub lines: Lines,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Indicators {
+    AdaptiveSMA(f32), // cycle_fraction
+    ADX(usize),
+    ALMA(usize, f32, f32),
     AMA(usize, usize, usize), // er_period, fast_period, slow_period
+    Aroon(usize),
+    ATR(usize),
+    BollingerBands(usize, f32), // period, multiplier
+    Candlestick(CandlestickPattern),
+    Candlesticks,
+    CCI(usize),
+    ChaikinMoneyFlow(usize),
+    ChaikinOscillator(usize, usize), // fast_period, slow_period
+    DEMA(usize),
+    DominantCycle,
+    DonchianChannels(usize),
+    FisherTransform(usize),
+    HMA(usize),
+    InstantaneousTrendline,
+    KeltnerChannels(usize, usize, f32), // period, atr_period, multiplier
+    MACD(usize, usize, usize),          // fast_period, slow_period, signal_period
+    MAMA(f32, f32),                     // fast_limit, slow_limit
+    MFI(usize),
+    OBV,
+    ParabolicSAR(f32, f32),      // step, max_step
+    RoofingFilter(usize, usize), // hp_period, ss_period
+    RSI(usize),
     SMA(usize),
+    Stochastic(usize, usize, usize), // k_period, k_smoothing, d_period
+    SuperSmoother(usize),
+    SuperTrend(usize, f32), // atr_period, multiplier
+    T3(usize, f32),
+    TEMA(usize),
     TMA(usize),
+    VIDYA(usize, usize),
+    VWAP(VWAPAnchor, f32), // anchor, multiplier
+    WilliamsR(usize),
+    WMA(usize),
     XAverage(usize),
     Unknown(String),
 }
@@ -28,9 +93,43 @@ pub enum Indicators {
 impl Indicators {
     pub fn name(&self) -> &str {
         match self {
+            Indicators::AdaptiveSMA(_) => "adaptive_sma",
+            Indicators::ADX(_) => "adx",
+            Indicators::ALMA(_, _, _) => "alma",
             Indicators::AMA(_, _, _) => "ama",
+            Indicators::Aroon(_) => "aroon",
+            Indicators::ATR(_) => "atr",
+            Indicators::BollingerBands(_, _) => "bollinger",
+            Indicators::Candlestick(pattern) => pattern.name(),
+            Indicators::Candlesticks => "candlesticks",
+            Indicators::CCI(_) => "cci",
+            Indicators::ChaikinMoneyFlow(_) => "cmf",
+            Indicators::ChaikinOscillator(_, _) => "chaikin_oscillator",
+            Indicators::DEMA(_) => "dema",
+            Indicators::DominantCycle => "dominant_cycle",
+            Indicators::DonchianChannels(_) => "donchian",
+            Indicators::FisherTransform(_) => "fisher",
+            Indicators::HMA(_) => "hma",
+            Indicators::InstantaneousTrendline => "instantaneous_trendline",
+            Indicators::KeltnerChannels(_, _, _) => "keltner",
+            Indicators::MACD(_, _, _) => "macd",
+            Indicators::MAMA(_, _) => "mama",
+            Indicators::MFI(_) => "mfi",
+            Indicators::OBV => "obv",
+            Indicators::ParabolicSAR(_, _) => "parabolic_sar",
+            Indicators::RoofingFilter(_, _) => "roofing",
+            Indicators::RSI(_) => "rsi",
             Indicators::SMA(_) => "sma",
+            Indicators::Stochastic(_, _, _) => "stochastic",
+            Indicators::SuperSmoother(_) => "super_smoother",
+            Indicators::SuperTrend(_, _) => "supertrend",
+            Indicators::T3(_, _) => "t3",
+            Indicators::TEMA(_) => "tema",
             Indicators::TMA(_) => "tma",
+            Indicators::VIDYA(_, _) => "vidya",
+            Indicators::VWAP(_, _) => "vwap",
+            Indicators::WilliamsR(_) => "williams_r",
+            Indicators::WMA(_) => "wma",
             Indicators::XAverage(_) => "xaverage",
             Indicators::Unknown(name) => name,
         }
@@ -38,42 +137,566 @@ impl Indicators {
 
     pub fn params(&self) -> Vec<f32> {
         match self {
+            Indicators::AdaptiveSMA(cycle_fraction) => vec![*cycle_fraction],
+            Indicators::ADX(period) => vec![*period as f32],
+            Indicators::ALMA(period, offset, sigma) => vec![*period as f32, *offset, *sigma],
             Indicators::AMA(er_period, fast_period, slow_period) => {
                 vec![*er_period as f32, *fast_period as f32, *slow_period as f32]
             }
+            Indicators::Aroon(period) => vec![*period as f32],
+            Indicators::ATR(period) => vec![*period as f32],
+            Indicators::BollingerBands(period, multiplier) => vec![*period as f32, *multiplier],
+            Indicators::Candlestick(_) | Indicators::Candlesticks => vec![],
+            Indicators::CCI(period) => vec![*period as f32],
+            Indicators::ChaikinMoneyFlow(period) => vec![*period as f32],
+            Indicators::ChaikinOscillator(fast_period, slow_period) => {
+                vec![*fast_period as f32, *slow_period as f32]
+            }
+            Indicators::DEMA(period) => vec![*period as f32],
+            Indicators::DominantCycle => vec![],
+            Indicators::DonchianChannels(period) => vec![*period as f32],
+            Indicators::FisherTransform(period) => vec![*period as f32],
+            Indicators::HMA(period) => vec![*period as f32],
+            Indicators::InstantaneousTrendline => vec![],
+            Indicators::KeltnerChannels(period, atr_period, multiplier) => {
+                vec![*period as f32, *atr_period as f32, *multiplier]
+            }
+            Indicators::MACD(fast_period, slow_period, signal_period) => {
+                vec![
+                    *fast_period as f32,
+                    *slow_period as f32,
+                    *signal_period as f32,
+                ]
+            }
+            Indicators::MAMA(fast_limit, slow_limit) => vec![*fast_limit, *slow_limit],
+            Indicators::MFI(period) => vec![*period as f32],
+            Indicators::OBV => vec![],
+            Indicators::ParabolicSAR(step, max_step) => vec![*step, *max_step],
+            Indicators::RoofingFilter(hp_period, ss_period) => {
+                vec![*hp_period as f32, *ss_period as f32]
+            }
+            Indicators::RSI(period) => vec![*period as f32],
             Indicators::SMA(period) => vec![*period as f32],
+            Indicators::Stochastic(k_period, k_smoothing, d_period) => {
+                vec![*k_period as f32, *k_smoothing as f32, *d_period as f32]
+            }
+            Indicators::SuperSmoother(period) => vec![*period as f32],
+            Indicators::SuperTrend(atr_period, multiplier) => vec![*atr_period as f32, *multiplier],
+            Indicators::T3(period, volume_factor) => vec![*period as f32, *volume_factor],
+            Indicators::TEMA(period) => vec![*period as f32],
             Indicators::TMA(period) => vec![*period as f32],
+            Indicators::VIDYA(period, cmo_period) => vec![*period as f32, *cmo_period as f32],
+            Indicators::VWAP(anchor, multiplier) => vec![anchor.code(), *multiplier],
+            Indicators::WilliamsR(period) => vec![*period as f32],
+            Indicators::WMA(period) => vec![*period as f32],
             Indicators::XAverage(period) => vec![*period as f32],
             Indicators::Unknown(_) => vec![],
         }
     }
+
+    /// Number of bars that must precede a bar for the indicator value at that
+    /// bar to be fully warmed up. Recursive averages are given three times
+    /// their slowest period so that the influence of the seed value decays.
+    /// Path dependent indicators are given a fixed number of bars, while
+    /// indicators accumulating from an anchor need the bars since
+    /// `lookback_start` instead.
+    pub fn lookback(&self) -> usize {
+        match self {
+            Indicators::AdaptiveSMA(cycle_fraction) => {
+                DominantCycle::WARMUP + (DominantCycle::MAX_PERIOD * cycle_fraction).ceil() as usize
+            }
+            Indicators::ADX(period) => 5 * period,
+            Indicators::ALMA(period, _, _) => period.saturating_sub(1),
+            Indicators::AMA(er_period, _, slow_period) => er_period + 3 * slow_period,
+            Indicators::Aroon(period) => *period,
+            Indicators::ATR(period) => 4 * period,
+            Indicators::BollingerBands(period, _) => period.saturating_sub(1),
+            Indicators::Candlestick(_) | Indicators::Candlesticks => Candlesticks::LOOKBACK,
+            Indicators::CCI(period) => period.saturating_sub(1),
+            Indicators::ChaikinMoneyFlow(period) => period.saturating_sub(1),
+            Indicators::ChaikinOscillator(_, slow_period) => 3 * slow_period,
+            Indicators::DEMA(period) => 3 * period,
+            Indicators::DominantCycle => DominantCycle::WARMUP,
+            Indicators::DonchianChannels(period) => period.saturating_sub(1),
+            Indicators::FisherTransform(period) => 3 * period,
+            Indicators::HMA(period) => (period + HMA::smoothing_period(*period)).saturating_sub(2),
+            Indicators::InstantaneousTrendline => PATH_DEPENDENT_LOOKBACK,
+            Indicators::KeltnerChannels(period, atr_period, _) => (3 * period).max(4 * atr_period),
+            Indicators::MACD(_, slow_period, signal_period) => 3 * slow_period + signal_period,
+            Indicators::MAMA(_, _) => PATH_DEPENDENT_LOOKBACK,
+            Indicators::MFI(period) => *period,
+            Indicators::OBV => 0,
+            Indicators::ParabolicSAR(_, _) => PATH_DEPENDENT_LOOKBACK,
+            Indicators::RoofingFilter(hp_period, ss_period) => 3 * hp_period.max(ss_period),
+            Indicators::RSI(period) => 4 * period,
+            Indicators::SMA(period) => period.saturating_sub(1),
+            Indicators::Stochastic(k_period, k_smoothing, d_period) => {
+                (k_period + k_smoothing + d_period).saturating_sub(3)
+            }
+            Indicators::SuperSmoother(period) => 3 * period,
+            Indicators::SuperTrend(atr_period, _) => (4 * atr_period).max(PATH_DEPENDENT_LOOKBACK),
+            Indicators::T3(period, _) => 6 * period,
+            Indicators::TEMA(period) => 3 * period,
+            Indicators::TMA(period) => (2 * period).saturating_sub(2),
+            Indicators::VIDYA(period, cmo_period) => cmo_period + 3 * period,
+            Indicators::VWAP(_, _) => 0,
+            Indicators::WilliamsR(period) => period.saturating_sub(1),
+            Indicators::WMA(period) => period.saturating_sub(1),
+            Indicators::XAverage(period) => 3 * period,
+            Indicators::Unknown(_) => 0,
+        }
+    }
+
+    /// Start of the period that the indicator accumulates over at `from`,
+    /// for indicators that accumulate from an anchor, such as the session of
+    /// a VWAP.
+    pub fn lookback_start(&self, from: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self {
+            Indicators::VWAP(anchor, _) => anchor.start(from),
+            _ => None,
+        }
+    }
+
+    /// Calculates the indicator over the bars, including context that is not
+    /// part of the numeric parameters, such as the anchor of a VWAP.
+    pub fn calculate(&self, data: &Vec<Bar>) -> (VecDeque<Option<f32>>, Lines) {
+        match self {
+            Indicators::VWAP(anchor, multiplier) => {
+                VWAP.calculate_anchored(data, anchor, *multiplier)
+            }
+            _ => calculate(self.name(), data, &self.params()),
+        }
+    }
+
+    /// Binds the trading session of the symbol and an optional anchor
+    /// timestamp to indicators that accumulate from an anchor.
+    pub fn with_context(self, session: &str, timezone: &str, anchor: &Option<String>) -> Self {
+        match self {
+            Indicators::VWAP(VWAPAnchor::Session { .. }, multiplier) => Indicators::VWAP(
+                VWAPAnchor::Session {
+                    session: session.to_string(),
+                    timezone: timezone.to_string(),
+                },
+                multiplier,
+            ),
+            Indicators::VWAP(VWAPAnchor::Week { .. }, multiplier) => Indicators::VWAP(
+                VWAPAnchor::Week {
+                    timezone: timezone.to_string(),
+                },
+                multiplier,
+            ),
+            Indicators::VWAP(VWAPAnchor::Timestamp(_), multiplier) => {
+                Indicators::VWAP(VWAPAnchor::Timestamp(anchor.clone()), multiplier)
+            }
+            indicator => indicator,
+        }
+    }
+
+    /// Validates the indicator parameters, returning a description of the
+    /// first invalid parameter found.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Indicators::AdaptiveSMA(cycle_fraction) => {
+                validate_multiplier(self.name(), "cycle_fraction", *cycle_fraction)
+            }
+            Indicators::ALMA(period, offset, sigma) => {
+                validate_period(self.name(), "period", *period)?;
+                validate_fraction(self.name(), "offset", *offset)?;
+                validate_multiplier(self.name(), "sigma", *sigma)
+            }
+            Indicators::AMA(er_period, fast_period, slow_period) => {
+                validate_period(self.name(), "er_period", *er_period)?;
+                validate_period(self.name(), "fast_period", *fast_period)?;
+                validate_period(self.name(), "slow_period", *slow_period)?;
+                if fast_period > slow_period {
+                    return Err(format!(
+                        "ama: fast_period ({}) must not exceed slow_period ({})",
+                        fast_period, slow_period
+                    ));
+                }
+                Ok(())
+            }
+            Indicators::ChaikinOscillator(fast_period, slow_period) => {
+                validate_period(self.name(), "fast_period", *fast_period)?;
+                validate_period(self.name(), "slow_period", *slow_period)?;
+                if fast_period >= slow_period {
+                    return Err(format!(
+                        "chaikin_oscillator: fast_period ({}) must be less than slow_period ({})",
+                        fast_period, slow_period
+                    ));
+                }
+                Ok(())
+            }
+            Indicators::MACD(fast_period, slow_period, signal_period) => {
+                validate_period(self.name(), "fast_period", *fast_period)?;
+                validate_period(self.name(), "slow_period", *slow_period)?;
+                validate_period(self.name(), "signal_period", *signal_period)?;
+                if fast_period >= slow_period {
+                    return Err(format!(
+                        "macd: fast_period ({}) must be less than slow_period ({})",
+                        fast_period, slow_period
+                    ));
+                }
+                Ok(())
+            }
+            Indicators::MAMA(fast_limit, slow_limit) => {
+                validate_multiplier(self.name(), "fast_limit", *fast_limit)?;
+                validate_multiplier(self.name(), "slow_limit", *slow_limit)?;
+                validate_fraction(self.name(), "fast_limit", *fast_limit)?;
+                if slow_limit > fast_limit {
+                    return Err(format!(
+                        "mama: slow_limit ({}) must not exceed fast_limit ({})",
+                        slow_limit, fast_limit
+                    ));
+                }
+                Ok(())
+            }
+            Indicators::ParabolicSAR(step, max_step) => {
+                validate_multiplier(self.name(), "step", *step)?;
+                validate_multiplier(self.name(), "max_step", *max_step)?;
+                if step > max_step {
+                    return Err(format!(
+                        "parabolic_sar: step ({}) must not exceed max_step ({})",
+                        step, max_step
+                    ));
+                }
+                Ok(())
+            }
+            Indicators::RoofingFilter(hp_period, ss_period) => {
+                validate_period(self.name(), "hp_period", *hp_period)?;
+                validate_period(self.name(), "ss_period", *ss_period)
+            }
+            Indicators::Stochastic(k_period, k_smoothing, d_period) => {
+                validate_period(self.name(), "k_period", *k_period)?;
+                validate_period(self.name(), "k_smoothing", *k_smoothing)?;
+                validate_period(self.name(), "d_period", *d_period)
+            }
+            Indicators::BollingerBands(period, multiplier) => {
+                validate_period(self.name(), "period", *period)?;
+                validate_multiplier(self.name(), "multiplier", *multiplier)
+            }
+            Indicators::KeltnerChannels(period, atr_period, multiplier) => {
+                validate_period(self.name(), "period", *period)?;
+                validate_period(self.name(), "atr_period", *atr_period)?;
+                validate_multiplier(self.name(), "multiplier", *multiplier)
+            }
+            Indicators::ADX(period)
+            | Indicators::Aroon(period)
+            | Indicators::ATR(period)
+            | Indicators::CCI(period)
+            | Indicators::ChaikinMoneyFlow(period)
+            | Indicators::DEMA(period)
+            | Indicators::DonchianChannels(period)
+            | Indicators::FisherTransform(period)
+            | Indicators::HMA(period)
+            | Indicators::MFI(period)
+            | Indicators::RSI(period)
+            | Indicators::SMA(period)
+            | Indicators::SuperSmoother(period)
+            | Indicators::TEMA(period)
+            | Indicators::TMA(period)
+            | Indicators::WilliamsR(period)
+            | Indicators::WMA(period)
+            | Indicators::XAverage(period) => validate_period(self.name(), "period", *period),
+            Indicators::SuperTrend(atr_period, multiplier) => {
+                validate_period(self.name(), "atr_period", *atr_period)?;
+                validate_multiplier(self.name(), "multiplier", *multiplier)
+            }
+            Indicators::T3(period, volume_factor) => {
+                validate_period(self.name(), "period", *period)?;
+                validate_fraction(self.name(), "volume_factor", *volume_factor)
+            }
+            Indicators::VIDYA(period, cmo_period) => {
+                validate_period(self.name(), "period", *period)?;
+                validate_period(self.name(), "cmo_period", *cmo_period)
+            }
+            Indicators::VWAP(anchor, multiplier) => {
+                validate_multiplier(self.name(), "multiplier", *multiplier)?;
+                if *anchor == VWAPAnchor::Timestamp(None) {
+                    return Err("vwap: an anchor timestamp is required".to_string());
+                }
+                anchor
+                    .periods(&[])
+                    .map(|_| ())
+                    .map_err(|err| format!("vwap: {}", err))
+            }
+            Indicators::Candlestick(_)
+            | Indicators::Candlesticks
+            | Indicators::DominantCycle
+            | Indicators::InstantaneousTrendline
+            | Indicators::OBV => Ok(()),
+            Indicators::Unknown(name) => Err(format!("{} is not a supported indicator", name)),
+        }
+    }
+}
+
+/// The largest lookback required by any of the given indicators.
+pub fn required_lookback(indicators: &[Indicators]) -> usize {
+    indicators
+        .iter()
+        .map(|indicator| indicator.lookback())
+        .max()
+        .unwrap_or(0)
+}
+
+/// The earliest start of the periods that the given indicators accumulate
+/// over at `from`, if any accumulates from an anchor.
+pub fn required_lookback_start(
+    indicators: &[Indicators],
+    from: NaiveDateTime,
+) -> Option<NaiveDateTime> {
+    indicators
+        .iter()
+        .filter_map(|indicator| indicator.lookback_start(from))
+        .min()
+}
+
+fn validate_period(indicator: &str, param: &str, period: usize) -> Result<(), String> {
+    if period == 0 {
+        return Err(format!("{}: {} must be at least 1", indicator, param));
+    }
+    Ok(())
 }
 
 impl From<(&str, &Vec<f32>)> for Indicators {
     fn from((name, params): (&str, &Vec<f32>)) -> Self {
         match name {
+            "adaptive_sma" => Indicators::AdaptiveSMA(params.first().cloned().unwrap_or(1.0)),
+            "adx" => Indicators::ADX(params.first().cloned().unwrap_or(14.0) as usize),
+            "alma" => {
+                let period = params.first().cloned().unwrap_or(9.0) as usize;
+                let offset = params.get(1).cloned().unwrap_or(0.85);
+                let sigma = params.get(2).cloned().unwrap_or(6.0);
+                Indicators::ALMA(period, offset, sigma)
+            }
             "ama" => {
                 let er_period = params.get(0).cloned().unwrap_or(10.0) as usize;
                 let fast_period = params.get(1).cloned().unwrap_or(2.0) as usize;
                 let slow_period = params.get(2).cloned().unwrap_or(30.0) as usize;
                 Indicators::AMA(er_period, fast_period, slow_period)
             }
+            "aroon" => Indicators::Aroon(params.first().cloned().unwrap_or(25.0) as usize),
+            "atr" => Indicators::ATR(params.first().cloned().unwrap_or(14.0) as usize),
+            "bollinger" => {
+                let period = params.first().cloned().unwrap_or(20.0) as usize;
+                let multiplier = params.get(1).cloned().unwrap_or(2.0);
+                Indicators::BollingerBands(period, multiplier)
+            }
+            "candlesticks" => Indicators::Candlesticks,
+            "cci" => Indicators::CCI(params.first().cloned().unwrap_or(20.0) as usize),
+            "chaikin_oscillator" => {
+                let fast_period = params.first().cloned().unwrap_or(3.0) as usize;
+                let slow_period = params.get(1).cloned().unwrap_or(10.0) as usize;
+                Indicators::ChaikinOscillator(fast_period, slow_period)
+            }
+            "cmf" => Indicators::ChaikinMoneyFlow(params.first().cloned().unwrap_or(20.0) as usize),
+            "dema" => Indicators::DEMA(params.first().cloned().unwrap_or(14.0) as usize),
+            "dominant_cycle" => Indicators::DominantCycle,
+            "donchian" => {
+                Indicators::DonchianChannels(params.first().cloned().unwrap_or(20.0) as usize)
+            }
+            "fisher" => {
+                Indicators::FisherTransform(params.first().cloned().unwrap_or(10.0) as usize)
+            }
+            "hma" => Indicators::HMA(params.first().cloned().unwrap_or(14.0) as usize),
+            "instantaneous_trendline" => Indicators::InstantaneousTrendline,
+            "keltner" => {
+                let period = params.first().cloned().unwrap_or(20.0) as usize;
+                let atr_period = params.get(1).cloned().unwrap_or(10.0) as usize;
+                let multiplier = params.get(2).cloned().unwrap_or(2.0);
+                Indicators::KeltnerChannels(period, atr_period, multiplier)
+            }
+            "macd" => {
+                let fast_period = params.first().cloned().unwrap_or(12.0) as usize;
+                let slow_period = params.get(1).cloned().unwrap_or(26.0) as usize;
+                let signal_period = params.get(2).cloned().unwrap_or(9.0) as usize;
+                Indicators::MACD(fast_period, slow_period, signal_period)
+            }
+            "mama" => {
+                let fast_limit = params.first().cloned().unwrap_or(0.5);
+                let slow_limit = params.get(1).cloned().unwrap_or(0.05);
+                Indicators::MAMA(fast_limit, slow_limit)
+            }
+            "mfi" => Indicators::MFI(params.first().cloned().unwrap_or(14.0) as usize),
+            "obv" => Indicators::OBV,
+            "parabolic_sar" => {
+                let step = params.first().cloned().unwrap_or(0.02);
+                let max_step = params.get(1).cloned().unwrap_or(0.2);
+                Indicators::ParabolicSAR(step, max_step)
+            }
+            "roofing" => {
+                let hp_period = params.first().cloned().unwrap_or(48.0) as usize;
+                let ss_period = params.get(1).cloned().unwrap_or(10.0) as usize;
+                Indicators::RoofingFilter(hp_period, ss_period)
+            }
+            "rsi" => Indicators::RSI(params.first().cloned().unwrap_or(14.0) as usize),
             "sma" => Indicators::SMA(params.get(0).cloned().unwrap_or(14.0) as usize),
+            "stochastic" => {
+                let k_period = params.first().cloned().unwrap_or(14.0) as usize;
+                let k_smoothing = params.get(1).cloned().unwrap_or(3.0) as usize;
+                let d_period = params.get(2).cloned().unwrap_or(3.0) as usize;
+                Indicators::Stochastic(k_period, k_smoothing, d_period)
+            }
+            "super_smoother" => {
+                Indicators::SuperSmoother(params.first().cloned().unwrap_or(10.0) as usize)
+            }
+            "supertrend" => {
+                let atr_period = params.first().cloned().unwrap_or(10.0) as usize;
+                let multiplier = params.get(1).cloned().unwrap_or(3.0);
+                Indicators::SuperTrend(atr_period, multiplier)
+            }
+            "t3" => {
+                let period = params.first().cloned().unwrap_or(5.0) as usize;
+                let volume_factor = params.get(1).cloned().unwrap_or(0.7);
+                Indicators::T3(period, volume_factor)
+            }
+            "tema" => Indicators::TEMA(params.first().cloned().unwrap_or(14.0) as usize),
             "tma" => Indicators::TMA(params.get(0).cloned().unwrap_or(9.0) as usize),
+            "vidya" => {
+                let period = params.first().cloned().unwrap_or(14.0) as usize;
+                let cmo_period = params.get(1).cloned().unwrap_or(9.0) as usize;
+                Indicators::VIDYA(period, cmo_period)
+            }
+            "vwap" => {
+                let anchor = VWAPAnchor::from_code(params.first().cloned().unwrap_or(0.0));
+                let multiplier = params.get(1).cloned().unwrap_or(1.0);
+                Indicators::VWAP(anchor, multiplier)
+            }
+            "williams_r" => Indicators::WilliamsR(params.first().cloned().unwrap_or(14.0) as usize),
+            "wma" => Indicators::WMA(params.first().cloned().unwrap_or(14.0) as usize),
             "xaverage" => Indicators::XAverage(params.get(0).cloned().unwrap_or(9.0) as usize),
             // Add more indicators here as needed
-            _ => Indicators::Unknown(name.to_string()),
+            _ => name
+                .parse()
+                .map(Indicators::Candlestick)
+                .unwrap_or_else(|_| Indicators::Unknown(name.to_string())),
         }
     }
 }
 
-pub fn calculate(name: &str, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<f32> {
+pub fn calculate(name: &str, data: &Vec<Bar>, params: &Vec<f32>) -> (VecDeque<Option<f32>>, Lines) {
     match name {
-        "ama" => AMA.calculate(data, params),
-        "sma" => SMA.calculate(data, params),
-        "tma" => TMA.calculate(data, params),
-        "xaverage" => XAverage.calculate(data, params),
         // Add more indicators as needed
-        _ => VecDeque::new(),
+        "adaptive_sma" => AdaptiveSMA.calculate_lines(data, params),
+        "adx" => ADX.calculate_lines(data, params),
+        "alma" => ALMA.calculate_lines(data, params),
+        "ama" => AMA.calculate_lines(data, params),
+        "aroon" => Aroon.calculate_lines(data, params),
+        "atr" => ATR.calculate_lines(data, params),
+        "bollinger" => BollingerBands.calculate_lines(data, params),
+        "candlesticks" => Candlesticks.calculate_lines(data, params),
+        "cci" => CCI.calculate_lines(data, params),
+        "chaikin_oscillator" => ChaikinOscillator.calculate_lines(data, params),
+        "cmf" => ChaikinMoneyFlow.calculate_lines(data, params),
+        "dema" => DEMA.calculate_lines(data, params),
+        "dominant_cycle" => DominantCycle.calculate_lines(data, params),
+        "donchian" => DonchianChannels.calculate_lines(data, params),
+        "fisher" => FisherTransform.calculate_lines(data, params),
+        "hma" => HMA.calculate_lines(data, params),
+        "instantaneous_trendline" => InstantaneousTrendline.calculate_lines(data, params),
+        "keltner" => KeltnerChannels.calculate_lines(data, params),
+        "macd" => MACD.calculate_lines(data, params),
+        "mama" => MAMA.calculate_lines(data, params),
+        "mfi" => MFI.calculate_lines(data, params),
+        "obv" => OBV.calculate_lines(data, params),
+        "parabolic_sar" => ParabolicSAR.calculate_lines(data, params),
+        "roofing" => RoofingFilter.calculate_lines(data, params),
+        "rsi" => RSI.calculate_lines(data, params),
+        "sma" => SMA.calculate_lines(data, params),
+        "stochastic" => Stochastic.calculate_lines(data, params),
+        "super_smoother" => SuperSmoother.calculate_lines(data, params),
+        "supertrend" => SuperTrend.calculate_lines(data, params),
+        "t3" => T3.calculate_lines(data, params),
+        "tema" => TEMA.calculate_lines(data, params),
+        "tma" => TMA.calculate_lines(data, params),
+        "vidya" => VIDYA.calculate_lines(data, params),
+        "vwap" => VWAP.calculate_lines(data, params),
+        "williams_r" => WilliamsR.calculate_lines(data, params),
+        "wma" => WMA.calculate_lines(data, params),
+        "xaverage" => XAverage.calculate_lines(data, params),
+        _ => match name.parse() {
+            Ok(pattern) => (Candlesticks.calculate_pattern(data, pattern), Lines::new()),
+            Err(_) => (VecDeque::new(), Lines::new()),
+        },
+    }
+}
+
+fn validate_multiplier(indicator: &str, param: &str, multiplier: f32) -> Result<(), String> {
+    if !multiplier.is_finite() || multiplier <= 0.0 {
+        return Err(format!(
+            "{}: {} must be a positive number",
+            indicator, param
+        ));
+    }
+    Ok(())
+}
+
+fn validate_fraction(indicator: &str, param: &str, fraction: f32) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err(format!("{}: {} must be between 0 and 1", indicator, param));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::{Indicators, required_lookback, required_lookback_start};
+    use crate::indicators::VWAPAnchor;
+
+    #[test]
+    fn test_required_lookback() {
+        assert_eq!(required_lookback(&[]), 0);
+        assert_eq!(required_lookback(&[Indicators::SMA(14)]), 13);
+        assert_eq!(
+            required_lookback(&[
+                Indicators::SMA(20),
+                Indicators::TMA(9),
+                Indicators::XAverage(5),
+            ]),
+            19
+        );
+        assert_eq!(
+            required_lookback(&[Indicators::AMA(10, 2, 30), Indicators::SMA(50)]),
+            100
+        );
+    }
+
+    #[test]
+    fn test_required_lookback_start() {
+        let from = "2024-12-05T20:30:00".parse().unwrap();
+        assert_eq!(required_lookback_start(&[Indicators::SMA(14)], from), None);
+
+        let session = Indicators::VWAP(
+            VWAPAnchor::Session {
+                session: "1700-1600".to_string(),
+                timezone: "Etc/UTC".to_string(),
+            },
+            1.0,
+        );
+        let week = Indicators::VWAP(
+            VWAPAnchor::Week {
+                timezone: "Etc/UTC".to_string(),
+            },
+            1.0,
+        );
+        assert_eq!(session.lookback(), 0);
+        assert_eq!(
+            required_lookback_start(&[Indicators::SMA(14), session.clone()], from),
+            "2024-12-05T17:00:00".parse().ok()
+        );
+        assert_eq!(
+            required_lookback_start(&[session, week], from),
+            "2024-12-02T00:00:00".parse().ok()
+        );
+
+        let anchored = |anchor: &str| {
+            Indicators::VWAP(VWAPAnchor::Timestamp(Some(anchor.to_string())), 1.0)
+                .lookback_start(from)
+        };
+        assert_eq!(
+            anchored("2024-11-01T00:00:00Z"),
+            "2024-11-01T00:00:00".parse().ok()
+        );
+        assert_eq!(anchored("2024-12-06T00:00:00Z"), None);
     }
 }
@@ -3,15 +3,17 @@ mod indicators;
 mod interval;
 mod resolution;
 mod series;
+mod session;
 mod symbol_info;
 mod symbol_type;
 mod tick;
 
-pub use bar::Bar;
+pub use bar::{Bar, parse_timestamp};
 pub use indicators::*;
 pub use interval::Interval;
 pub use resolution::Resolution;
 pub use series::*;
+pub use session::Session;
 pub use symbol_info::SymbolInfo;
 pub use symbol_type::SymbolType;
 pub use tick::Tick;
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::domain::{Bar, Indicator, Indicators, Interval, Resolution, calculate};
+use crate::analysis::{
+    ChartPatterns, Divergence, Divergences, HarmonicPatterns, Mark, ReversalThreshold, Swing,
+    ZigZag,
+};
+use crate::domain::{Bar, Indicator, Indicators, Interval, Resolution};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Series {
@@ -17,6 +21,12 @@ pub struct EnhancedSeries {
     pub interval: Interval,
     pub data: Vec<Bar>,
     pub indicators: Option<Vec<Indicator>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub swings: Option<Vec<Swing>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marks: Option<Vec<Mark>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub divergences: Option<Vec<Divergence>>,
 }
 
 impl Series {
@@ -46,13 +56,131 @@ impl Series {
             indicators: Some(
                 indicators
                     .iter()
-                    .map(|ind| Indicator {
-                        name: ind.name().to_string(),
-                        params: ind.params().clone(),
-                        data: calculate(ind.name(), &self.data, &ind.params()),
+                    .map(|ind| {
+                        let (data, lines) = ind.calculate(&self.data);
+                        Indicator {
+                            name: ind.name().to_string(),
+                            params: ind.params().clone(),
+                            data,
+                            lines,
+                        }
                     })
                     .collect(),
             ),
+            swings: None,
+            marks: None,
+            divergences: None,
         }
     }
+
+    /// Swing highs and lows of the bars, confirmed by the given threshold.
+    pub fn swings(&self, threshold: &ReversalThreshold) -> Vec<Swing> {
+        ZigZag::new(threshold.clone()).swings(&self.data)
+    }
+}
+
+impl EnhancedSeries {
+    /// Drops the first `count` bars and their indicator values, e.g. bars
+    /// fetched only to warm up indicators before the requested window.
+    pub fn skip_bars(mut self, count: usize) -> Self {
+        let count = count.min(self.data.len());
+        self.data.drain(..count);
+        if let Some(indicators) = self.indicators.as_mut() {
+            for indicator in indicators.iter_mut() {
+                let n = count.min(indicator.data.len());
+                indicator.data.drain(..n);
+                for line in indicator.lines.values_mut() {
+                    let n = count.min(line.len());
+                    line.drain(..n);
+                }
+            }
+        }
+        if let Some(swings) = self.swings.as_mut() {
+            swings.retain(|swing| swing.index >= count);
+            for swing in swings.iter_mut() {
+                swing.index -= count;
+                swing.confirmed_at = swing.confirmed_at.map(|index| index - count);
+            }
+        }
+        if let Some(marks) = self.marks.as_mut() {
+            marks.retain(|mark| mark.pattern.points.iter().all(|point| point.index >= count));
+            for mark in marks.iter_mut() {
+                for point in mark.pattern.points.iter_mut() {
+                    point.index -= count;
+                }
+            }
+        }
+        if let Some(divergences) = self.divergences.as_mut() {
+            divergences.retain(|divergence| {
+                divergence.price[0].index >= count && divergence.oscillator[0].index >= count
+            });
+            for divergence in divergences.iter_mut() {
+                for point in divergence.price.iter_mut() {
+                    point.index -= count;
+                }
+                for point in divergence.oscillator.iter_mut() {
+                    point.index -= count;
+                }
+                divergence.confirmed_at = divergence.confirmed_at.map(|index| index - count);
+            }
+        }
+        self
+    }
+
+    /// Adds the swing highs and lows of the bars, confirmed by the given
+    /// threshold.
+    pub fn with_swings(mut self, threshold: &ReversalThreshold) -> Self {
+        self.swings = Some(ZigZag::new(threshold.clone()).swings(&self.data));
+        self
+    }
+
+    /// Adds chart marks for the harmonic and chart patterns found between
+    /// the swings confirmed by the given threshold, ordered by time.
+    pub fn with_patterns(mut self, threshold: &ReversalThreshold) -> Self {
+        let swings = ZigZag::new(threshold.clone()).swings(&self.data);
+        let mut marks: Vec<Mark> = HarmonicPatterns::default()
+            .scan(&swings)
+            .into_iter()
+            .chain(ChartPatterns::default().scan(&self.data, &swings))
+            .map(|pattern| Mark::new(pattern, &self.data))
+            .collect();
+        marks.sort_by_key(|mark| mark.time);
+        self.marks = Some(marks);
+        self
+    }
+
+    /// Adds the divergences between the swings confirmed by the given
+    /// threshold and an indicator line of the series, given as the name of
+    /// the indicator, e.g. `rsi`, optionally followed by the name of a
+    /// secondary line, e.g. `macd:histogram`.
+    pub fn with_divergences(
+        mut self,
+        source: &str,
+        threshold: &ReversalThreshold,
+    ) -> Result<Self, String> {
+        let (name, line_name) = match source.split_once(':') {
+            Some((name, line_name)) => (name, Some(line_name)),
+            None => (source, None),
+        };
+        let indicator = self
+            .indicators
+            .iter()
+            .flatten()
+            .find(|indicator| indicator.name == name)
+            .ok_or_else(|| format!("divergences: indicator '{}' was not requested", name))?;
+        let line = match line_name {
+            Some(line_name) => indicator.lines.get(line_name).ok_or_else(|| {
+                format!(
+                    "divergences: indicator '{}' has no line '{}'",
+                    name, line_name
+                )
+            })?,
+            None => &indicator.data,
+        };
+
+        let swings = ZigZag::new(threshold.clone()).swings(&self.data);
+        let divergences = Divergences::default().scan(&self.data, &swings, line, source);
+        self.divergences = Some(divergences);
+        Ok(self)
+    }
 }
@@ -0,0 +1,166 @@
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+
+/// Trading session of a symbol, parsed from the session format used by
+/// `SymbolInfo.session`, e.g. `24x7`, `0930-1600`, `0930-1200,1300-1600` or
+/// `1700-1600:23456` for a session starting the previous evening.
+///
+/// Only session start times are used to find session boundaries, so trading
+/// day suffixes are accepted but ignored. Of the comma separated windows of
+/// a trading day, only the first starts a session, so that the session does
+/// not restart after a break such as lunch. Timezones are IANA names such as
+/// `America/New_York`, so session starts follow daylight saving time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Session {
+    starts: Vec<NaiveTime>,
+    timezone: Tz,
+}
+
+impl Session {
+    pub fn parse(session: &str, timezone: &str) -> Result<Self, String> {
+        let timezone: Tz = timezone
+            .parse()
+            .map_err(|_| format!("{} is not a supported timezone", timezone))?;
+        let session = session.trim();
+        if session == "24x7" {
+            return Ok(Self {
+                starts: vec![NaiveTime::MIN],
+                timezone,
+            });
+        }
+
+        let mut starts = Vec::new();
+        for day in session.split('|') {
+            let mut windows = Vec::new();
+            for window in day.split(',') {
+                let hours = window.split(':').next().unwrap_or_default();
+                let start = hours
+                    .split('-')
+                    .next()
+                    .and_then(|start| NaiveTime::parse_from_str(start, "%H%M").ok())
+                    .filter(|_| hours.contains('-'))
+                    .ok_or_else(|| format!("{} is not a valid session", session))?;
+                windows.push(start);
+            }
+            starts.push(windows[0]);
+        }
+        starts.sort();
+        starts.dedup();
+
+        Ok(Self { starts, timezone })
+    }
+
+    /// Start of the session containing `timestamp`, both in UTC.
+    pub fn session_start(&self, timestamp: NaiveDateTime) -> NaiveDateTime {
+        let local = self.local(timestamp);
+        let latest_start = self
+            .starts
+            .iter()
+            .map(|start| {
+                let start_today = local.date().and_time(*start);
+                if start_today <= local {
+                    start_today
+                } else {
+                    start_today - Duration::days(1)
+                }
+            })
+            .max()
+            .unwrap_or(local);
+        self.utc(latest_start)
+    }
+
+    /// Start of the week, Monday at midnight in the session timezone,
+    /// containing `timestamp`, both in UTC.
+    pub fn week_start(&self, timestamp: NaiveDateTime) -> NaiveDateTime {
+        let local = self.local(timestamp);
+        let days_from_monday = local.weekday().num_days_from_monday() as i64;
+        let monday = local.date() - Duration::days(days_from_monday);
+        self.utc(monday.and_time(NaiveTime::MIN))
+    }
+
+    fn local(&self, timestamp: NaiveDateTime) -> NaiveDateTime {
+        self.timezone.from_utc_datetime(&timestamp).naive_local()
+    }
+
+    /// UTC time of a local time. Local times skipped when clocks go forward
+    /// are moved to the change, and repeated ones are taken at their first
+    /// occurrence.
+    fn utc(&self, local: NaiveDateTime) -> NaiveDateTime {
+        let mut time = local;
+        loop {
+            if let Some(utc) = self.timezone.from_local_datetime(&time).earliest() {
+                return utc.naive_utc();
+            }
+            time += Duration::minutes(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+
+    use crate::domain::Session;
+
+    fn datetime(s: &str) -> NaiveDateTime {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_session_start() {
+        let session = Session::parse("24x7", "Etc/UTC").unwrap();
+        assert_eq!(
+            session.session_start(datetime("2024-12-05T20:30:00")),
+            datetime("2024-12-05T00:00:00")
+        );
+
+        let session = Session::parse("1700-1600:23456", "Etc/GMT+5").unwrap();
+        assert_eq!(
+            session.session_start(datetime("2024-12-05T21:30:00")),
+            datetime("2024-12-04T22:00:00")
+        );
+        assert_eq!(
+            session.session_start(datetime("2024-12-05T22:30:00")),
+            datetime("2024-12-05T22:00:00")
+        );
+
+        let session = Session::parse("0930-1200,1300-1600", "UTC").unwrap();
+        assert_eq!(
+            session.session_start(datetime("2024-12-05T14:00:00")),
+            datetime("2024-12-05T09:30:00")
+        );
+        assert!(Session::parse("0930-1200,1300", "UTC").is_err());
+
+        assert!(Session::parse("0930", "UTC").is_err());
+        assert!(Session::parse("24x7", "Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn test_daylight_saving() {
+        let session = Session::parse("1800-1700", "America/New_York").unwrap();
+        assert_eq!(
+            session.session_start(datetime("2024-12-05T21:30:00")),
+            datetime("2024-12-04T23:00:00")
+        );
+        assert_eq!(
+            session.session_start(datetime("2024-07-10T21:30:00")),
+            datetime("2024-07-09T22:00:00")
+        );
+
+        // 02:30 is skipped when clocks go forward on 2024-03-10
+        let session = Session::parse("0230-1600", "America/New_York").unwrap();
+        assert_eq!(
+            session.session_start(datetime("2024-03-10T12:00:00")),
+            datetime("2024-03-10T07:00:00")
+        );
+    }
+
+    #[test]
+    fn test_week_start() {
+        let session = Session::parse("24x7", "Etc/UTC").unwrap();
+        assert_eq!(
+            session.week_start(datetime("2024-12-05T20:30:00")),
+            datetime("2024-12-02T00:00:00")
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::domain::SymbolType;
+use crate::domain::{Session, SymbolType};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub enum VisiblePlotSet {
@@ -55,3 +55,15 @@ pub struct SymbolInfo {
     pub visibible_plots_set: Option<VisiblePlotSet>,
     pub volume_precision: Option<i32>,
 }
+
+impl SymbolInfo {
+    /// The trading session of the symbol in its timezone.
+    pub fn trading_session(&self) -> Result<Session, String> {
+        Session::parse(&self.session, &self.timezone)
+    }
+
+    /// The minimum price movement, `minmov / pricescale`.
+    pub fn tick_size(&self) -> f32 {
+        self.minmov.max(1) as f32 / self.pricescale.max(1) as f32
+    }
+}
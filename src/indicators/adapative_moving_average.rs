@@ -27,6 +27,10 @@ pub struct AMA;
 /// - fast_period: The period for the fast smoothing factor
 /// - slow_period: The period for the slow smoothing factor
 ///
+/// The Efficiency Ratio needs `er_period` price changes, so the first
+/// `er_period` values are undefined. The average is seeded with the close
+/// preceding the first defined value.
+///
 /// https://corporatefinanceinstitute.com/resources/career-map/sell-side/capital-markets/kaufmans-adaptive-moving-average-kama/
 impl AMA {
     pub fn calculate_adaptive_moving_average(
@@ -35,57 +39,47 @@ impl AMA {
         er_period: usize,
         fast_period: usize,
         slow_period: usize,
-    ) -> VecDeque<f32> {
+    ) -> VecDeque<Option<f32>> {
         let mut ama_data = VecDeque::new();
         let mut price_changes = VecDeque::new();
-        let mut prev_prices = VecDeque::new();
         let mut prev_ama = 0.0;
-        let mut prev_price = 0.0;
-        let mut er_price = 0.0;
         let mut efficiency_ratio;
         let mut volatility;
         let mut smoothing_constant;
         let fastest = 2.0 / (fast_period as f32 + 1.0);
         let slowest = 2.0 / (slow_period as f32 + 1.0);
 
+        if er_period == 0 {
+            return data.iter().map(|_| None).collect();
+        }
+
         for (i, bar) in data.iter().enumerate() {
-            if i == 0 {
-                er_price = bar.close;
-                prev_price = bar.close;
-                prev_ama = bar.close;
-                price_changes.push_back(0.0);
-                prev_prices.push_back(bar.close);
-                ama_data.push_back(bar.close);
-                continue;
+            if i > 0 {
+                price_changes.push_back((bar.close - data[i - 1].close).abs());
+                if price_changes.len() > er_period {
+                    price_changes.pop_front();
+                }
             }
 
-            let price_change = (bar.close - prev_price).abs();
-            price_changes.push_back(price_change);
-            prev_prices.push_back(bar.close);
-
-            if price_changes.len() > er_period {
-                price_changes.pop_front();
-                er_price = prev_prices.pop_front().unwrap_or(er_price);
+            if i < er_period {
+                ama_data.push_back(None); // Not enough data yet
+                prev_ama = bar.close;
+                continue;
             }
 
-            if price_changes.len() == er_period {
-                volatility = price_changes.iter().sum::<f32>();
-                efficiency_ratio = if volatility != 0.0 {
-                    (bar.close - er_price).abs() / volatility
-                } else {
-                    0.0
-                };
+            volatility = price_changes.iter().sum::<f32>();
+            efficiency_ratio = if volatility != 0.0 {
+                (bar.close - data[i - er_period].close).abs() / volatility
             } else {
-                efficiency_ratio = 0.0; // Not enough data yet
-            }
+                0.0
+            };
 
             smoothing_constant = (efficiency_ratio * (fastest - slowest) + slowest).powi(2);
 
             let ama_value = prev_ama + smoothing_constant * (bar.close - prev_ama);
-            ama_data.push_back(prev_ama + smoothing_constant * (bar.close - prev_ama));
+            ama_data.push_back(Some(ama_value));
 
             prev_ama = ama_value;
-            prev_price = bar.close;
         }
 
         ama_data
@@ -93,7 +87,7 @@ impl AMA {
 }
 
 impl Calculation for AMA {
-    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<f32> {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
         let er_period = params.get(0).cloned().unwrap_or(10.0) as usize;
         let fast_period = params.get(1).cloned().unwrap_or(2.0) as usize;
         let slow_period = params.get(2).cloned().unwrap_or(30.0) as usize;
@@ -103,6 +97,8 @@ impl Calculation for AMA {
 
 #[cfg(test)]
 mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
     use crate::domain::Bar;
     use crate::indicators::adapative_moving_average::AMA;
 
@@ -158,10 +154,10 @@ mod tests {
             ama.calculate_adaptive_moving_average(&data, er_period, fast_period, slow_period);
 
         assert_eq!(result.len(), 5);
-        assert_eq!(result[0], 102.0);
-        assert_eq!(result[1], 102.888885);
-        assert_eq!(result[2], 102.910835);
-        assert_eq!(result[3], 103.32351);
-        assert_eq!(result[4], 104.51306);
+        assert_eq!(result[0], None);
+        assert_eq!(result[1], None);
+        assert_float_absolute_eq!(result[2].unwrap(), 103.80247, 1e-4);
+        assert_float_absolute_eq!(result[3].unwrap(), 104.03902, 1e-4);
+        assert_float_absolute_eq!(result[4].unwrap(), 104.91057, 1e-4);
     }
 }
@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::closes;
+use crate::indicators::{DominantCycle, SMA};
+
+pub struct AdaptiveSMA;
+
+/// Cycle Adaptive Simple Moving Average
+///
+/// A simple moving average of the closing prices whose period follows the
+/// dominant cycle measured by the Hilbert transform.
+///
+/// Period = Cycle Fraction * DC Period
+///
+/// where:
+/// - `DC Period` is the dominant cycle period.
+/// - `Cycle Fraction` scales the period, for example 0.5 for half a cycle.
+/// - The period is rounded to the nearest bar. Values are undefined while the
+///   dominant cycle is undefined or the period exceeds the available bars.
+impl AdaptiveSMA {
+    pub fn calculate_adaptive_average(
+        &self,
+        data: &[Bar],
+        cycle_fraction: f32,
+    ) -> VecDeque<Option<f32>> {
+        let periods: VecDeque<Option<f32>> = DominantCycle
+            .calculate_dominant_cycle(data)
+            .into_iter()
+            .map(|period| period.map(|period| period * cycle_fraction))
+            .collect();
+
+        SMA.adaptive_average(&closes(data), &periods)
+    }
+}
+
+impl Calculation for AdaptiveSMA {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let cycle_fraction = params.first().cloned().unwrap_or(1.0);
+        self.calculate_adaptive_average(data, cycle_fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::adaptive_simple_moving_average::AdaptiveSMA;
+
+    /// Bars whose prices follow a sine wave of the given period around 100,
+    /// with an amplitude of 10.
+    fn sine_bars(count: usize, period: f32) -> Vec<Bar> {
+        (0..count)
+            .map(|i| {
+                let price = 100.0 + 10.0 * (2.0 * std::f32::consts::PI * i as f32 / period).sin();
+                Bar {
+                    timestamp: format!("2023-01-01T{:02}:{:02}:00Z", i / 60, i % 60),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: 1.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_calculate_adaptive_average() {
+        let adaptive_sma = AdaptiveSMA;
+        let data = sine_bars(200, 20.0);
+
+        // Averaging over a full cycle cancels it out
+        let result = adaptive_sma.calculate_adaptive_average(&data, 1.0);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[5], None);
+        assert_float_absolute_eq!(result[150].unwrap(), 100.0, 1e-3);
+        assert_float_absolute_eq!(result[199].unwrap(), 100.0, 1e-3);
+    }
+}
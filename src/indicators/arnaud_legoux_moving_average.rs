@@ -0,0 +1,110 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+
+pub struct ALMA;
+
+/// Arnaud Legoux Moving Average (ALMA)
+///
+/// ALMA weights the closing prices in the window with a Gaussian curve whose
+/// peak can be shifted towards the most recent bar.
+///
+/// ALMA = Sum(W[i] * Price[i]) / Sum(W[i]), for i in 0..n
+///
+/// where:
+/// - W[i] = exp(-(i - m)^2 / (2 * s^2))
+/// - m = Offset * (n - 1)
+/// - s = n / Sigma
+/// - `i` runs from the oldest to the most recent price in the window.
+/// - `Offset` between 0 and 1 moves the peak towards the most recent bar.
+/// - `Sigma` controls the sharpness of the curve.
+impl ALMA {
+    pub fn calculate_alma(
+        &self,
+        data: &[Bar],
+        period: usize,
+        offset: f32,
+        sigma: f32,
+    ) -> VecDeque<Option<f32>> {
+        let m = offset * (period as f32 - 1.0);
+        let s = period as f32 / sigma;
+        let weights: Vec<f32> = (0..period)
+            .map(|i| (-(i as f32 - m).powi(2) / (2.0 * s * s)).exp())
+            .collect();
+        let norm: f32 = weights.iter().sum();
+
+        (0..data.len())
+            .map(|i| {
+                if period == 0 || i + 1 < period {
+                    return None;
+                }
+                let sum: f32 = data[i + 1 - period..=i]
+                    .iter()
+                    .zip(&weights)
+                    .map(|(bar, weight)| bar.close * weight)
+                    .sum();
+                Some(sum / norm)
+            })
+            .collect()
+    }
+}
+
+impl Calculation for ALMA {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(9.0) as usize;
+        let offset = params.get(1).cloned().unwrap_or(0.85);
+        let sigma = params.get(2).cloned().unwrap_or(6.0);
+        self.calculate_alma(data, period, offset, sigma)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::arnaud_legoux_moving_average::ALMA;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_alma() {
+        let alma = ALMA;
+        let data = sample_bars();
+
+        let result = alma.calculate_alma(&data, 4, 0.85, 6.0);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), 104.05293, 1e-3);
+        assert_float_absolute_eq!(result[8].unwrap(), 100.69941, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 106.34355, 1e-3);
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+use crate::indicators::functions::zip_with;
+
+pub struct Aroon;
+
+/// Aroon Up/Down
+///
+/// Measures how recently the highest high and lowest low of the last
+/// `period + 1` bars occurred, on a scale of 0 to 100.
+///
+/// Aroon Up = 100 * (period - bars since highest high) / period
+/// Aroon Down = 100 * (period - bars since lowest low) / period
+/// Oscillator = Aroon Up - Aroon Down
+///
+/// Ties are resolved in favour of the most recent bar. The first `period`
+/// values are undefined. The primary line is Aroon Up, with secondary lines
+/// `down` and `oscillator`.
+impl Aroon {
+    pub fn calculate_aroon(&self, data: &[Bar], period: usize) -> (Line, Line, Line) {
+        let mut up: Line = VecDeque::with_capacity(data.len());
+        let mut down: Line = VecDeque::with_capacity(data.len());
+
+        for i in 0..data.len() {
+            if period == 0 || i < period {
+                up.push_back(None);
+                down.push_back(None);
+                continue;
+            }
+
+            let mut highest = i - period;
+            let mut lowest = i - period;
+            for j in i - period..=i {
+                if data[j].high >= data[highest].high {
+                    highest = j;
+                }
+                if data[j].low <= data[lowest].low {
+                    lowest = j;
+                }
+            }
+
+            up.push_back(Some(
+                100.0 * (period - (i - highest)) as f32 / period as f32,
+            ));
+            down.push_back(Some(100.0 * (period - (i - lowest)) as f32 / period as f32));
+        }
+
+        let oscillator = zip_with(&up, &down, |up, down| up - down);
+        (up, down, oscillator)
+    }
+}
+
+impl Calculation for Aroon {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let period = params.first().cloned().unwrap_or(25.0) as usize;
+        let (up, down, oscillator) = self.calculate_aroon(data, period);
+        (
+            up,
+            Lines::from([
+                ("down".to_string(), down),
+                ("oscillator".to_string(), oscillator),
+            ]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::Bar;
+    use crate::indicators::aroon::Aroon;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_aroon() {
+        let aroon = Aroon;
+        let data = sample_bars();
+
+        let (up, down, oscillator) = aroon.calculate_aroon(&data, 4);
+        assert_eq!(up.len(), data.len());
+        assert_eq!(up[3], None);
+        assert_eq!(down[3], None);
+        assert_eq!(up[4], Some(75.0));
+        assert_eq!(down[4], Some(0.0));
+        assert_eq!(up[8], Some(50.0));
+        assert_eq!(down[8], Some(100.0));
+        assert_eq!(oscillator[8], Some(-50.0));
+        assert_eq!(up[11], Some(100.0));
+        assert_eq!(down[11], Some(50.0));
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::{true_range, wilder_average};
+
+pub struct ATR;
+
+/// Average True Range (ATR)
+///
+/// Wilder's smoothed average of the true range, a measure of volatility that
+/// accounts for gaps between bars.
+///
+/// TrueRange = max(high, previous close) - min(low, previous close)
+/// ATR_t = ATR_{t-1} + (TrueRange_t - ATR_{t-1}) / period
+///
+/// The average is seeded with the simple average of the first `period` true
+/// ranges, so the first `period - 1` values are undefined.
+impl ATR {
+    pub fn calculate_average_true_range(
+        &self,
+        data: &[Bar],
+        period: usize,
+    ) -> VecDeque<Option<f32>> {
+        wilder_average(&true_range(data), period)
+    }
+}
+
+impl Calculation for ATR {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        self.calculate_average_true_range(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::average_true_range::ATR;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_average_true_range() {
+        let atr = ATR;
+        let data = sample_bars();
+
+        let result = atr.calculate_average_true_range(&data, 3);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[1], None);
+        assert_float_absolute_eq!(result[2].unwrap(), 8.66667, 1e-3);
+        assert_float_absolute_eq!(result[5].unwrap(), 7.30864, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 6.42764, 1e-3);
+    }
+}
@@ -0,0 +1,113 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+use crate::indicators::SMA;
+use crate::indicators::functions::{closes, standard_deviation, zip_with};
+
+pub struct BollingerBands;
+
+/// Bollinger Bands
+///
+/// Volatility bands placed a number of standard deviations around a simple
+/// moving average of the close.
+///
+/// Middle = Average(close, period)
+/// Upper = Middle + multiplier * StdDev(close, period)
+/// Lower = Middle - multiplier * StdDev(close, period)
+///
+/// The standard deviation is the population standard deviation. The primary
+/// line is the middle band, with secondary lines `upper` and `lower`.
+impl BollingerBands {
+    pub fn calculate_bollinger_bands(
+        &self,
+        data: &[Bar],
+        period: usize,
+        multiplier: f32,
+    ) -> (Line, Line, Line) {
+        let closes = closes(data);
+        let middle = SMA.average(&closes, period);
+        let deviation = standard_deviation(&closes, period);
+        let upper = zip_with(&middle, &deviation, |middle, deviation| {
+            middle + multiplier * deviation
+        });
+        let lower = zip_with(&middle, &deviation, |middle, deviation| {
+            middle - multiplier * deviation
+        });
+        (middle, upper, lower)
+    }
+}
+
+impl Calculation for BollingerBands {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let period = params.first().cloned().unwrap_or(20.0) as usize;
+        let multiplier = params.get(1).cloned().unwrap_or(2.0);
+        let (middle, upper, lower) = self.calculate_bollinger_bands(data, period, multiplier);
+        (
+            middle,
+            Lines::from([("upper".to_string(), upper), ("lower".to_string(), lower)]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::bollinger_bands::BollingerBands;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_bollinger_bands() {
+        let bollinger = BollingerBands;
+        let data = sample_bars();
+
+        let (middle, upper, lower) = bollinger.calculate_bollinger_bands(&data, 4, 2.0);
+        assert_eq!(middle.len(), data.len());
+        assert_eq!(middle[2], None);
+        assert_eq!(upper[2], None);
+        assert_eq!(lower[2], None);
+        assert_float_absolute_eq!(middle[3].unwrap(), 103.5, 1e-3);
+        assert_float_absolute_eq!(upper[3].unwrap(), 105.73607, 1e-3);
+        assert_float_absolute_eq!(lower[3].unwrap(), 101.26393, 1e-3);
+        assert_float_absolute_eq!(upper[8].unwrap(), 112.17149, 1e-3);
+        assert_float_absolute_eq!(lower[11].unwrap(), 96.26788, 1e-3);
+    }
+}
@@ -0,0 +1,456 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{Bar, Calculation, Lines};
+
+/// Largest body of a doji, as a fraction of the bar range.
+const DOJI_BODY: f32 = 0.1;
+/// Largest body of a small bar, such as a hammer or a star, as a fraction of
+/// the bar range.
+const SMALL_BODY: f32 = 0.3;
+/// Smallest body of a long bar, as a fraction of the bar range.
+const LONG_BODY: f32 = 0.5;
+/// Largest shadow regarded as missing, as a fraction of the bar range.
+const MISSING_SHADOW: f32 = 0.1;
+/// Smallest shadow regarded as long, as a fraction of the bar range.
+const LONG_SHADOW: f32 = 0.3;
+/// Number of bars over which the trend preceding a pattern is measured.
+const TREND_PERIOD: usize = 5;
+
+/// Candlestick patterns, flagged on the last bar of the pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandlestickPattern {
+    Doji,
+    DragonflyDoji,
+    Engulfing,
+    EveningStar,
+    GravestoneDoji,
+    Hammer,
+    Harami,
+    InsideBar,
+    LongLeggedDoji,
+    MorningStar,
+    OutsideBar,
+    ShootingStar,
+    ThreeBlackCrows,
+    ThreeWhiteSoldiers,
+}
+
+impl CandlestickPattern {
+    pub const ALL: [CandlestickPattern; 14] = [
+        CandlestickPattern::Doji,
+        CandlestickPattern::DragonflyDoji,
+        CandlestickPattern::Engulfing,
+        CandlestickPattern::EveningStar,
+        CandlestickPattern::GravestoneDoji,
+        CandlestickPattern::Hammer,
+        CandlestickPattern::Harami,
+        CandlestickPattern::InsideBar,
+        CandlestickPattern::LongLeggedDoji,
+        CandlestickPattern::MorningStar,
+        CandlestickPattern::OutsideBar,
+        CandlestickPattern::ShootingStar,
+        CandlestickPattern::ThreeBlackCrows,
+        CandlestickPattern::ThreeWhiteSoldiers,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CandlestickPattern::Doji => "doji",
+            CandlestickPattern::DragonflyDoji => "dragonfly_doji",
+            CandlestickPattern::Engulfing => "engulfing",
+            CandlestickPattern::EveningStar => "evening_star",
+            CandlestickPattern::GravestoneDoji => "gravestone_doji",
+            CandlestickPattern::Hammer => "hammer",
+            CandlestickPattern::Harami => "harami",
+            CandlestickPattern::InsideBar => "inside_bar",
+            CandlestickPattern::LongLeggedDoji => "long_legged_doji",
+            CandlestickPattern::MorningStar => "morning_star",
+            CandlestickPattern::OutsideBar => "outside_bar",
+            CandlestickPattern::ShootingStar => "shooting_star",
+            CandlestickPattern::ThreeBlackCrows => "three_black_crows",
+            CandlestickPattern::ThreeWhiteSoldiers => "three_white_soldiers",
+        }
+    }
+
+    /// Flags the pattern ending at bar `i` with its direction: `1` for
+    /// bullish, `-1` for bearish and `0` for patterns without a direction,
+    /// such as a doji. Bars without the pattern are `None`.
+    ///
+    /// A hammer after a rising trend is a bearish hanging man, and a shooting
+    /// star after a falling trend is a bullish inverted hammer.
+    pub fn detect(&self, data: &[Bar], i: usize) -> Option<f32> {
+        let bar = &data[i];
+        let prev = i.checked_sub(1).map(|j| &data[j]);
+        match self {
+            CandlestickPattern::Doji => is_doji(bar).then_some(0.0),
+            CandlestickPattern::DragonflyDoji => {
+                (is_doji(bar) && upper_shadow(bar) <= MISSING_SHADOW * range(bar)).then_some(1.0)
+            }
+            CandlestickPattern::GravestoneDoji => {
+                (is_doji(bar) && lower_shadow(bar) <= MISSING_SHADOW * range(bar)).then_some(-1.0)
+            }
+            CandlestickPattern::LongLeggedDoji => (is_doji(bar)
+                && upper_shadow(bar) >= LONG_SHADOW * range(bar)
+                && lower_shadow(bar) >= LONG_SHADOW * range(bar))
+            .then_some(0.0),
+            CandlestickPattern::Hammer => {
+                let shape = !is_doji(bar)
+                    && body(bar) <= SMALL_BODY * range(bar)
+                    && lower_shadow(bar) >= 2.0 * body(bar)
+                    && upper_shadow(bar) <= MISSING_SHADOW * range(bar);
+                shape.then(|| if trend(data, i) > 0.0 { -1.0 } else { 1.0 })
+            }
+            CandlestickPattern::ShootingStar => {
+                let shape = !is_doji(bar)
+                    && body(bar) <= SMALL_BODY * range(bar)
+                    && upper_shadow(bar) >= 2.0 * body(bar)
+                    && lower_shadow(bar) <= MISSING_SHADOW * range(bar);
+                shape.then(|| if trend(data, i) < 0.0 { 1.0 } else { -1.0 })
+            }
+            CandlestickPattern::Engulfing => prev.and_then(|prev| {
+                let engulfs = direction(bar) == -direction(prev)
+                    && direction(bar) != 0.0
+                    && body_high(bar) >= body_high(prev)
+                    && body_low(bar) <= body_low(prev)
+                    && body(bar) > body(prev);
+                engulfs.then(|| direction(bar))
+            }),
+            CandlestickPattern::Harami => prev.and_then(|prev| {
+                let contained = direction(prev) != 0.0
+                    && direction(bar) != direction(prev)
+                    && body_high(bar) <= body_high(prev)
+                    && body_low(bar) >= body_low(prev)
+                    && body(bar) < body(prev);
+                contained.then(|| -direction(prev))
+            }),
+            CandlestickPattern::InsideBar => prev
+                .filter(|prev| bar.high < prev.high && bar.low > prev.low)
+                .map(|_| 0.0),
+            CandlestickPattern::OutsideBar => prev
+                .filter(|prev| bar.high > prev.high && bar.low < prev.low)
+                .map(|_| direction(bar)),
+            CandlestickPattern::MorningStar => is_star(data, i, 1.0).then_some(1.0),
+            CandlestickPattern::EveningStar => is_star(data, i, -1.0).then_some(-1.0),
+            CandlestickPattern::ThreeWhiteSoldiers => is_three(data, i, 1.0).then_some(1.0),
+            CandlestickPattern::ThreeBlackCrows => is_three(data, i, -1.0).then_some(-1.0),
+        }
+    }
+}
+
+impl FromStr for CandlestickPattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CandlestickPattern::ALL
+            .into_iter()
+            .find(|pattern| pattern.name() == s)
+            .ok_or_else(|| format!("unknown candlestick pattern '{}'", s))
+    }
+}
+
+impl fmt::Display for CandlestickPattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+pub struct Candlesticks;
+
+/// Candlestick Patterns
+///
+/// Flags each bar on which a candlestick pattern ends with the direction of
+/// the pattern: `1` for bullish, `-1` for bearish and `0` for patterns
+/// without a direction. Bars without the pattern are `None`.
+///
+/// The primary line combines every pattern: the sign of the sum of the
+/// directions of the patterns found on a bar. Each pattern is also returned
+/// as a line of its own, keyed by its name.
+impl Candlesticks {
+    /// Number of bars needed to measure the trend preceding a pattern of up
+    /// to three bars.
+    pub const LOOKBACK: usize = TREND_PERIOD + 2;
+
+    pub fn calculate_pattern(
+        &self,
+        data: &[Bar],
+        pattern: CandlestickPattern,
+    ) -> VecDeque<Option<f32>> {
+        (0..data.len()).map(|i| pattern.detect(data, i)).collect()
+    }
+
+    pub fn calculate_candlesticks(&self, data: &[Bar]) -> (VecDeque<Option<f32>>, Lines) {
+        let lines: Lines = CandlestickPattern::ALL
+            .iter()
+            .map(|pattern| {
+                (
+                    pattern.name().to_string(),
+                    self.calculate_pattern(data, *pattern),
+                )
+            })
+            .collect();
+        let combined = (0..data.len())
+            .map(|i| {
+                let flags: Vec<f32> = lines.values().filter_map(|line| line[i]).collect();
+                if flags.is_empty() {
+                    return None;
+                }
+                let net: f32 = flags.iter().sum();
+                Some(if net > 0.0 {
+                    1.0
+                } else if net < 0.0 {
+                    -1.0
+                } else {
+                    0.0
+                })
+            })
+            .collect();
+
+        (combined, lines)
+    }
+}
+
+impl Calculation for Candlesticks {
+    fn calculate(&self, data: &Vec<Bar>, _params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_candlesticks(data).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        _params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        self.calculate_candlesticks(data)
+    }
+}
+
+fn body(bar: &Bar) -> f32 {
+    (bar.close - bar.open).abs()
+}
+
+fn body_high(bar: &Bar) -> f32 {
+    bar.open.max(bar.close)
+}
+
+fn body_low(bar: &Bar) -> f32 {
+    bar.open.min(bar.close)
+}
+
+fn range(bar: &Bar) -> f32 {
+    bar.high - bar.low
+}
+
+fn upper_shadow(bar: &Bar) -> f32 {
+    bar.high - body_high(bar)
+}
+
+fn lower_shadow(bar: &Bar) -> f32 {
+    body_low(bar) - bar.low
+}
+
+/// `1` for a bar closing above its open, `-1` below it and `0` otherwise.
+fn direction(bar: &Bar) -> f32 {
+    if bar.close > bar.open {
+        1.0
+    } else if bar.close < bar.open {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+fn is_doji(bar: &Bar) -> bool {
+    range(bar) > 0.0 && body(bar) <= DOJI_BODY * range(bar)
+}
+
+/// Direction of the closes over the bars preceding bar `i`, or `0` when
+/// there are too few bars.
+fn trend(data: &[Bar], i: usize) -> f32 {
+    if i <= TREND_PERIOD {
+        return 0.0;
+    }
+    let change = data[i - 1].close - data[i - 1 - TREND_PERIOD].close;
+    if change > 0.0 {
+        1.0
+    } else if change < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+/// A long bar against `sign`, a small bar beyond its close and a bar with
+/// `sign` closing beyond the midpoint of the first body.
+fn is_star(data: &[Bar], i: usize, sign: f32) -> bool {
+    if i < 2 {
+        return false;
+    }
+    let (first, star, last) = (&data[i - 2], &data[i - 1], &data[i]);
+    let midpoint = (first.open + first.close) / 2.0;
+    let beyond_close = if sign > 0.0 {
+        body_high(star) <= first.close
+    } else {
+        body_low(star) >= first.close
+    };
+
+    direction(first) == -sign
+        && body(first) >= LONG_BODY * range(first)
+        && body(star) <= SMALL_BODY * body(first)
+        && beyond_close
+        && direction(last) == sign
+        && sign * (last.close - midpoint) > 0.0
+}
+
+/// Three long bars with `sign`, each opening within the body of the bar
+/// before it and closing beyond its close.
+fn is_three(data: &[Bar], i: usize, sign: f32) -> bool {
+    if i < 2 {
+        return false;
+    }
+    let bars = &data[i - 2..=i];
+    let long = bars
+        .iter()
+        .all(|bar| direction(bar) == sign && body(bar) >= LONG_BODY * range(bar));
+    let stepped = bars.windows(2).all(|pair| {
+        pair[1].open >= body_low(&pair[0])
+            && pair[1].open <= body_high(&pair[0])
+            && sign * (pair[1].close - pair[0].close) > 0.0
+    });
+    long && stepped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::Bar;
+    use crate::indicators::candlestick_patterns::{CandlestickPattern, Candlesticks};
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    /// Bars with the given open, high, low and close prices.
+    fn ohlc_bars(prices: &[(f32, f32, f32, f32)]) -> Vec<Bar> {
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, (open, high, low, close))| Bar {
+                timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+                open: *open,
+                high: *high,
+                low: *low,
+                close: *close,
+                volume: 1.0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_calculate_candlesticks() {
+        let candlesticks = Candlesticks;
+        let data = sample_bars();
+
+        let (result, lines) = candlesticks.calculate_candlesticks(&data);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(lines.len(), CandlestickPattern::ALL.len());
+        assert_eq!(lines["engulfing"][3], Some(1.0));
+        assert_eq!(lines["engulfing"][2], None);
+        assert_eq!(result[0], None);
+        assert_eq!(result[3], Some(1.0));
+    }
+
+    #[test]
+    fn test_single_bar_patterns() {
+        let candlesticks = Candlesticks;
+        let data = ohlc_bars(&[
+            (100.0, 105.0, 95.0, 100.5),
+            (100.0, 100.5, 90.0, 100.0),
+            (100.0, 110.0, 99.8, 100.2),
+            (100.0, 101.5, 94.0, 101.2),
+        ]);
+
+        let doji = candlesticks.calculate_pattern(&data, CandlestickPattern::Doji);
+        assert_eq!(doji, [Some(0.0), Some(0.0), Some(0.0), None]);
+        let long_legged = candlesticks.calculate_pattern(&data, CandlestickPattern::LongLeggedDoji);
+        assert_eq!(long_legged, [Some(0.0), None, None, None]);
+        let dragonfly = candlesticks.calculate_pattern(&data, CandlestickPattern::DragonflyDoji);
+        assert_eq!(dragonfly, [None, Some(1.0), None, None]);
+        let gravestone = candlesticks.calculate_pattern(&data, CandlestickPattern::GravestoneDoji);
+        assert_eq!(gravestone, [None, None, Some(-1.0), None]);
+        let hammer = candlesticks.calculate_pattern(&data, CandlestickPattern::Hammer);
+        assert_eq!(hammer, [None, None, None, Some(1.0)]);
+
+        let data = ohlc_bars(&[
+            (100.0, 110.0, 90.0, 105.0),
+            (101.0, 108.0, 95.0, 99.0),
+            (98.0, 112.0, 92.0, 108.0),
+        ]);
+
+        let inside_bar = candlesticks.calculate_pattern(&data, CandlestickPattern::InsideBar);
+        assert_eq!(inside_bar, [None, Some(0.0), None]);
+        let outside_bar = candlesticks.calculate_pattern(&data, CandlestickPattern::OutsideBar);
+        assert_eq!(outside_bar, [None, None, Some(1.0)]);
+    }
+
+    #[test]
+    fn test_three_bar_patterns() {
+        let candlesticks = Candlesticks;
+        let data = ohlc_bars(&[
+            (110.0, 111.0, 99.0, 100.0),
+            (99.0, 100.0, 97.0, 98.5),
+            (99.0, 107.0, 98.5, 106.0),
+        ]);
+
+        let morning_star = candlesticks.calculate_pattern(&data, CandlestickPattern::MorningStar);
+        assert_eq!(morning_star, [None, None, Some(1.0)]);
+        let evening_star = candlesticks.calculate_pattern(&data, CandlestickPattern::EveningStar);
+        assert_eq!(evening_star, [None, None, None]);
+
+        let data = ohlc_bars(&[
+            (110.0, 110.5, 104.0, 105.0),
+            (106.0, 106.5, 100.0, 101.0),
+            (102.0, 102.5, 96.0, 97.0),
+        ]);
+
+        let crows = candlesticks.calculate_pattern(&data, CandlestickPattern::ThreeBlackCrows);
+        assert_eq!(crows, [None, None, Some(-1.0)]);
+        let soldiers =
+            candlesticks.calculate_pattern(&data, CandlestickPattern::ThreeWhiteSoldiers);
+        assert_eq!(soldiers, [None, None, None]);
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        assert_eq!("harami".parse(), Ok(CandlestickPattern::Harami));
+        assert!("marubozu".parse::<CandlestickPattern>().is_err());
+    }
+}
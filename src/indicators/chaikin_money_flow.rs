@@ -0,0 +1,94 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::money_flow_volume;
+
+pub struct ChaikinMoneyFlow;
+
+/// Chaikin Money Flow (CMF)
+///
+/// Ratio of money flow volume to volume over `period` bars, between -1 and 1.
+///
+/// CMF = sum(money flow volume, period) / sum(volume, period)
+///
+/// where money flow volume = ((close - low) - (high - close)) / (high - low) * volume
+///
+/// The first `period - 1` values are undefined, as are windows without volume.
+impl ChaikinMoneyFlow {
+    pub fn calculate_chaikin_money_flow(
+        &self,
+        data: &[Bar],
+        period: usize,
+    ) -> VecDeque<Option<f32>> {
+        let flow = money_flow_volume(data);
+
+        (0..data.len())
+            .map(|i| {
+                if period == 0 || i + 1 < period {
+                    return None;
+                }
+                let volume: f32 = data[i + 1 - period..=i].iter().map(|bar| bar.volume).sum();
+                let flow: f32 = (i + 1 - period..=i).filter_map(|j| flow[j]).sum();
+                (volume != 0.0).then(|| flow / volume)
+            })
+            .collect()
+    }
+}
+
+impl Calculation for ChaikinMoneyFlow {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(20.0) as usize;
+        self.calculate_chaikin_money_flow(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::chaikin_money_flow::ChaikinMoneyFlow;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_chaikin_money_flow() {
+        let cmf = ChaikinMoneyFlow;
+        let data = sample_bars();
+
+        let result = cmf.calculate_chaikin_money_flow(&data, 4);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), 0.2875, 1e-4);
+        assert_float_absolute_eq!(result[8].unwrap(), -0.2437, 1e-4);
+        assert_float_absolute_eq!(result[11].unwrap(), 0.29266, 1e-4);
+    }
+}
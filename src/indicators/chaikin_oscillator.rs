@@ -0,0 +1,100 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::XAverage;
+use crate::indicators::functions::{money_flow_volume, zip_with};
+
+pub struct ChaikinOscillator;
+
+/// Chaikin Oscillator
+///
+/// Momentum of the Accumulation/Distribution Line (ADL).
+///
+/// ADL_t = ADL_{t-1} + money flow volume_t
+/// Chaikin Oscillator = XAverage(ADL, fast_period) - XAverage(ADL, slow_period)
+///
+/// The first `slow_period - 1` values are undefined.
+impl ChaikinOscillator {
+    pub fn calculate_chaikin_oscillator(
+        &self,
+        data: &[Bar],
+        fast_period: usize,
+        slow_period: usize,
+    ) -> VecDeque<Option<f32>> {
+        let mut total = 0.0;
+        let adl: VecDeque<Option<f32>> = money_flow_volume(data)
+            .into_iter()
+            .map(|flow| {
+                total += flow.unwrap_or(0.0);
+                Some(total)
+            })
+            .collect();
+
+        let fast = XAverage.xaverage(&adl, fast_period);
+        let slow = XAverage.xaverage(&adl, slow_period);
+        zip_with(&fast, &slow, |fast, slow| fast - slow)
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| value.filter(|_| i + 1 >= slow_period))
+            .collect()
+    }
+}
+
+impl Calculation for ChaikinOscillator {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let fast_period = params.first().cloned().unwrap_or(3.0) as usize;
+        let slow_period = params.get(1).cloned().unwrap_or(10.0) as usize;
+        self.calculate_chaikin_oscillator(data, fast_period, slow_period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::chaikin_oscillator::ChaikinOscillator;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_chaikin_oscillator() {
+        let chaikin = ChaikinOscillator;
+        let data = sample_bars();
+
+        let result = chaikin.calculate_chaikin_oscillator(&data, 2, 4);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), 7.65126, 1e-3);
+        assert_float_absolute_eq!(result[8].unwrap(), -16.67642, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 17.56066, 1e-3);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::SMA;
+use crate::indicators::functions::typical_prices;
+
+pub struct CCI;
+
+/// Commodity Channel Index (CCI)
+///
+/// Measures the deviation of the typical price from its average, scaled so
+/// that most values fall between -100 and 100.
+///
+/// CCI = (TP - Average(TP, period)) / (0.015 * MeanDeviation)
+///
+/// where:
+/// - TP = (high + low + close) / 3
+/// - MeanDeviation = average of |TP - Average(TP, period)| over `period` bars
+///
+/// A mean deviation of zero is reported as 0. The first `period - 1` values
+/// are undefined.
+impl CCI {
+    pub fn calculate_commodity_channel_index(
+        &self,
+        data: &[Bar],
+        period: usize,
+    ) -> VecDeque<Option<f32>> {
+        let typical = typical_prices(data);
+        let average = SMA.average(&typical, period);
+
+        average
+            .iter()
+            .enumerate()
+            .map(|(i, average)| {
+                let average = (*average)?;
+                let tp = typical[i]?;
+                let mean_deviation = (i + 1 - period..=i)
+                    .filter_map(|j| typical[j])
+                    .map(|tp| (tp - average).abs())
+                    .sum::<f32>()
+                    / period as f32;
+                if mean_deviation == 0.0 {
+                    Some(0.0)
+                } else {
+                    Some((tp - average) / (0.015 * mean_deviation))
+                }
+            })
+            .collect()
+    }
+}
+
+impl Calculation for CCI {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(20.0) as usize;
+        self.calculate_commodity_channel_index(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::commodity_channel_index::CCI;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_commodity_channel_index() {
+        let cci = CCI;
+        let data = sample_bars();
+
+        let result = cci.calculate_commodity_channel_index(&data, 4);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), 111.11111, 1e-2);
+        assert_float_absolute_eq!(result[7].unwrap(), -78.78788, 1e-2);
+        assert_float_absolute_eq!(result[8].unwrap(), -108.57143, 1e-2);
+        assert_float_absolute_eq!(result[11].unwrap(), 105.37634, 1e-2);
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+use crate::indicators::functions::{true_range, wilder_average, zip_with};
+
+pub struct ADX;
+
+/// Average Directional Index (ADX) with the Directional Movement Index (DMI)
+///
+/// Measures trend strength regardless of direction.
+///
+/// +DM = high_t - high_{t-1} when it exceeds low_{t-1} - low_t and is positive, else 0
+/// -DM = low_{t-1} - low_t when it exceeds high_t - high_{t-1} and is positive, else 0
+/// +DI = 100 * Wilder(+DM, period) / Wilder(TrueRange, period)
+/// -DI = 100 * Wilder(-DM, period) / Wilder(TrueRange, period)
+/// DX = 100 * |+DI - -DI| / (+DI + -DI)
+/// ADX = Wilder(DX, period)
+///
+/// Directional movement starts at the second bar, so the DI lines are
+/// undefined for the first `period` bars and the ADX for the first
+/// `2 * period - 1` bars.
+///
+/// The primary line is the ADX, with secondary lines `plus_di` and `minus_di`.
+impl ADX {
+    pub fn calculate_adx(&self, data: &[Bar], period: usize) -> (Line, Line, Line) {
+        let mut plus_dm = VecDeque::with_capacity(data.len());
+        let mut minus_dm = VecDeque::with_capacity(data.len());
+        let mut tr = true_range(data);
+
+        for (i, bar) in data.iter().enumerate() {
+            if i == 0 {
+                plus_dm.push_back(None);
+                minus_dm.push_back(None);
+                tr[0] = None;
+                continue;
+            }
+            let up_move = bar.high - data[i - 1].high;
+            let down_move = data[i - 1].low - bar.low;
+            plus_dm.push_back(Some(if up_move > down_move && up_move > 0.0 {
+                up_move
+            } else {
+                0.0
+            }));
+            minus_dm.push_back(Some(if down_move > up_move && down_move > 0.0 {
+                down_move
+            } else {
+                0.0
+            }));
+        }
+
+        let smoothed_tr = wilder_average(&tr, period);
+        let directional_index = |dm: &Line| {
+            zip_with(&wilder_average(dm, period), &smoothed_tr, |dm, tr| {
+                if tr == 0.0 { 0.0 } else { 100.0 * dm / tr }
+            })
+        };
+        let plus_di = directional_index(&plus_dm);
+        let minus_di = directional_index(&minus_dm);
+        let dx = zip_with(&plus_di, &minus_di, |plus, minus| {
+            if plus + minus == 0.0 {
+                0.0
+            } else {
+                100.0 * (plus - minus).abs() / (plus + minus)
+            }
+        });
+
+        (wilder_average(&dx, period), plus_di, minus_di)
+    }
+}
+
+impl Calculation for ADX {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        let (adx, plus_di, minus_di) = self.calculate_adx(data, period);
+        (
+            adx,
+            Lines::from([
+                ("plus_di".to_string(), plus_di),
+                ("minus_di".to_string(), minus_di),
+            ]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::directional_movement::ADX;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_adx() {
+        let adx = ADX;
+        let data = sample_bars();
+
+        let (line, plus_di, minus_di) = adx.calculate_adx(&data, 3);
+        assert_eq!(line.len(), data.len());
+        assert_eq!(plus_di[2], None);
+        assert_eq!(minus_di[2], None);
+        assert_float_absolute_eq!(plus_di[3].unwrap(), 12.5, 1e-3);
+        assert_float_absolute_eq!(minus_di[3].unwrap(), 0.0, 1e-3);
+        assert_float_absolute_eq!(plus_di[8].unwrap(), 8.3682, 1e-3);
+        assert_float_absolute_eq!(minus_di[8].unwrap(), 32.27735, 1e-3);
+        assert_eq!(line[4], None);
+        assert_float_absolute_eq!(line[5].unwrap(), 100.0, 1e-3);
+        assert_float_absolute_eq!(line[7].unwrap(), 73.78277, 1e-3);
+        assert_float_absolute_eq!(line[11].unwrap(), 43.43514, 1e-3);
+    }
+}
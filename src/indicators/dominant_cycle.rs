@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::hilbert_transform::{
+    HilbertTransform, MAX_CYCLE_PERIOD, MIN_CYCLE_PERIOD, median_prices,
+};
+
+pub struct DominantCycle;
+
+/// Hilbert Transform Dominant Cycle Period
+///
+/// The smoothed period, in bars, of the dominant cycle in the median price as
+/// measured by the Hilbert transform homodyne discriminator. The period is
+/// limited to the range of 6 to 50 bars once warmed up.
+///
+/// The output can be used as the period of other indicators, for example
+/// `SMA::adaptive_average`, so that they adapt to the prevailing cycle.
+///
+/// The smoothed period rises from zero while the Hilbert transform warms up,
+/// so the values of the first 50 bars are undefined.
+impl DominantCycle {
+    /// Longest cycle period, in bars, that can be measured.
+    pub const MAX_PERIOD: f32 = MAX_CYCLE_PERIOD;
+
+    /// Number of bars before the smoothed period has settled.
+    pub const WARMUP: usize = 50;
+
+    pub fn calculate_dominant_cycle(&self, data: &[Bar]) -> VecDeque<Option<f32>> {
+        self.dominant_cycle(&median_prices(data))
+    }
+
+    /// Dominant cycle period of an arbitrary series.
+    pub fn dominant_cycle(&self, prices: &[f32]) -> VecDeque<Option<f32>> {
+        HilbertTransform::new(prices)
+            .smooth_period
+            .into_iter()
+            .enumerate()
+            .map(|(i, period)| {
+                Some(period.clamp(MIN_CYCLE_PERIOD, MAX_CYCLE_PERIOD)).filter(|_| i >= Self::WARMUP)
+            })
+            .collect()
+    }
+}
+
+impl Calculation for DominantCycle {
+    fn calculate(&self, data: &Vec<Bar>, _params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_dominant_cycle(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::dominant_cycle::DominantCycle;
+
+    /// Bars whose prices follow a sine wave of the given period around 100,
+    /// with an amplitude of 10.
+    fn sine_bars(count: usize, period: f32) -> Vec<Bar> {
+        (0..count)
+            .map(|i| {
+                let price = 100.0 + 10.0 * (2.0 * std::f32::consts::PI * i as f32 / period).sin();
+                Bar {
+                    timestamp: format!("2023-01-01T{:02}:{:02}:00Z", i / 60, i % 60),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: 1.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_calculate_dominant_cycle() {
+        let dominant_cycle = DominantCycle;
+        let data = sine_bars(200, 20.0);
+
+        let result = dominant_cycle.calculate_dominant_cycle(&data);
+        assert_eq!(result.len(), data.len());
+        assert!(
+            result
+                .iter()
+                .take(DominantCycle::WARMUP)
+                .all(Option::is_none)
+        );
+        assert_float_absolute_eq!(result[150].unwrap(), 20.0, 0.1);
+        assert_float_absolute_eq!(result[199].unwrap(), 20.0, 0.1);
+    }
+
+    #[test]
+    fn test_warmup() {
+        // The smoothed period is far below 6 bars early in the warm-up
+        let data = sine_bars(60, 20.0);
+        let result = DominantCycle.calculate_dominant_cycle(&data);
+        assert!((6..=20).all(|i| result[i].is_none()));
+        assert!(
+            result
+                .iter()
+                .flatten()
+                .all(|period| (6.0..=50.0).contains(period))
+        );
+        assert!(result[DominantCycle::WARMUP].is_some());
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+use crate::indicators::functions::{highest, lowest, zip_with};
+
+pub struct DonchianChannels;
+
+/// Donchian Channels
+///
+/// Price channel formed by the highest high and lowest low of the last
+/// `period` bars.
+///
+/// Upper = Highest(high, period)
+/// Lower = Lowest(low, period)
+/// Middle = (Upper + Lower) / 2
+///
+/// The primary line is the middle line, with secondary lines `upper` and
+/// `lower`. The first `period - 1` values are undefined.
+impl DonchianChannels {
+    pub fn calculate_donchian_channels(&self, data: &[Bar], period: usize) -> (Line, Line, Line) {
+        let upper = highest(data, period);
+        let lower = lowest(data, period);
+        let middle = zip_with(&upper, &lower, |upper, lower| (upper + lower) / 2.0);
+        (middle, upper, lower)
+    }
+}
+
+impl Calculation for DonchianChannels {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let period = params.first().cloned().unwrap_or(20.0) as usize;
+        let (middle, upper, lower) = self.calculate_donchian_channels(data, period);
+        (
+            middle,
+            Lines::from([("upper".to_string(), upper), ("lower".to_string(), lower)]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::Bar;
+    use crate::indicators::donchian_channels::DonchianChannels;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_donchian_channels() {
+        let donchian = DonchianChannels;
+        let data = sample_bars();
+
+        let (middle, upper, lower) = donchian.calculate_donchian_channels(&data, 4);
+        assert_eq!(middle.len(), data.len());
+        assert_eq!(middle[2], None);
+        assert_eq!(upper[3], Some(108.0));
+        assert_eq!(lower[3], Some(95.0));
+        assert_eq!(middle[3], Some(101.5));
+        assert_eq!(upper[8], Some(111.0));
+        assert_eq!(lower[8], Some(97.0));
+        assert_eq!(middle[11], Some(102.5));
+    }
+}
@@ -0,0 +1,84 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::XAverage;
+use crate::indicators::functions::{closes, zip_with};
+
+pub struct DEMA;
+
+/// Double Exponential Moving Average (DEMA)
+///
+/// DEMA reduces the lag of an XAverage by subtracting the XAverage of the
+/// XAverage.
+///
+/// DEMA = 2 * XAverage(Price, n) - XAverage(XAverage(Price, n), n)
+///
+/// where:
+/// - `n` is the number of periods over which the averages are calculated.
+/// - Both averages are seeded with the first closing price.
+impl DEMA {
+    pub fn calculate_double_xaverage(&self, data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+        let xavg1 = XAverage.xaverage(&closes(data), period);
+        let xavg2 = XAverage.xaverage(&xavg1, period);
+
+        zip_with(&xavg1, &xavg2, |xavg1, xavg2| 2.0 * xavg1 - xavg2)
+    }
+}
+
+impl Calculation for DEMA {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        self.calculate_double_xaverage(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::double_xaverage::DEMA;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_double_xaverage() {
+        let dema = DEMA;
+        let data = sample_bars();
+
+        let result = dema.calculate_double_xaverage(&data, 3);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[0], Some(102.0));
+        assert_float_absolute_eq!(result[3].unwrap(), 104.625, 1e-4);
+        assert_float_absolute_eq!(result[7].unwrap(), 103.60156, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 106.9751, 1e-3);
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Lines};
+use crate::indicators::hilbert_transform::median_prices;
+
+pub struct FisherTransform;
+
+/// Ehlers Fisher Transform
+///
+/// Normalizes the median price to the range of the last `period` bars and
+/// applies the Fisher transform, turning the price into a nearly Gaussian
+/// distribution with sharp turning points.
+///
+/// Value = 0.66 * ((Price - Lowest) / (Highest - Lowest) - 0.5) + 0.67 * Value[1]
+///
+/// Fisher = 0.5 * ln((1 + Value) / (1 - Value)) + 0.5 * Fisher[1]
+///
+/// where:
+/// - Price = (High + Low) / 2
+/// - `Highest` and `Lowest` are the highest and lowest prices over `period` bars.
+/// - Value is limited to the range -0.999 to 0.999.
+/// - A flat range normalizes to the midpoint.
+/// - The first `period - 1` values are undefined.
+///
+/// The primary line is Fisher, with the secondary line `trigger` holding the
+/// previous Fisher value.
+impl FisherTransform {
+    pub fn calculate_fisher_transform(
+        &self,
+        data: &[Bar],
+        period: usize,
+    ) -> (VecDeque<Option<f32>>, VecDeque<Option<f32>>) {
+        let prices = median_prices(data);
+        let mut fisher: VecDeque<Option<f32>> = VecDeque::with_capacity(data.len());
+        let mut value = 0.0;
+        let mut prev_fisher: Option<f32> = None;
+
+        for i in 0..prices.len() {
+            if period == 0 || i + 1 < period {
+                fisher.push_back(None);
+                continue;
+            }
+
+            let window = &prices[i + 1 - period..=i];
+            let highest = window.iter().cloned().fold(f32::MIN, f32::max);
+            let lowest = window.iter().cloned().fold(f32::MAX, f32::min);
+            let normalized = if highest > lowest {
+                (prices[i] - lowest) / (highest - lowest) - 0.5
+            } else {
+                0.0
+            };
+
+            value = (0.66 * normalized + 0.67 * value).clamp(-0.999, 0.999);
+            let current =
+                0.5 * ((1.0 + value) / (1.0 - value)).ln() + 0.5 * prev_fisher.unwrap_or(0.0);
+            prev_fisher = Some(current);
+            fisher.push_back(prev_fisher);
+        }
+
+        let trigger = (0..fisher.len())
+            .map(|i| if i > 0 { fisher[i - 1] } else { None })
+            .collect();
+
+        (fisher, trigger)
+    }
+}
+
+impl Calculation for FisherTransform {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let period = params.first().cloned().unwrap_or(10.0) as usize;
+        let (fisher, trigger) = self.calculate_fisher_transform(data, period);
+        (fisher, Lines::from([("trigger".to_string(), trigger)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::fisher_transform::FisherTransform;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_fisher_transform() {
+        let fisher_transform = FisherTransform;
+        let data = sample_bars();
+
+        let (fisher, trigger) = fisher_transform.calculate_fisher_transform(&data, 4);
+        assert_eq!(fisher.len(), data.len());
+        assert_eq!(fisher[2], None);
+        assert_float_absolute_eq!(fisher[3].unwrap(), 0.34283, 1e-3);
+        assert_float_absolute_eq!(fisher[6].unwrap(), 1.39937, 1e-3);
+        assert_float_absolute_eq!(fisher[11].unwrap(), 0.09909, 1e-3);
+        assert_eq!(trigger[3], None);
+        assert_eq!(trigger[7], fisher[6]);
+    }
+}
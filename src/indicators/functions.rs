@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use crate::domain::Bar;
+
+/// Closing prices of the bars as an indicator input series.
+pub fn closes(data: &[Bar]) -> VecDeque<Option<f32>> {
+    data.iter().map(|bar| Some(bar.close)).collect()
+}
+
+/// Typical prices of the bars, (high + low + close) / 3.
+pub fn typical_prices(data: &[Bar]) -> VecDeque<Option<f32>> {
+    data.iter()
+        .map(|bar| Some((bar.high + bar.low + bar.close) / 3.0))
+        .collect()
+}
+
+/// Highest high over the last `period` bars, including the current bar.
+pub fn highest(data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+    window(data, period, |bars| {
+        bars.iter().map(|bar| bar.high).fold(f32::MIN, f32::max)
+    })
+}
+
+/// Lowest low over the last `period` bars, including the current bar.
+pub fn lowest(data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+    window(data, period, |bars| {
+        bars.iter().map(|bar| bar.low).fold(f32::MAX, f32::min)
+    })
+}
+
+/// Applies `f` to every complete window of `period` bars. Values before the
+/// first complete window are `None`.
+pub fn window<F>(data: &[Bar], period: usize, f: F) -> VecDeque<Option<f32>>
+where
+    F: Fn(&[Bar]) -> f32,
+{
+    (0..data.len())
+        .map(|i| {
+            if period == 0 || i + 1 < period {
+                None
+            } else {
+                Some(f(&data[i + 1 - period..=i]))
+            }
+        })
+        .collect()
+}
+
+/// Combines two series value by value, `None` where either input is `None`.
+pub fn zip_with<F>(
+    a: &VecDeque<Option<f32>>,
+    b: &VecDeque<Option<f32>>,
+    f: F,
+) -> VecDeque<Option<f32>>
+where
+    F: Fn(f32, f32) -> f32,
+{
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| match (a, b) {
+            (Some(a), Some(b)) => Some(f(*a, *b)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Wilder's smoothing, an exponential average with a factor of `1 / period`
+/// that is seeded with the simple average of the first `period` values.
+pub fn wilder_average(values: &VecDeque<Option<f32>>, period: usize) -> VecDeque<Option<f32>> {
+    let mut result = VecDeque::with_capacity(values.len());
+    let mut seed = Vec::with_capacity(period);
+    let mut prev: Option<f32> = None;
+
+    if period == 0 {
+        return values.iter().map(|_| None).collect();
+    }
+
+    for value in values {
+        let Some(value) = *value else {
+            result.push_back(None);
+            continue;
+        };
+        prev = match prev {
+            Some(prev) => Some(prev + (value - prev) / period as f32),
+            None => {
+                seed.push(value);
+                if seed.len() == period {
+                    Some(seed.iter().sum::<f32>() / period as f32)
+                } else {
+                    None
+                }
+            }
+        };
+        result.push_back(prev);
+    }
+
+    result
+}
+
+/// True range of each bar, the greatest of high - low, |high - previous close|
+/// and |low - previous close|. The first bar uses its high - low range.
+pub fn true_range(data: &[Bar]) -> VecDeque<Option<f32>> {
+    data.iter()
+        .enumerate()
+        .map(|(i, bar)| match i {
+            0 => Some(bar.high - bar.low),
+            _ => {
+                let prev_close = data[i - 1].close;
+                Some(bar.high.max(prev_close) - bar.low.min(prev_close))
+            }
+        })
+        .collect()
+}
+
+/// Population standard deviation over the last `period` values. A value is
+/// defined only when all `period` values in its window are defined.
+pub fn standard_deviation(values: &VecDeque<Option<f32>>, period: usize) -> VecDeque<Option<f32>> {
+    (0..values.len())
+        .map(|i| {
+            if period == 0 || i + 1 < period {
+                return None;
+            }
+            let window = (i + 1 - period..=i)
+                .map(|j| values[j])
+                .collect::<Option<Vec<f32>>>()?;
+            let mean = window.iter().sum::<f32>() / period as f32;
+            let variance = window.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / period as f32;
+            Some(variance.sqrt())
+        })
+        .collect()
+}
+
+/// Money flow volume of each bar, the volume weighted by where the close
+/// lies within the bar range: ((close - low) - (high - close)) / (high - low).
+/// Bars without a range have no money flow.
+pub fn money_flow_volume(data: &[Bar]) -> VecDeque<Option<f32>> {
+    data.iter()
+        .map(|bar| {
+            let range = bar.high - bar.low;
+            if range == 0.0 {
+                Some(0.0)
+            } else {
+                Some(((bar.close - bar.low) - (bar.high - bar.close)) / range * bar.volume)
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,108 @@
+use crate::domain::Bar;
+
+/// Number of bars consumed by the smoothing and detrending filters before the
+/// Hilbert transform produces its first measurement.
+pub const HILBERT_WARMUP: usize = 6;
+
+/// Range of the cycle periods, in bars, measured by the Hilbert transform.
+pub const MIN_CYCLE_PERIOD: f32 = 6.0;
+pub const MAX_CYCLE_PERIOD: f32 = 50.0;
+
+/// Hilbert transform cycle measurements
+///
+/// Measures the dominant cycle period and phase of a price series with the
+/// homodyne discriminator described by John Ehlers in "Rocket Science for
+/// Traders". Shared by MAMA, the dominant cycle and the instantaneous
+/// trendline.
+///
+/// Smooth = (4 * Price + 3 * Price[1] + 2 * Price[2] + Price[3]) / 10
+/// Detrender = HT(Smooth)
+/// I1 = Detrender[3], Q1 = HT(Detrender)
+/// I2 = I1 - HT(Q1), Q2 = Q1 + HT(I1)
+/// Period = 360 / ArcTan(Im(Z * Z[1]) / Re(Z * Z[1])), where Z = I2 + jQ2
+/// Phase = ArcTan(Q1 / I1)
+///
+/// where:
+/// - HT(x) = (0.0962 * x + 0.5769 * x[2] - 0.5769 * x[4] - 0.0962 * x[6]) * (0.075 * Period[1] + 0.54)
+/// - I2, Q2, Re and Im are smoothed with a factor of 0.2.
+/// - Period is limited to a 50% change per bar and to the range 6 to 50 bars,
+///   then smoothed with a factor of 0.2. The smooth period applies a further
+///   factor of 0.33.
+/// - Angles are measured in degrees. Values are zero during the warm-up.
+pub struct HilbertTransform {
+    pub smooth_period: Vec<f32>,
+    pub phase: Vec<f32>,
+}
+
+impl HilbertTransform {
+    pub fn new(prices: &[f32]) -> Self {
+        let n = prices.len();
+        let mut smooth = vec![0.0; n];
+        let mut detrender = vec![0.0; n];
+        let mut i1 = vec![0.0; n];
+        let mut q1 = vec![0.0; n];
+        let mut smooth_period = vec![0.0; n];
+        let mut phase = vec![0.0; n];
+        let (mut i2, mut q2, mut re, mut im, mut period) = (0.0, 0.0, 0.0, 0.0, 0.0);
+
+        let lag = |values: &[f32], i: usize, k: usize| if i >= k { values[i - k] } else { 0.0 };
+
+        for i in HILBERT_WARMUP..n {
+            smooth[i] =
+                (4.0 * prices[i] + 3.0 * prices[i - 1] + 2.0 * prices[i - 2] + prices[i - 3])
+                    / 10.0;
+
+            let adjustment = 0.075 * period + 0.54;
+            let transform = |values: &[f32]| {
+                (0.0962 * values[i] + 0.5769 * lag(values, i, 2)
+                    - 0.5769 * lag(values, i, 4)
+                    - 0.0962 * lag(values, i, 6))
+                    * adjustment
+            };
+
+            detrender[i] = transform(&smooth);
+            q1[i] = transform(&detrender);
+            i1[i] = lag(&detrender, i, 3);
+
+            // Advance the phase of I1 and Q1 by 90 degrees
+            let j_i = transform(&i1);
+            let j_q = transform(&q1);
+            let prev_i2 = i2;
+            let prev_q2 = q2;
+            i2 = 0.2 * (i1[i] - j_q) + 0.8 * prev_i2;
+            q2 = 0.2 * (q1[i] + j_i) + 0.8 * prev_q2;
+
+            // Homodyne discriminator
+            re = 0.2 * (i2 * prev_i2 + q2 * prev_q2) + 0.8 * re;
+            im = 0.2 * (i2 * prev_q2 - q2 * prev_i2) + 0.8 * im;
+
+            let mut measured = period;
+            if im != 0.0 && re != 0.0 {
+                measured = 360.0 / (im / re).atan().to_degrees();
+            }
+            measured = measured
+                .min(1.5 * period)
+                .max(0.67 * period)
+                .clamp(MIN_CYCLE_PERIOD, MAX_CYCLE_PERIOD);
+            period = 0.2 * measured + 0.8 * period;
+
+            smooth_period[i] = 0.33 * period + 0.67 * smooth_period[i - 1];
+            phase[i] = if i1[i] != 0.0 {
+                (q1[i] / i1[i]).atan().to_degrees()
+            } else {
+                phase[i - 1]
+            };
+        }
+
+        HilbertTransform {
+            smooth_period,
+            phase,
+        }
+    }
+}
+
+/// Median prices, (High + Low) / 2, which Ehlers uses as the input to his
+/// filters.
+pub fn median_prices(data: &[Bar]) -> Vec<f32> {
+    data.iter().map(|bar| (bar.high + bar.low) / 2.0).collect()
+}
@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::WMA;
+use crate::indicators::functions::{closes, zip_with};
+
+pub struct HMA;
+
+/// Hull Moving Average (HMA)
+///
+/// HMA reduces the lag of a weighted average by extrapolating the difference
+/// between a fast and a slow WMA, then smoothing the result over the square
+/// root of the period.
+///
+/// HMA = WMA(2 * WMA(Price, n / 2) - WMA(Price, n), sqrt(n))
+///
+/// where:
+/// - `n` is the number of periods over which the average is calculated.
+/// - `n / 2` and `sqrt(n)` are rounded down, with a minimum of 1.
+impl HMA {
+    pub fn calculate_hull_average(&self, data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+        if period == 0 {
+            return data.iter().map(|_| None).collect();
+        }
+
+        let prices = closes(data);
+        let fast = WMA.weighted_average(&prices, (period / 2).max(1));
+        let slow = WMA.weighted_average(&prices, period);
+        let raw = zip_with(&fast, &slow, |fast, slow| 2.0 * fast - slow);
+
+        WMA.weighted_average(&raw, Self::smoothing_period(period))
+    }
+
+    pub fn smoothing_period(period: usize) -> usize {
+        ((period as f32).sqrt() as usize).max(1)
+    }
+}
+
+impl Calculation for HMA {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        self.calculate_hull_average(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::hull_moving_average::HMA;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_hull_average() {
+        let hma = HMA;
+        let data = sample_bars();
+
+        let result = hma.calculate_hull_average(&data, 4);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[3], None);
+        assert_float_absolute_eq!(result[4].unwrap(), 105.87778, 1e-3);
+        assert_float_absolute_eq!(result[8].unwrap(), 99.01111, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 107.81111, 1e-3);
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::hilbert_transform::median_prices;
+use crate::indicators::{DominantCycle, SMA};
+
+/// Number of bars for which the trendline follows the price.
+const TRENDLINE_WARMUP: usize = 12;
+
+pub struct InstantaneousTrendline;
+
+/// Ehlers Instantaneous Trendline
+///
+/// Removes the dominant cycle from the price by averaging over exactly one
+/// dominant cycle period, then smooths the result with a 4 bar weighted
+/// average.
+///
+/// ITrend = Average(Price, DC Period)
+///
+/// Trendline = (4 * ITrend + 3 * ITrend[1] + 2 * ITrend[2] + ITrend[3]) / 10
+///
+/// where:
+/// - Price = (High + Low) / 2
+/// - `DC Period` is the dominant cycle period rounded to the nearest bar.
+/// - ITrend is the price while the dominant cycle is undefined.
+/// - The trendline is the price for the first 12 bars.
+impl InstantaneousTrendline {
+    pub fn calculate_trendline(&self, data: &[Bar]) -> VecDeque<Option<f32>> {
+        let prices = median_prices(data);
+        let cycle = DominantCycle.dominant_cycle(&prices);
+        let values: VecDeque<Option<f32>> = prices.iter().cloned().map(Some).collect();
+        let itrend: Vec<f32> = SMA
+            .adaptive_average(&values, &cycle)
+            .into_iter()
+            .zip(&prices)
+            .map(|(average, price)| average.unwrap_or(*price))
+            .collect();
+
+        (0..prices.len())
+            .map(|i| {
+                if i < TRENDLINE_WARMUP {
+                    return Some(prices[i]);
+                }
+                Some(
+                    (4.0 * itrend[i] + 3.0 * itrend[i - 1] + 2.0 * itrend[i - 2] + itrend[i - 3])
+                        / 10.0,
+                )
+            })
+            .collect()
+    }
+}
+
+impl Calculation for InstantaneousTrendline {
+    fn calculate(&self, data: &Vec<Bar>, _params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_trendline(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::instantaneous_trendline::InstantaneousTrendline;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    /// Bars whose prices follow a sine wave of the given period around 100,
+    /// with an amplitude of 10.
+    fn sine_bars(count: usize, period: f32) -> Vec<Bar> {
+        (0..count)
+            .map(|i| {
+                let price = 100.0 + 10.0 * (2.0 * std::f32::consts::PI * i as f32 / period).sin();
+                Bar {
+                    timestamp: format!("2023-01-01T{:02}:{:02}:00Z", i / 60, i % 60),
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume: 1.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_calculate_trendline() {
+        let trendline = InstantaneousTrendline;
+
+        let data = sample_bars();
+        let result = trendline.calculate_trendline(&data);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[0], Some(100.0));
+        assert_eq!(result[11], Some(106.0));
+
+        // The trendline of a pure cycle is its mean
+        let data = sine_bars(200, 20.0);
+        let result = trendline.calculate_trendline(&data);
+        assert_float_absolute_eq!(result[150].unwrap(), 100.0, 0.05);
+        assert_float_absolute_eq!(result[199].unwrap(), 100.0, 0.05);
+    }
+}
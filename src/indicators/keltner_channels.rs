@@ -0,0 +1,111 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+use crate::indicators::functions::{closes, zip_with};
+use crate::indicators::{ATR, XAverage};
+
+pub struct KeltnerChannels;
+
+/// Keltner Channels
+///
+/// Volatility channels placed a multiple of the Average True Range around an
+/// exponential average of the close.
+///
+/// Middle = XAverage(close, period)
+/// Upper = Middle + multiplier * ATR(atr_period)
+/// Lower = Middle - multiplier * ATR(atr_period)
+///
+/// The primary line is the middle line, with secondary lines `upper` and
+/// `lower`, which are undefined until the ATR is.
+impl KeltnerChannels {
+    pub fn calculate_keltner_channels(
+        &self,
+        data: &[Bar],
+        period: usize,
+        atr_period: usize,
+        multiplier: f32,
+    ) -> (Line, Line, Line) {
+        let middle = XAverage.xaverage(&closes(data), period);
+        let atr = ATR.calculate_average_true_range(data, atr_period);
+        let upper = zip_with(&middle, &atr, |middle, atr| middle + multiplier * atr);
+        let lower = zip_with(&middle, &atr, |middle, atr| middle - multiplier * atr);
+        (middle, upper, lower)
+    }
+}
+
+impl Calculation for KeltnerChannels {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let period = params.first().cloned().unwrap_or(20.0) as usize;
+        let atr_period = params.get(1).cloned().unwrap_or(10.0) as usize;
+        let multiplier = params.get(2).cloned().unwrap_or(2.0);
+        let (middle, upper, lower) =
+            self.calculate_keltner_channels(data, period, atr_period, multiplier);
+        (
+            middle,
+            Lines::from([("upper".to_string(), upper), ("lower".to_string(), lower)]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::keltner_channels::KeltnerChannels;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_keltner_channels() {
+        let keltner = KeltnerChannels;
+        let data = sample_bars();
+
+        let (middle, upper, lower) = keltner.calculate_keltner_channels(&data, 4, 3, 1.5);
+        assert_eq!(middle.len(), data.len());
+        assert_eq!(middle[0], Some(102.0));
+        assert_eq!(upper[1], None);
+        assert_eq!(lower[1], None);
+        assert_float_absolute_eq!(middle[2].unwrap(), 102.88, 1e-3);
+        assert_float_absolute_eq!(upper[2].unwrap(), 115.88, 1e-3);
+        assert_float_absolute_eq!(lower[2].unwrap(), 89.88, 1e-3);
+        assert_float_absolute_eq!(upper[11].unwrap(), 114.71824, 1e-3);
+        assert_float_absolute_eq!(lower[11].unwrap(), 95.4353, 1e-3);
+    }
+}
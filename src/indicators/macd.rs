@@ -0,0 +1,128 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+use crate::indicators::XAverage;
+use crate::indicators::functions::{closes, zip_with};
+
+pub struct MACD;
+
+/// Moving Average Convergence Divergence (MACD)
+///
+/// MACD = XAverage(close, fast_period) - XAverage(close, slow_period)
+/// Signal = XAverage(MACD, signal_period)
+/// Histogram = MACD - Signal
+///
+/// The MACD line is undefined until `slow_period` bars are available and the
+/// signal line until `signal_period` MACD values are available.
+///
+/// The primary line is MACD, with secondary lines `signal` and `histogram`.
+impl MACD {
+    pub fn calculate_macd(
+        &self,
+        data: &[Bar],
+        fast_period: usize,
+        slow_period: usize,
+        signal_period: usize,
+    ) -> (Line, Line, Line) {
+        let closes = closes(data);
+        let fast = XAverage.xaverage(&closes, fast_period);
+        let slow = XAverage.xaverage(&closes, slow_period);
+        let macd: VecDeque<Option<f32>> = zip_with(&fast, &slow, |fast, slow| fast - slow)
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| value.filter(|_| i + 1 >= slow_period))
+            .collect();
+
+        let warmup = slow_period + signal_period;
+        let signal: VecDeque<Option<f32>> = XAverage
+            .xaverage(&macd, signal_period)
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| value.filter(|_| i + 2 >= warmup))
+            .collect();
+        let histogram = zip_with(&macd, &signal, |macd, signal| macd - signal);
+
+        (macd, signal, histogram)
+    }
+}
+
+impl Calculation for MACD {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let fast_period = params.first().cloned().unwrap_or(12.0) as usize;
+        let slow_period = params.get(1).cloned().unwrap_or(26.0) as usize;
+        let signal_period = params.get(2).cloned().unwrap_or(9.0) as usize;
+        let (macd, signal, histogram) =
+            self.calculate_macd(data, fast_period, slow_period, signal_period);
+        (
+            macd,
+            Lines::from([
+                ("signal".to_string(), signal),
+                ("histogram".to_string(), histogram),
+            ]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::macd::MACD;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_macd() {
+        let macd = MACD;
+        let data = sample_bars();
+
+        let (line, signal, histogram) = macd.calculate_macd(&data, 3, 5, 3);
+        assert_eq!(line.len(), data.len());
+        assert_eq!(line[3], None);
+        assert_float_absolute_eq!(line[4].unwrap(), 0.65432, 1e-3);
+        assert_float_absolute_eq!(line[8].unwrap(), -1.14544, 1e-3);
+        assert_eq!(signal[5], None);
+        assert_float_absolute_eq!(signal[6].unwrap(), 0.80693, 1e-3);
+        assert_float_absolute_eq!(signal[11].unwrap(), 0.23972, 1e-3);
+        assert_eq!(histogram[5], None);
+        assert_float_absolute_eq!(histogram[7].unwrap(), -0.57505, 1e-3);
+        assert_float_absolute_eq!(histogram[11].unwrap(), 0.5702, 1e-3);
+    }
+}
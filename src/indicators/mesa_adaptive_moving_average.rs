@@ -0,0 +1,124 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Lines};
+use crate::indicators::hilbert_transform::{HILBERT_WARMUP, HilbertTransform, median_prices};
+
+pub struct MAMA;
+
+/// MESA Adaptive Moving Average (MAMA) and Following Adaptive Moving Average (FAMA)
+///
+/// MAMA adapts its smoothing factor to the rate of change of the phase
+/// measured by the Hilbert transform, so that it follows price closely when
+/// the cycle phase changes quickly and holds its value otherwise.
+///
+/// MAMA = Alpha * Price + (1 - Alpha) * MAMA[1]
+///
+/// FAMA = 0.5 * Alpha * MAMA + (1 - 0.5 * Alpha) * FAMA[1]
+///
+/// where:
+/// - Price = (High + Low) / 2
+/// - Alpha = Fast Limit / Delta Phase, no less than Slow Limit
+/// - Delta Phase = Phase[1] - Phase, no less than 1 degree
+/// - Initializes MAMA and FAMA to the price during the Hilbert transform warm-up.
+///
+/// The primary line is MAMA, with the secondary line `fama`.
+impl MAMA {
+    pub fn calculate_mama(
+        &self,
+        data: &[Bar],
+        fast_limit: f32,
+        slow_limit: f32,
+    ) -> (VecDeque<Option<f32>>, VecDeque<Option<f32>>) {
+        let prices = median_prices(data);
+        let hilbert = HilbertTransform::new(&prices);
+        let mut mama: VecDeque<Option<f32>> = VecDeque::with_capacity(prices.len());
+        let mut fama: VecDeque<Option<f32>> = VecDeque::with_capacity(prices.len());
+        let (mut prev_mama, mut prev_fama) = (0.0, 0.0);
+
+        for (i, price) in prices.iter().enumerate() {
+            if i < HILBERT_WARMUP {
+                prev_mama = *price;
+                prev_fama = *price;
+            } else {
+                let delta_phase = (hilbert.phase[i - 1] - hilbert.phase[i]).max(1.0);
+                let alpha = (fast_limit / delta_phase).max(slow_limit);
+                prev_mama = alpha * price + (1.0 - alpha) * prev_mama;
+                prev_fama = 0.5 * alpha * prev_mama + (1.0 - 0.5 * alpha) * prev_fama;
+            }
+            mama.push_back(Some(prev_mama));
+            fama.push_back(Some(prev_fama));
+        }
+
+        (mama, fama)
+    }
+}
+
+impl Calculation for MAMA {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let fast_limit = params.first().cloned().unwrap_or(0.5);
+        let slow_limit = params.get(1).cloned().unwrap_or(0.05);
+        let (mama, fama) = self.calculate_mama(data, fast_limit, slow_limit);
+        (mama, Lines::from([("fama".to_string(), fama)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::mesa_adaptive_moving_average::MAMA;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_mama() {
+        let mama = MAMA;
+        let data = sample_bars();
+
+        let (mama, fama) = mama.calculate_mama(&data, 0.5, 0.05);
+        assert_eq!(mama.len(), data.len());
+        assert_eq!(mama[5], Some(107.0));
+        assert_eq!(fama[5], Some(107.0));
+        assert_float_absolute_eq!(mama[7].unwrap(), 106.0, 1e-3);
+        assert_float_absolute_eq!(fama[7].unwrap(), 106.84375, 1e-3);
+        assert_float_absolute_eq!(mama[11].unwrap(), 102.37812, 1e-3);
+        assert_float_absolute_eq!(fama[11].unwrap(), 104.10466, 1e-3);
+    }
+}
@@ -1,9 +1,77 @@
 mod adapative_moving_average;
+mod adaptive_simple_moving_average;
+mod arnaud_legoux_moving_average;
+mod aroon;
+mod average_true_range;
+mod bollinger_bands;
+mod candlestick_patterns;
+mod chaikin_money_flow;
+mod chaikin_oscillator;
+mod commodity_channel_index;
+mod directional_movement;
+mod dominant_cycle;
+mod donchian_channels;
+mod double_xaverage;
+mod fisher_transform;
+mod functions;
+mod hilbert_transform;
+mod hull_moving_average;
+mod instantaneous_trendline;
+mod keltner_channels;
+mod macd;
+mod mesa_adaptive_moving_average;
+mod money_flow_index;
+mod on_balance_volume;
+mod parabolic_sar;
+mod relative_strength_index;
+mod roofing_filter;
 mod simple_moving_average;
+mod stochastic;
+mod super_smoother;
+mod supertrend;
+mod tillson_t3;
 mod tri_average;
+mod triple_xaverage;
+mod variable_index_dynamic_average;
+mod vwap;
+mod weighted_moving_average;
+mod williams_r;
 mod xaverage;
 
 pub use adapative_moving_average::AMA;
+pub use adaptive_simple_moving_average::AdaptiveSMA;
+pub use arnaud_legoux_moving_average::ALMA;
+pub use aroon::Aroon;
+pub use average_true_range::ATR;
+pub use bollinger_bands::BollingerBands;
+pub use candlestick_patterns::{CandlestickPattern, Candlesticks};
+pub use chaikin_money_flow::ChaikinMoneyFlow;
+pub use chaikin_oscillator::ChaikinOscillator;
+pub use commodity_channel_index::CCI;
+pub use directional_movement::ADX;
+pub use dominant_cycle::DominantCycle;
+pub use donchian_channels::DonchianChannels;
+pub use double_xaverage::DEMA;
+pub use fisher_transform::FisherTransform;
+pub use hull_moving_average::HMA;
+pub use instantaneous_trendline::InstantaneousTrendline;
+pub use keltner_channels::KeltnerChannels;
+pub use macd::MACD;
+pub use mesa_adaptive_moving_average::MAMA;
+pub use money_flow_index::MFI;
+pub use on_balance_volume::OBV;
+pub use parabolic_sar::ParabolicSAR;
+pub use relative_strength_index::RSI;
+pub use roofing_filter::RoofingFilter;
 pub use simple_moving_average::SMA;
+pub use stochastic::Stochastic;
+pub use super_smoother::SuperSmoother;
+pub use supertrend::SuperTrend;
+pub use tillson_t3::T3;
 pub use tri_average::TMA;
+pub use triple_xaverage::TEMA;
+pub use variable_index_dynamic_average::VIDYA;
+pub use vwap::{VWAP, VWAPAnchor};
+pub use weighted_moving_average::WMA;
+pub use williams_r::WilliamsR;
 pub use xaverage::XAverage;
@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::typical_prices;
+
+pub struct MFI;
+
+/// Money Flow Index (MFI)
+///
+/// Volume weighted RSI of the typical price on a scale of 0 to 100.
+///
+/// MFI = 100 - 100 / (1 + positive flow / negative flow)
+///
+/// where:
+/// - TP = (high + low + close) / 3
+/// - raw money flow = TP * volume
+/// - positive (negative) flow = sum of the raw money flow over `period` bars
+///   where TP rose (fell) from the previous bar
+///
+/// Without negative flow the MFI is 100, or 50 when there is no flow at all.
+/// The first `period` values are undefined.
+impl MFI {
+    pub fn calculate_money_flow_index(&self, data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+        let typical = typical_prices(data);
+        let flows: Vec<(f32, f32)> = (0..data.len())
+            .map(
+                |i| match (typical[i], i.checked_sub(1).and_then(|j| typical[j])) {
+                    (Some(tp), Some(prev_tp)) if tp > prev_tp => (tp * data[i].volume, 0.0),
+                    (Some(tp), Some(prev_tp)) if tp < prev_tp => (0.0, tp * data[i].volume),
+                    _ => (0.0, 0.0),
+                },
+            )
+            .collect();
+
+        (0..data.len())
+            .map(|i| {
+                if period == 0 || i < period {
+                    return None;
+                }
+                let positive: f32 = flows[i + 1 - period..=i].iter().map(|flow| flow.0).sum();
+                let negative: f32 = flows[i + 1 - period..=i].iter().map(|flow| flow.1).sum();
+                Some(if negative == 0.0 {
+                    if positive == 0.0 { 50.0 } else { 100.0 }
+                } else {
+                    100.0 - 100.0 / (1.0 + positive / negative)
+                })
+            })
+            .collect()
+    }
+}
+
+impl Calculation for MFI {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        self.calculate_money_flow_index(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::money_flow_index::MFI;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_money_flow_index() {
+        let mfi = MFI;
+        let data = sample_bars();
+
+        let result = mfi.calculate_money_flow_index(&data, 3);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), 100.0, 1e-3);
+        assert_float_absolute_eq!(result[7].unwrap(), 43.78671, 1e-3);
+        assert_float_absolute_eq!(result[8].unwrap(), 0.0, 1e-3);
+        assert_float_absolute_eq!(result[10].unwrap(), 42.54446, 1e-3);
+    }
+}
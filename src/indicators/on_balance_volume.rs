@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+
+pub struct OBV;
+
+/// On-Balance Volume (OBV)
+///
+/// Running total of volume, added on up closes and subtracted on down closes.
+///
+/// OBV_t = OBV_{t-1} + volume_t   if close_t > close_{t-1}
+/// OBV_t = OBV_{t-1} - volume_t   if close_t < close_{t-1}
+/// OBV_t = OBV_{t-1}              otherwise
+///
+/// The total starts at 0 on the first bar, so only changes in OBV are
+/// meaningful.
+impl OBV {
+    pub fn calculate_on_balance_volume(&self, data: &[Bar]) -> VecDeque<Option<f32>> {
+        let mut obv = 0.0;
+
+        data.iter()
+            .enumerate()
+            .map(|(i, bar)| {
+                if i > 0 {
+                    let prev_close = data[i - 1].close;
+                    if bar.close > prev_close {
+                        obv += bar.volume;
+                    } else if bar.close < prev_close {
+                        obv -= bar.volume;
+                    }
+                }
+                Some(obv)
+            })
+            .collect()
+    }
+}
+
+impl Calculation for OBV {
+    fn calculate(&self, data: &Vec<Bar>, _params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_on_balance_volume(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::Bar;
+    use crate::indicators::on_balance_volume::OBV;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_on_balance_volume() {
+        let obv = OBV;
+        let data = sample_bars();
+
+        let result = obv.calculate_on_balance_volume(&data);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[0], Some(0.0));
+        assert_eq!(result[1], Some(49.0));
+        assert_eq!(result[2], Some(0.0));
+        assert_eq!(result[5], Some(158.0));
+        assert_eq!(result[8], Some(-37.0));
+        assert_eq!(result[11], Some(133.0));
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+
+pub struct ParabolicSAR;
+
+/// Parabolic Stop and Reverse (SAR)
+///
+/// Trailing stop that accelerates towards price as the trend extends and
+/// flips to the other side of price when it is hit.
+///
+/// SAR_t = SAR_{t-1} + AF * (EP - SAR_{t-1})
+///
+/// where:
+/// - EP = extreme point, the highest high of an uptrend or lowest low of a downtrend
+/// - AF = acceleration factor, starting at `step` and increasing by `step`
+///   each time a new extreme point is made, up to `max_step`
+///
+/// In an uptrend the SAR may not rise above the lows of the previous two bars
+/// (the highs in a downtrend). When the low of a bar falls below the SAR the
+/// trend reverses, the SAR is reset to the extreme point and the extreme
+/// point to the low of the bar, and vice versa for a downtrend.
+///
+/// The initial trend is taken from the first two closes. The first value is
+/// undefined. The primary line is the SAR, with the secondary line
+/// `direction` being 1 in an uptrend and -1 in a downtrend.
+impl ParabolicSAR {
+    pub fn calculate_parabolic_sar(&self, data: &[Bar], step: f32, max_step: f32) -> (Line, Line) {
+        let mut sar_data: Line = VecDeque::with_capacity(data.len());
+        let mut direction: Line = VecDeque::with_capacity(data.len());
+
+        if data.len() < 2 {
+            return (
+                data.iter().map(|_| None).collect(),
+                data.iter().map(|_| None).collect(),
+            );
+        }
+
+        let mut uptrend = data[1].close >= data[0].close;
+        let (mut sar, mut extreme_point) = if uptrend {
+            (data[0].low, data[1].high)
+        } else {
+            (data[0].high, data[1].low)
+        };
+        let mut acceleration = step;
+
+        sar_data.push_back(None);
+        direction.push_back(None);
+        sar_data.push_back(Some(sar));
+        direction.push_back(Some(if uptrend { 1.0 } else { -1.0 }));
+
+        for i in 2..data.len() {
+            let bar = &data[i];
+            sar += acceleration * (extreme_point - sar);
+
+            if uptrend {
+                sar = sar.min(data[i - 1].low).min(data[i - 2].low);
+                if bar.low < sar {
+                    uptrend = false;
+                    sar = extreme_point;
+                    extreme_point = bar.low;
+                    acceleration = step;
+                } else if bar.high > extreme_point {
+                    extreme_point = bar.high;
+                    acceleration = (acceleration + step).min(max_step);
+                }
+            } else {
+                sar = sar.max(data[i - 1].high).max(data[i - 2].high);
+                if bar.high > sar {
+                    uptrend = true;
+                    sar = extreme_point;
+                    extreme_point = bar.high;
+                    acceleration = step;
+                } else if bar.low < extreme_point {
+                    extreme_point = bar.low;
+                    acceleration = (acceleration + step).min(max_step);
+                }
+            }
+
+            sar_data.push_back(Some(sar));
+            direction.push_back(Some(if uptrend { 1.0 } else { -1.0 }));
+        }
+
+        (sar_data, direction)
+    }
+}
+
+impl Calculation for ParabolicSAR {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let step = params.first().cloned().unwrap_or(0.02);
+        let max_step = params.get(1).cloned().unwrap_or(0.2);
+        let (sar, direction) = self.calculate_parabolic_sar(data, step, max_step);
+        (sar, Lines::from([("direction".to_string(), direction)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::parabolic_sar::ParabolicSAR;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_parabolic_sar() {
+        let parabolic_sar = ParabolicSAR;
+        let data = sample_bars();
+
+        let (sar, direction) = parabolic_sar.calculate_parabolic_sar(&data, 0.02, 0.2);
+        assert_eq!(sar.len(), data.len());
+        assert_eq!(sar[0], None);
+        assert_eq!(sar[1], Some(95.0));
+        assert_float_absolute_eq!(sar[3].unwrap(), 95.48, 1e-3);
+        assert_float_absolute_eq!(sar[7].unwrap(), 99.28411, 1e-3);
+        assert_eq!(direction[7], Some(1.0));
+
+        // The low of 97.0 breaks the SAR, which resets to the extreme point
+        assert_eq!(sar[8], Some(111.0));
+        assert_eq!(direction[8], Some(-1.0));
+        assert_float_absolute_eq!(sar[11].unwrap(), 109.56595, 1e-3);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::{wilder_average, zip_with};
+
+pub struct RSI;
+
+/// Relative Strength Index (RSI)
+///
+/// Momentum oscillator measuring the speed of price changes on a scale of
+/// 0 to 100, using Wilder's smoothing of the average gains and losses.
+///
+/// RSI = 100 - 100 / (1 + RS)
+///
+/// where:
+/// - RS = average gain / average loss over `period` bars
+/// - the averages are seeded with the simple average of the first `period`
+///   changes, then smoothed as avg_t = avg_{t-1} + (change_t - avg_{t-1}) / period
+///
+/// The first `period` values are undefined.
+impl RSI {
+    pub fn calculate_relative_strength_index(
+        &self,
+        data: &[Bar],
+        period: usize,
+    ) -> VecDeque<Option<f32>> {
+        let changes: Vec<Option<f32>> = data
+            .iter()
+            .enumerate()
+            .map(|(i, bar)| (i > 0).then(|| bar.close - data[i - 1].close))
+            .collect();
+        let gains = changes.iter().map(|c| c.map(|c| c.max(0.0))).collect();
+        let losses = changes.iter().map(|c| c.map(|c| (-c).max(0.0))).collect();
+
+        zip_with(
+            &wilder_average(&gains, period),
+            &wilder_average(&losses, period),
+            |avg_gain, avg_loss| {
+                if avg_loss == 0.0 {
+                    if avg_gain == 0.0 { 50.0 } else { 100.0 }
+                } else {
+                    100.0 - 100.0 / (1.0 + avg_gain / avg_loss)
+                }
+            },
+        )
+    }
+}
+
+impl Calculation for RSI {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        self.calculate_relative_strength_index(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::relative_strength_index::RSI;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_relative_strength_index() {
+        let rsi = RSI;
+        let data = sample_bars();
+
+        let result = rsi.calculate_relative_strength_index(&data, 3);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[0], None);
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), 80.0, 1e-3);
+        assert_float_absolute_eq!(result[4].unwrap(), 84.61538, 1e-3);
+        assert_float_absolute_eq!(result[7].unwrap(), 27.03448, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 76.34796, 1e-3);
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::SuperSmoother;
+
+pub struct RoofingFilter;
+
+/// Ehlers Roofing Filter
+///
+/// A band-pass filter that removes the trend with a two-pole high-pass
+/// filter and the noise with a Super Smoother, leaving the cycles between the
+/// two cut-off periods. The output oscillates around zero.
+///
+/// HP = (1 - a / 2)^2 * (Price - 2 * Price[1] + Price[2]) + 2 * (1 - a) * HP[1] - (1 - a)^2 * HP[2]
+///
+/// Roofing = SuperSmoother(HP, Smoother Period)
+///
+/// where:
+/// - a = (cos(0.707 * 2 * PI / HP Period) + sin(0.707 * 2 * PI / HP Period) - 1) / cos(0.707 * 2 * PI / HP Period)
+/// - Initializes the first two values of the high-pass filter to zero.
+impl RoofingFilter {
+    pub fn calculate_roofing_filter(
+        &self,
+        data: &[Bar],
+        hp_period: usize,
+        ss_period: usize,
+    ) -> VecDeque<Option<f32>> {
+        if hp_period == 0 || ss_period == 0 {
+            return data.iter().map(|_| None).collect();
+        }
+
+        let angle = 0.707 * 2.0 * PI / hp_period as f32;
+        let alpha = (angle.cos() + angle.sin() - 1.0) / angle.cos();
+
+        let mut high_pass: Vec<f32> = Vec::with_capacity(data.len());
+        for i in 0..data.len() {
+            if i < 2 {
+                high_pass.push(0.0);
+            } else {
+                high_pass.push(
+                    (1.0 - alpha / 2.0).powi(2)
+                        * (data[i].close - 2.0 * data[i - 1].close + data[i - 2].close)
+                        + 2.0 * (1.0 - alpha) * high_pass[i - 1]
+                        - (1.0 - alpha).powi(2) * high_pass[i - 2],
+                );
+            }
+        }
+
+        SuperSmoother
+            .smooth(&high_pass, ss_period)
+            .into_iter()
+            .map(Some)
+            .collect()
+    }
+}
+
+impl Calculation for RoofingFilter {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let hp_period = params.first().cloned().unwrap_or(48.0) as usize;
+        let ss_period = params.get(1).cloned().unwrap_or(10.0) as usize;
+        self.calculate_roofing_filter(data, hp_period, ss_period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::roofing_filter::RoofingFilter;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_roofing_filter() {
+        let roofing_filter = RoofingFilter;
+        let data = sample_bars();
+
+        let result = roofing_filter.calculate_roofing_filter(&data, 8, 4);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[0], Some(0.0));
+        assert_float_absolute_eq!(result[2].unwrap(), -0.74117, 1e-3);
+        assert_float_absolute_eq!(result[8].unwrap(), -3.60752, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 3.18531, 1e-3);
+    }
+}
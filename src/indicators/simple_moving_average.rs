@@ -1,33 +1,82 @@
 use std::collections::VecDeque;
 
 use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::closes;
 
 pub struct SMA;
 
+/// Simple Moving Average (SMA)
+///
+/// The arithmetic mean of the last `period` closing prices. The first
+/// `period - 1` values are undefined.
 impl SMA {
-    pub fn calculate_simple_moving_average(&self, data: &Vec<Bar>, period: usize) -> VecDeque<f32> {
-        let mut sma_data = VecDeque::new();
+    pub fn calculate_simple_moving_average(
+        &self,
+        data: &Vec<Bar>,
+        period: usize,
+    ) -> VecDeque<Option<f32>> {
+        self.average(&closes(data), period)
+    }
+
+    /// Simple moving average of an arbitrary series. A value is defined only
+    /// when all `period` values in its window are defined.
+    pub fn average(&self, values: &VecDeque<Option<f32>>, period: usize) -> VecDeque<Option<f32>> {
+        let mut sma_data = VecDeque::with_capacity(values.len());
         let mut sum = 0.0;
+        let mut defined = 0;
 
-        for (i, bar) in data.iter().enumerate() {
-            sum += bar.close;
+        if period == 0 {
+            return values.iter().map(|_| None).collect();
+        }
 
-            if i >= period - 1 {
-                if i >= period {
-                    sum -= data[i - period].close; // Remove the oldest value from the sum
-                }
-                sma_data.push_back(sum / period as f32);
+        for (i, value) in values.iter().enumerate() {
+            if let Some(value) = value {
+                sum += value;
+                defined += 1;
+            }
+            if i >= period
+                && let Some(oldest) = values[i - period]
+            {
+                sum -= oldest; // Remove the oldest value from the sum
+                defined -= 1;
+            }
+
+            if defined == period {
+                sma_data.push_back(Some(sum / period as f32));
             } else {
-                sma_data.push_back(0.0); // Not enough data yet
+                sma_data.push_back(None); // Not enough data yet
             }
         }
 
         sma_data
     }
+
+    /// Simple moving average whose period varies from bar to bar, such as a
+    /// period taken from the dominant cycle. Periods are rounded to the
+    /// nearest bar. A value is undefined when its period is undefined or less
+    /// than one bar, or when any value in its window is undefined.
+    pub fn adaptive_average(
+        &self,
+        values: &VecDeque<Option<f32>>,
+        periods: &VecDeque<Option<f32>>,
+    ) -> VecDeque<Option<f32>> {
+        (0..values.len())
+            .map(|i| {
+                let period = periods.get(i).cloned().flatten()?.round() as usize;
+                if period == 0 || i + 1 < period {
+                    return None;
+                }
+                values
+                    .range(i + 1 - period..=i)
+                    .try_fold(0.0, |sum, value| value.map(|value| sum + value))
+                    .map(|sum| sum / period as f32)
+            })
+            .collect()
+    }
 }
 
 impl Calculation for SMA {
-    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<f32> {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
         let period = params.get(0).cloned().unwrap_or(14.0) as usize;
         self.calculate_simple_moving_average(data, period)
     }
@@ -35,6 +84,8 @@ impl Calculation for SMA {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::VecDeque;
+
     use crate::domain::Bar;
     use crate::indicators::simple_moving_average::SMA;
 
@@ -86,10 +137,30 @@ mod tests {
 
         let result = sma.calculate_simple_moving_average(&data, 2);
         assert_eq!(result.len(), data.len());
-        assert_eq!(result[0], 0.0);
-        assert_eq!(result[1], (102.0 + 104.0) / 2.0); // 103.0
-        assert_eq!(result[2], (104.0 + 103.0) / 2.0); // 103.5
-        assert_eq!(result[3], (103.0 + 105.0) / 2.0); // 104.0
-        assert_eq!(result[4], (105.0 + 106.0) / 2.0); // 105.5
+        assert_eq!(result[0], None);
+        assert_eq!(result[1], Some((102.0 + 104.0) / 2.0)); // 103.0
+        assert_eq!(result[2], Some((104.0 + 103.0) / 2.0)); // 103.5
+        assert_eq!(result[3], Some((103.0 + 105.0) / 2.0)); // 104.0
+        assert_eq!(result[4], Some((105.0 + 106.0) / 2.0)); // 105.5
+
+        let result = sma.calculate_simple_moving_average(&data, 0);
+        assert_eq!(result.len(), data.len());
+        assert!(result.iter().all(|value| value.is_none()));
+    }
+
+    #[test]
+    fn test_adaptive_average() {
+        let sma = SMA;
+        let values = VecDeque::from([Some(2.0), Some(4.0), Some(6.0), Some(8.0), None, Some(12.0)]);
+        let periods = VecDeque::from([Some(1.0), Some(2.4), None, Some(3.0), Some(1.0), Some(0.4)]);
+
+        let result = sma.adaptive_average(&values, &periods);
+        assert_eq!(result.len(), values.len());
+        assert_eq!(result[0], Some(2.0));
+        assert_eq!(result[1], Some(3.0));
+        assert_eq!(result[2], None);
+        assert_eq!(result[3], Some(6.0));
+        assert_eq!(result[4], None);
+        assert_eq!(result[5], None);
     }
 }
@@ -0,0 +1,120 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+use crate::indicators::SMA;
+use crate::indicators::functions::{highest, lowest};
+
+pub struct Stochastic;
+
+/// Stochastic Oscillator
+///
+/// Locates the close relative to the high-low range of the last `k_period`
+/// bars on a scale of 0 to 100.
+///
+/// Raw %K = 100 * (close - lowest low) / (highest high - lowest low)
+/// %K = Average(Raw %K, k_smoothing)
+/// %D = Average(%K, d_period)
+///
+/// A `k_smoothing` of 1 gives the fast stochastic. A flat range, where the
+/// highest high equals the lowest low, is reported as the midpoint of 50.
+///
+/// The primary line is %K, with %D as the secondary line `d`.
+impl Stochastic {
+    pub fn calculate_stochastic(
+        &self,
+        data: &[Bar],
+        k_period: usize,
+        k_smoothing: usize,
+        d_period: usize,
+    ) -> (Line, Line) {
+        let highest_high = highest(data, k_period);
+        let lowest_low = lowest(data, k_period);
+        let raw_k: VecDeque<Option<f32>> = data
+            .iter()
+            .enumerate()
+            .map(|(i, bar)| match (highest_high[i], lowest_low[i]) {
+                (Some(high), Some(low)) if high > low => {
+                    Some(100.0 * (bar.close - low) / (high - low))
+                }
+                (Some(_), Some(_)) => Some(50.0),
+                _ => None,
+            })
+            .collect();
+
+        let k = SMA.average(&raw_k, k_smoothing);
+        let d = SMA.average(&k, d_period);
+        (k, d)
+    }
+}
+
+impl Calculation for Stochastic {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let k_period = params.first().cloned().unwrap_or(14.0) as usize;
+        let k_smoothing = params.get(1).cloned().unwrap_or(3.0) as usize;
+        let d_period = params.get(2).cloned().unwrap_or(3.0) as usize;
+        let (k, d) = self.calculate_stochastic(data, k_period, k_smoothing, d_period);
+        (k, Lines::from([("d".to_string(), d)]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::stochastic::Stochastic;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_stochastic() {
+        let stochastic = Stochastic;
+        let data = sample_bars();
+
+        let (k, d) = stochastic.calculate_stochastic(&data, 3, 2, 2);
+        assert_eq!(k.len(), data.len());
+        assert_eq!(d.len(), data.len());
+        assert_eq!(k[2], None);
+        assert_float_absolute_eq!(k[3].unwrap(), 68.33333, 1e-3);
+        assert_eq!(d[3], None);
+        assert_float_absolute_eq!(d[4].unwrap(), 71.11111, 1e-3);
+        assert_float_absolute_eq!(k[8].unwrap(), 12.14286, 1e-3);
+        assert_float_absolute_eq!(d[11].unwrap(), 78.49359, 1e-3);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::VecDeque;
+use std::f32::consts::PI;
+
+use crate::domain::{Bar, Calculation};
+
+pub struct SuperSmoother;
+
+/// Ehlers Super Smoother
+///
+/// A two-pole Butterworth low-pass filter that removes aliasing noise with
+/// less lag than a moving average of the same period.
+///
+/// Filt = c1 * (Price + Price[1]) / 2 + c2 * Filt[1] + c3 * Filt[2]
+///
+/// where:
+/// - a1 = exp(-1.414 * PI / Period)
+/// - c2 = 2 * a1 * cos(1.414 * PI / Period)
+/// - c3 = -a1 * a1
+/// - c1 = 1 - c2 - c3
+/// - Initializes the first two values of the filter to the price.
+impl SuperSmoother {
+    pub fn calculate_super_smoother(&self, data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+        if period == 0 {
+            return data.iter().map(|_| None).collect();
+        }
+
+        let closes: Vec<f32> = data.iter().map(|bar| bar.close).collect();
+        self.smooth(&closes, period).into_iter().map(Some).collect()
+    }
+
+    /// Super Smoother of an arbitrary series.
+    pub fn smooth(&self, values: &[f32], period: usize) -> Vec<f32> {
+        let a1 = (-1.414 * PI / period as f32).exp();
+        let c2 = 2.0 * a1 * (1.414 * PI / period as f32).cos();
+        let c3 = -a1 * a1;
+        let c1 = 1.0 - c2 - c3;
+
+        let mut filt: Vec<f32> = Vec::with_capacity(values.len());
+        for (i, value) in values.iter().enumerate() {
+            if i < 2 {
+                filt.push(*value);
+            } else {
+                filt.push(c1 * (value + values[i - 1]) / 2.0 + c2 * filt[i - 1] + c3 * filt[i - 2]);
+            }
+        }
+
+        filt
+    }
+}
+
+impl Calculation for SuperSmoother {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(10.0) as usize;
+        self.calculate_super_smoother(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::super_smoother::SuperSmoother;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_super_smoother() {
+        let super_smoother = SuperSmoother;
+        let data = sample_bars();
+
+        let result = super_smoother.calculate_super_smoother(&data, 4);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[1], Some(104.0));
+        assert_float_absolute_eq!(result[2].unwrap(), 103.80903, 1e-3);
+        assert_float_absolute_eq!(result[7].unwrap(), 105.25018, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 105.99145, 1e-3);
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines};
+use crate::indicators::ATR;
+
+pub struct SuperTrend;
+
+/// SuperTrend
+///
+/// Trend following stop placed a multiple of the Average True Range away
+/// from the bar midpoint.
+///
+/// BasicUpper = (high + low) / 2 + multiplier * ATR(atr_period)
+/// BasicLower = (high + low) / 2 - multiplier * ATR(atr_period)
+///
+/// The final bands only ratchet towards price: the upper band is lowered to
+/// the basic upper band, and only raised when the previous close broke above
+/// it. Likewise for the lower band. The trend turns down when the close
+/// falls below the lower band and up when it rises above the upper band.
+/// The SuperTrend is the lower band in an uptrend and the upper band in a
+/// downtrend.
+///
+/// The initial trend is up. Values are undefined until the ATR is. The
+/// primary line is the SuperTrend, with the secondary line `direction` being
+/// 1 in an uptrend and -1 in a downtrend.
+impl SuperTrend {
+    pub fn calculate_supertrend(
+        &self,
+        data: &[Bar],
+        atr_period: usize,
+        multiplier: f32,
+    ) -> (Line, Line) {
+        let atr = ATR.calculate_average_true_range(data, atr_period);
+        let mut supertrend: Line = VecDeque::with_capacity(data.len());
+        let mut direction: Line = VecDeque::with_capacity(data.len());
+        let mut bands: Option<(f32, f32)> = None;
+        let mut uptrend = true;
+
+        for (i, bar) in data.iter().enumerate() {
+            let Some(atr) = atr[i] else {
+                supertrend.push_back(None);
+                direction.push_back(None);
+                continue;
+            };
+
+            let midpoint = (bar.high + bar.low) / 2.0;
+            let basic_upper = midpoint + multiplier * atr;
+            let basic_lower = midpoint - multiplier * atr;
+
+            let (upper, lower) = match bands {
+                None => (basic_upper, basic_lower),
+                Some((prev_upper, prev_lower)) => {
+                    let prev_close = data[i - 1].close;
+                    let upper = if basic_upper < prev_upper || prev_close > prev_upper {
+                        basic_upper
+                    } else {
+                        prev_upper
+                    };
+                    let lower = if basic_lower > prev_lower || prev_close < prev_lower {
+                        basic_lower
+                    } else {
+                        prev_lower
+                    };
+                    if uptrend && bar.close < lower {
+                        uptrend = false;
+                    } else if !uptrend && bar.close > upper {
+                        uptrend = true;
+                    }
+                    (upper, lower)
+                }
+            };
+            bands = Some((upper, lower));
+
+            supertrend.push_back(Some(if uptrend { lower } else { upper }));
+            direction.push_back(Some(if uptrend { 1.0 } else { -1.0 }));
+        }
+
+        (supertrend, direction)
+    }
+}
+
+impl Calculation for SuperTrend {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let atr_period = params.first().cloned().unwrap_or(10.0) as usize;
+        let multiplier = params.get(1).cloned().unwrap_or(3.0);
+        let (supertrend, direction) = self.calculate_supertrend(data, atr_period, multiplier);
+        (
+            supertrend,
+            Lines::from([("direction".to_string(), direction)]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::supertrend::SuperTrend;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_supertrend() {
+        let supertrend = SuperTrend;
+        let data = sample_bars();
+
+        let (line, direction) = supertrend.calculate_supertrend(&data, 3, 0.5);
+        assert_eq!(line.len(), data.len());
+        assert_eq!(line[1], None);
+        assert_float_absolute_eq!(line[2].unwrap(), 98.66667, 1e-3);
+        assert_float_absolute_eq!(line[6].unwrap(), 104.56379, 1e-3);
+        assert_eq!(direction[6], Some(1.0));
+
+        // The close of 102.0 breaks the lower band and flips to the upper band
+        assert_float_absolute_eq!(line[7].unwrap(), 107.95748, 1e-3);
+        assert_eq!(direction[7], Some(-1.0));
+        assert_float_absolute_eq!(line[9].unwrap(), 102.9811, 1e-3);
+
+        // The close of 105.0 breaks the upper band and flips back
+        assert_float_absolute_eq!(line[10].unwrap(), 99.67927, 1e-3);
+        assert_eq!(direction[10], Some(1.0));
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::XAverage;
+use crate::indicators::functions::{closes, zip_with};
+
+pub struct T3;
+
+/// Tillson T3 Moving Average
+///
+/// T3 applies a generalized DEMA three times in succession, giving a smooth
+/// average with little lag.
+///
+/// GD(Price) = XAverage(Price, n) * (1 + v) - XAverage(XAverage(Price, n), n) * v
+///
+/// T3 = GD(GD(GD(Price)))
+///
+/// where:
+/// - `n` is the number of periods over which the averages are calculated.
+/// - `v` is the volume factor between 0 and 1. A value of 0 reduces GD to an
+///   XAverage and a value of 1 to a DEMA.
+impl T3 {
+    pub fn calculate_t3(
+        &self,
+        data: &[Bar],
+        period: usize,
+        volume_factor: f32,
+    ) -> VecDeque<Option<f32>> {
+        let gd1 = self.generalized_dema(&closes(data), period, volume_factor);
+        let gd2 = self.generalized_dema(&gd1, period, volume_factor);
+        self.generalized_dema(&gd2, period, volume_factor)
+    }
+
+    fn generalized_dema(
+        &self,
+        values: &VecDeque<Option<f32>>,
+        period: usize,
+        volume_factor: f32,
+    ) -> VecDeque<Option<f32>> {
+        let xavg1 = XAverage.xaverage(values, period);
+        let xavg2 = XAverage.xaverage(&xavg1, period);
+
+        zip_with(&xavg1, &xavg2, |xavg1, xavg2| {
+            xavg1 * (1.0 + volume_factor) - xavg2 * volume_factor
+        })
+    }
+}
+
+impl Calculation for T3 {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(5.0) as usize;
+        let volume_factor = params.get(1).cloned().unwrap_or(0.7);
+        self.calculate_t3(data, period, volume_factor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::tillson_t3::T3;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_t3() {
+        let t3 = T3;
+        let data = sample_bars();
+
+        let result = t3.calculate_t3(&data, 3, 0.7);
+        assert_eq!(result.len(), data.len());
+        assert_float_absolute_eq!(result[0].unwrap(), 102.0, 1e-4);
+        assert_float_absolute_eq!(result[4].unwrap(), 104.74, 1e-3);
+        assert_float_absolute_eq!(result[8].unwrap(), 103.28441, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 104.08691, 1e-3);
+    }
+}
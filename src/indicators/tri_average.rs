@@ -11,17 +11,21 @@ pub struct TMA;
 /// var0 = Ceil(( Len + 1 ) * .5);
 /// TMA = Average( Average( PriceValue, var0 ), var0 );
 impl TMA {
-    pub fn calculate_tri_average(&self, data: &Vec<Bar>, period: usize) -> VecDeque<f32> {
+    pub fn calculate_tri_average(&self, data: &Vec<Bar>, period: usize) -> VecDeque<Option<f32>> {
         // let len = (period + 1).div_ceil(2);
-        let mut tma_data: VecDeque<f32> = VecDeque::new();
+        let mut tma_data: VecDeque<Option<f32>> = VecDeque::new();
         let mut avg1_data: VecDeque<f32> = VecDeque::new();
         let mut sum1 = 0.0;
         let mut sum2 = 0.0;
 
+        if period == 0 {
+            return data.iter().map(|_| None).collect();
+        }
+
         for (i, bar) in data.iter().enumerate() {
             sum1 += bar.close;
 
-            if i >= period - 1 {
+            if i + 1 >= period {
                 if i >= period {
                     sum1 -= data[i - period].close; // Remove the oldest value from the sum
                 }
@@ -29,18 +33,18 @@ impl TMA {
                 avg1_data.push_back(avg1);
                 sum2 += avg1;
 
-                if i >= 2 * period - 2 {
-                    if i >= 2 * period - 1 {
+                if i + 2 >= 2 * period {
+                    if i + 1 >= 2 * period {
                         if let Some(val) = avg1_data.pop_front() {
                             sum2 -= val; // Remove the oldest value from the sum
                         }
                     }
-                    tma_data.push_back(sum2 / period as f32);
+                    tma_data.push_back(Some(sum2 / period as f32));
                 } else {
-                    tma_data.push_back(0.0); // Not enough data yet
+                    tma_data.push_back(None); // Not enough data yet
                 }
             } else {
-                tma_data.push_back(0.0); // Not enough data yet
+                tma_data.push_back(None); // Not enough data yet
             }
         }
 
@@ -49,7 +53,7 @@ impl TMA {
 }
 
 impl Calculation for TMA {
-    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<f32> {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
         let period = params.get(0).cloned().unwrap_or(14.0) as usize;
         self.calculate_tri_average(data, period)
     }
@@ -108,18 +112,22 @@ mod tests {
 
         let result = tma.calculate_tri_average(&data, 2);
         assert_eq!(result.len(), data.len());
-        assert_eq!(result[0], 0.0);
-        assert_eq!(result[1], 0.0);
-        assert_eq!(result[2], 103.25);
-        assert_eq!(result[3], 103.75);
-        assert_eq!(result[4], 104.75);
+        assert_eq!(result[0], None);
+        assert_eq!(result[1], None);
+        assert_eq!(result[2], Some(103.25));
+        assert_eq!(result[3], Some(103.75));
+        assert_eq!(result[4], Some(104.75));
 
         let result = tma.calculate_tri_average(&data, 3);
         assert_eq!(result.len(), data.len());
-        assert_eq!(result[0], 0.0);
-        assert_eq!(result[1], 0.0);
-        assert_eq!(result[2], 0.0);
-        assert_eq!(result[3], 0.0);
-        assert_eq!(result[4], 103.888889);
+        assert_eq!(result[0], None);
+        assert_eq!(result[1], None);
+        assert_eq!(result[2], None);
+        assert_eq!(result[3], None);
+        assert_eq!(result[4], Some(103.888889));
+
+        let result = tma.calculate_tri_average(&data, 0);
+        assert_eq!(result.len(), data.len());
+        assert!(result.iter().all(|value| value.is_none()));
     }
 }
@@ -0,0 +1,87 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::XAverage;
+use crate::indicators::functions::{closes, zip_with};
+
+pub struct TEMA;
+
+/// Triple Exponential Moving Average (TEMA)
+///
+/// TEMA extends DEMA with a third XAverage to further reduce lag.
+///
+/// TEMA = 3 * XAvg1 - 3 * XAvg2 + XAvg3
+///
+/// where:
+/// - XAvg1 = XAverage(Price, n)
+/// - XAvg2 = XAverage(XAvg1, n)
+/// - XAvg3 = XAverage(XAvg2, n)
+/// - `n` is the number of periods over which the averages are calculated.
+impl TEMA {
+    pub fn calculate_triple_xaverage(&self, data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+        let xavg1 = XAverage.xaverage(&closes(data), period);
+        let xavg2 = XAverage.xaverage(&xavg1, period);
+        let xavg3 = XAverage.xaverage(&xavg2, period);
+
+        let lag = zip_with(&xavg1, &xavg2, |xavg1, xavg2| 3.0 * (xavg1 - xavg2));
+        zip_with(&lag, &xavg3, |lag, xavg3| lag + xavg3)
+    }
+}
+
+impl Calculation for TEMA {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        self.calculate_triple_xaverage(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::triple_xaverage::TEMA;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_triple_xaverage() {
+        let tema = TEMA;
+        let data = sample_bars();
+
+        let result = tema.calculate_triple_xaverage(&data, 3);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[0], Some(102.0));
+        assert_float_absolute_eq!(result[3].unwrap(), 104.8125, 1e-4);
+        assert_float_absolute_eq!(result[7].unwrap(), 102.72266, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 107.82935, 1e-3);
+    }
+}
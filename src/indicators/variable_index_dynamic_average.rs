@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+
+pub struct VIDYA;
+
+/// Variable Index Dynamic Average (VIDYA)
+///
+/// VIDYA is an XAverage whose smoothing factor is scaled by the absolute
+/// Chande Momentum Oscillator, so that the average speeds up in trending
+/// markets and slows down in ranging ones.
+///
+/// VIDYA = Price * Factor * |CMO| + Previous VIDYA * (1 - Factor * |CMO|)
+///
+/// where:
+/// - Factor = 2 / (Period + 1)
+/// - CMO = (Sum of Up Moves - Sum of Down Moves) / (Sum of Up Moves + Sum of Down Moves)
+///   over the last `CMO Period` closing price changes.
+/// - Initializes VIDYA to the close preceding the first defined CMO. The
+///   first `CMO Period` values are undefined.
+impl VIDYA {
+    pub fn calculate_vidya(
+        &self,
+        data: &[Bar],
+        period: usize,
+        cmo_period: usize,
+    ) -> VecDeque<Option<f32>> {
+        let factor = 2.0 / (period as f32 + 1.0);
+        let mut vidya: VecDeque<Option<f32>> = VecDeque::with_capacity(data.len());
+        let mut prev_vidya: Option<f32> = None;
+
+        for (i, bar) in data.iter().enumerate() {
+            if period == 0 || cmo_period == 0 || i < cmo_period {
+                vidya.push_back(None);
+                continue;
+            }
+
+            let (up, down) = data[i - cmo_period..=i]
+                .windows(2)
+                .map(|pair| pair[1].close - pair[0].close)
+                .fold((0.0, 0.0), |(up, down), change| {
+                    if change > 0.0 {
+                        (up + change, down)
+                    } else {
+                        (up, down - change)
+                    }
+                });
+            let cmo = if up + down > 0.0 {
+                (up - down) / (up + down)
+            } else {
+                0.0
+            };
+
+            let alpha = factor * cmo.abs();
+            let prev = prev_vidya.unwrap_or(data[i - 1].close);
+            prev_vidya = Some(bar.close * alpha + prev * (1.0 - alpha));
+            vidya.push_back(prev_vidya);
+        }
+
+        vidya
+    }
+}
+
+impl Calculation for VIDYA {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        let cmo_period = params.get(1).cloned().unwrap_or(9.0) as usize;
+        self.calculate_vidya(data, period, cmo_period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::variable_index_dynamic_average::VIDYA;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_vidya() {
+        let vidya = VIDYA;
+        let data = sample_bars();
+
+        let result = vidya.calculate_vidya(&data, 3, 3);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), 103.6, 1e-4);
+        assert_float_absolute_eq!(result[6].unwrap(), 106.66667, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), 105.23194, 1e-3);
+    }
+}
@@ -0,0 +1,270 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation, Line, Lines, Session, parse_timestamp};
+
+pub struct VWAP;
+
+/// Point from which the VWAP accumulates.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum VWAPAnchor {
+    /// Restarts at the start of every trading session.
+    Session { session: String, timezone: String },
+    /// Restarts every Monday at midnight in the session timezone.
+    Week { timezone: String },
+    /// Starts at the given timestamp, or the first bar when there is none.
+    Timestamp(Option<String>),
+}
+
+impl VWAPAnchor {
+    /// Parameter code of the anchor, as used in the `indicators` query.
+    pub fn code(&self) -> f32 {
+        match self {
+            VWAPAnchor::Session { .. } => 0.0,
+            VWAPAnchor::Week { .. } => 1.0,
+            VWAPAnchor::Timestamp(_) => 2.0,
+        }
+    }
+
+    /// Anchor for the parameter code, using a UTC session that starts at
+    /// midnight until bound to the trading session of a symbol.
+    pub fn from_code(code: f32) -> Self {
+        match code as usize {
+            1 => VWAPAnchor::Week {
+                timezone: "Etc/UTC".to_string(),
+            },
+            2 => VWAPAnchor::Timestamp(None),
+            _ => VWAPAnchor::Session {
+                session: "24x7".to_string(),
+                timezone: "Etc/UTC".to_string(),
+            },
+        }
+    }
+
+    /// Start of the period that `timestamp` accumulates in, both in UTC, if
+    /// the period starts before it.
+    pub fn start(&self, timestamp: NaiveDateTime) -> Option<NaiveDateTime> {
+        match self {
+            VWAPAnchor::Session { session, timezone } => Session::parse(session, timezone)
+                .ok()
+                .map(|session| session.session_start(timestamp)),
+            VWAPAnchor::Week { timezone } => Session::parse("24x7", timezone)
+                .ok()
+                .map(|session| session.week_start(timestamp)),
+            VWAPAnchor::Timestamp(None) => None,
+            VWAPAnchor::Timestamp(Some(anchor)) => {
+                parse_timestamp(anchor).filter(|anchor| *anchor < timestamp)
+            }
+        }
+    }
+
+    /// Assigns every bar to the period it accumulates in, identified by the
+    /// start of that period. Bars before a timestamp anchor, or with a
+    /// timestamp that cannot be parsed, belong to no period.
+    pub fn periods(&self, data: &[Bar]) -> Result<Vec<Option<i64>>, String> {
+        let timestamps = data.iter().map(|bar| bar.datetime());
+        Ok(match self {
+            VWAPAnchor::Session { session, timezone } => {
+                let session = Session::parse(session, timezone)?;
+                timestamps
+                    .map(|t| t.map(|t| session.session_start(t).and_utc().timestamp()))
+                    .collect()
+            }
+            VWAPAnchor::Week { timezone } => {
+                let session = Session::parse("24x7", timezone)?;
+                timestamps
+                    .map(|t| t.map(|t| session.week_start(t).and_utc().timestamp()))
+                    .collect()
+            }
+            VWAPAnchor::Timestamp(None) => timestamps.map(|_| Some(0)).collect(),
+            VWAPAnchor::Timestamp(Some(anchor)) => {
+                let anchor = parse_timestamp(anchor)
+                    .ok_or_else(|| format!("{} is not a valid anchor timestamp", anchor))?;
+                timestamps
+                    .map(|t| t.filter(|t| *t >= anchor).map(|_| 0))
+                    .collect()
+            }
+        })
+    }
+}
+
+/// Volume Weighted Average Price (VWAP)
+///
+/// Average typical price weighted by volume, accumulated from an anchor.
+///
+/// VWAP = sum(TP * volume) / sum(volume)
+/// StdDev = sqrt(sum(TP^2 * volume) / sum(volume) - VWAP^2)
+/// Upper = VWAP + multiplier * StdDev
+/// Lower = VWAP - multiplier * StdDev
+///
+/// where TP = (high + low + close) / 3 and the sums restart at every anchor.
+///
+/// Values are undefined for bars outside an anchored period and until the
+/// period has volume. The primary line is the VWAP, with secondary lines
+/// `upper` and `lower`.
+impl VWAP {
+    pub fn calculate_vwap(
+        &self,
+        data: &[Bar],
+        periods: &[Option<i64>],
+        multiplier: f32,
+    ) -> (Line, Line, Line) {
+        let mut vwap: Line = VecDeque::with_capacity(data.len());
+        let mut upper: Line = VecDeque::with_capacity(data.len());
+        let mut lower: Line = VecDeque::with_capacity(data.len());
+        let mut current_period = None;
+        let (mut price_volume, mut price_squared_volume, mut volume) = (0.0, 0.0, 0.0);
+
+        for (bar, period) in data.iter().zip(periods) {
+            if period.is_none() {
+                vwap.push_back(None);
+                upper.push_back(None);
+                lower.push_back(None);
+                continue;
+            }
+            if *period != current_period {
+                current_period = *period;
+                (price_volume, price_squared_volume, volume) = (0.0, 0.0, 0.0);
+            }
+
+            let tp = (bar.high + bar.low + bar.close) / 3.0;
+            price_volume += tp * bar.volume;
+            price_squared_volume += tp * tp * bar.volume;
+            volume += bar.volume;
+
+            if volume == 0.0 {
+                vwap.push_back(None);
+                upper.push_back(None);
+                lower.push_back(None);
+                continue;
+            }
+            let average = price_volume / volume;
+            let deviation = (price_squared_volume / volume - average * average)
+                .max(0.0)
+                .sqrt();
+            vwap.push_back(Some(average));
+            upper.push_back(Some(average + multiplier * deviation));
+            lower.push_back(Some(average - multiplier * deviation));
+        }
+
+        (vwap, upper, lower)
+    }
+
+    /// Calculates the VWAP from the given anchor.
+    pub fn calculate_anchored(
+        &self,
+        data: &[Bar],
+        anchor: &VWAPAnchor,
+        multiplier: f32,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let periods = anchor
+            .periods(data)
+            .unwrap_or_else(|_| vec![None; data.len()]);
+        let (vwap, upper, lower) = self.calculate_vwap(data, &periods, multiplier);
+        (
+            vwap,
+            Lines::from([("upper".to_string(), upper), ("lower".to_string(), lower)]),
+        )
+    }
+}
+
+impl Calculation for VWAP {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        self.calculate_lines(data, params).0
+    }
+
+    fn calculate_lines(
+        &self,
+        data: &Vec<Bar>,
+        params: &Vec<f32>,
+    ) -> (VecDeque<Option<f32>>, Lines) {
+        let anchor = VWAPAnchor::from_code(params.first().cloned().unwrap_or(0.0));
+        let multiplier = params.get(1).cloned().unwrap_or(1.0);
+        self.calculate_anchored(data, &anchor, multiplier)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::vwap::{VWAP, VWAPAnchor};
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_vwap() {
+        let vwap = VWAP;
+        let data = sample_bars();
+
+        let periods = VWAPAnchor::from_code(0.0).periods(&data).unwrap();
+        assert!(periods.iter().all(|period| *period == periods[0]));
+
+        let (line, upper, lower) = vwap.calculate_vwap(&data, &periods, 1.0);
+        assert_eq!(line.len(), data.len());
+        assert_float_absolute_eq!(line[0].unwrap(), 100.66667, 1e-3);
+        assert_float_absolute_eq!(upper[0].unwrap(), 100.66667, 1e-3);
+        assert_float_absolute_eq!(line[5].unwrap(), 103.91803, 1e-3);
+        assert_float_absolute_eq!(upper[5].unwrap(), 106.13278, 1e-2);
+        assert_float_absolute_eq!(lower[5].unwrap(), 101.70328, 1e-2);
+        assert_float_absolute_eq!(line[11].unwrap(), 103.65672, 1e-3);
+
+        let anchor = VWAPAnchor::Timestamp(Some("2023-01-01T00:03:00".to_string()));
+        let periods = anchor.periods(&data).unwrap();
+        let (line, upper, _) = vwap.calculate_vwap(&data, &periods, 1.0);
+        assert_eq!(line[2], None);
+        assert_float_absolute_eq!(line[3].unwrap(), 104.33333, 1e-3);
+        assert_float_absolute_eq!(line[11].unwrap(), 104.09114, 1e-3);
+        assert_float_absolute_eq!(upper[11].unwrap(), 106.82009, 1e-2);
+    }
+
+    #[test]
+    fn test_vwap_session_anchor() {
+        let mut data = sample_bars();
+        for (i, bar) in data.iter_mut().enumerate() {
+            bar.timestamp = format!("2023-01-01T{:02}:00:00Z", 10 + i);
+        }
+        let anchor = VWAPAnchor::Session {
+            session: "1700-1600".to_string(),
+            timezone: "Etc/UTC".to_string(),
+        };
+
+        let periods = anchor.periods(&data).unwrap();
+        assert_eq!(periods[0], periods[6]);
+        assert_ne!(periods[6], periods[7]);
+        assert_eq!(periods[7], periods[11]);
+
+        let (line, _, _) = VWAP.calculate_vwap(&data, &periods, 1.0);
+        assert_float_absolute_eq!(line[7].unwrap(), 103.66667, 1e-3);
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::closes;
+
+pub struct WMA;
+
+/// Weighted Moving Average (WMA)
+///
+/// WMA weights the closing prices linearly so that the most recent bar
+/// carries the most weight.
+///
+/// WMA = (1 * Price[n-1] + 2 * Price[n-2] + ... + n * Price[0]) / (n * (n + 1) / 2)
+///
+/// where:
+/// - `n` is the number of periods over which the average is calculated.
+/// - `Price[0]` is the current closing price.
+/// - The first `n - 1` values are undefined.
+impl WMA {
+    pub fn calculate_weighted_average(&self, data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+        self.weighted_average(&closes(data), period)
+    }
+
+    /// Linearly weighted average of an arbitrary series. A value is only
+    /// defined when every value in its window is defined.
+    pub fn weighted_average(
+        &self,
+        values: &VecDeque<Option<f32>>,
+        period: usize,
+    ) -> VecDeque<Option<f32>> {
+        let divisor = (period * (period + 1)) as f32 / 2.0;
+
+        (0..values.len())
+            .map(|i| {
+                if period == 0 || i + 1 < period {
+                    return None;
+                }
+                values
+                    .range(i + 1 - period..=i)
+                    .enumerate()
+                    .try_fold(0.0, |sum, (weight, value)| {
+                        value.map(|value| sum + (weight + 1) as f32 * value)
+                    })
+                    .map(|sum| sum / divisor)
+            })
+            .collect()
+    }
+}
+
+impl Calculation for WMA {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        self.calculate_weighted_average(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::weighted_moving_average::WMA;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_weighted_average() {
+        let wma = WMA;
+        let data = sample_bars();
+
+        let result = wma.calculate_weighted_average(&data, 4);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), 103.9, 1e-4);
+        assert_float_absolute_eq!(result[6].unwrap(), 107.2, 1e-4);
+        assert_float_absolute_eq!(result[11].unwrap(), 104.8, 1e-4);
+    }
+}
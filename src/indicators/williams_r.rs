@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+
+use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::{highest, lowest};
+
+pub struct WilliamsR;
+
+/// Williams %R
+///
+/// Locates the close relative to the high-low range of the last `period`
+/// bars on a scale of -100 to 0.
+///
+/// %R = -100 * (highest high - close) / (highest high - lowest low)
+///
+/// A flat range is reported as the midpoint of -50. The first `period - 1`
+/// values are undefined.
+impl WilliamsR {
+    pub fn calculate_williams_r(&self, data: &[Bar], period: usize) -> VecDeque<Option<f32>> {
+        let highest_high = highest(data, period);
+        let lowest_low = lowest(data, period);
+
+        data.iter()
+            .enumerate()
+            .map(|(i, bar)| match (highest_high[i], lowest_low[i]) {
+                (Some(high), Some(low)) if high > low => {
+                    Some(-100.0 * (high - bar.close) / (high - low))
+                }
+                (Some(_), Some(_)) => Some(-50.0),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Calculation for WilliamsR {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
+        let period = params.first().cloned().unwrap_or(14.0) as usize;
+        self.calculate_williams_r(data, period)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_float_eq::assert_float_absolute_eq;
+
+    use crate::domain::Bar;
+    use crate::indicators::williams_r::WilliamsR;
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_calculate_williams_r() {
+        let williams_r = WilliamsR;
+        let data = sample_bars();
+
+        let result = williams_r.calculate_williams_r(&data, 4);
+        assert_eq!(result.len(), data.len());
+        assert_eq!(result[2], None);
+        assert_float_absolute_eq!(result[3].unwrap(), -23.07692, 1e-3);
+        assert_float_absolute_eq!(result[6].unwrap(), -36.36364, 1e-3);
+        assert_float_absolute_eq!(result[8].unwrap(), -85.71429, 1e-3);
+        assert_float_absolute_eq!(result[11].unwrap(), -7.69231, 1e-3);
+    }
+}
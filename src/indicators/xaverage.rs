@@ -1,6 +1,7 @@
 use std::collections::VecDeque;
 
 use crate::domain::{Bar, Calculation};
+use crate::indicators::functions::closes;
 
 /// XAverage
 ///
@@ -17,17 +18,32 @@ use crate::domain::{Bar, Calculation};
 pub struct XAverage;
 
 impl XAverage {
-    pub fn calculate_xaverage(&self, data: &Vec<Bar>, period: usize) -> VecDeque<f32> {
-        let mut xavg: VecDeque<f32> = VecDeque::new();
+    pub fn calculate_xaverage(&self, data: &Vec<Bar>, period: usize) -> VecDeque<Option<f32>> {
+        self.xaverage(&closes(data), period)
+    }
+
+    /// Exponential average of an arbitrary series, seeded with its first
+    /// defined value. Undefined values leave the average unchanged.
+    pub fn xaverage(&self, values: &VecDeque<Option<f32>>, period: usize) -> VecDeque<Option<f32>> {
+        let mut xavg: VecDeque<Option<f32>> = VecDeque::with_capacity(values.len());
         let factor = 2.0 / (period as f32 + 1.0);
+        let mut prev_xaverage: Option<f32> = None;
+
+        if period == 0 {
+            return values.iter().map(|_| None).collect();
+        }
 
-        for (i, bar) in data.iter().enumerate() {
-            if i == 0 {
-                xavg.push_back(bar.close); // First value is just the close price
-            } else {
-                let prev_xaverage = *xavg.back().unwrap();
-                let new_xaverage = (bar.close - prev_xaverage) * factor + prev_xaverage;
-                xavg.push_back(new_xaverage);
+        for value in values {
+            match (value, prev_xaverage) {
+                (Some(value), None) => {
+                    prev_xaverage = Some(*value); // First value is just the price
+                    xavg.push_back(prev_xaverage);
+                }
+                (Some(value), Some(prev)) => {
+                    prev_xaverage = Some((value - prev) * factor + prev);
+                    xavg.push_back(prev_xaverage);
+                }
+                (None, _) => xavg.push_back(None),
             }
         }
 
@@ -36,7 +52,7 @@ impl XAverage {
 }
 
 impl Calculation for XAverage {
-    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<f32> {
+    fn calculate(&self, data: &Vec<Bar>, params: &Vec<f32>) -> VecDeque<Option<f32>> {
         let period = params.get(0).cloned().unwrap_or(14.0) as usize;
         self.calculate_xaverage(data, period)
     }
@@ -95,10 +111,10 @@ mod tests {
 
         let result = xavg.calculate_xaverage(&data, 3);
         assert_eq!(result.len(), data.len());
-        assert_eq!(result[0], data[0].close);
-        assert_eq!(result[1], 103.0);
-        assert_eq!(result[2], 103.0);
-        assert_eq!(result[3], 104.0);
-        assert_eq!(result[4], 105.0);
+        assert_eq!(result[0], Some(data[0].close));
+        assert_eq!(result[1], Some(103.0));
+        assert_eq!(result[2], Some(103.0));
+        assert_eq!(result[3], Some(104.0));
+        assert_eq!(result[4], Some(105.0));
     }
 }
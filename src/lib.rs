@@ -1,7 +1,11 @@
+pub mod analysis;
 pub mod application;
+pub mod backtest;
+pub mod broker;
 pub mod configuration;
 pub mod datafeed;
 pub mod domain;
 pub mod indicators;
+pub mod paper;
 pub mod routes;
 pub mod storage;
@@ -0,0 +1,339 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::backtest::{Account, Backtest, Context, Fill, Order, OrderType, RiskManager, Side};
+use crate::broker::{AccountState, Broker, MAX_HISTORY, OrderEvent, OrderStatus, PositionState};
+use crate::domain::Tick;
+
+/// Simulated broker
+///
+/// Keeps the orders, positions and profit of an account in memory and fills
+/// orders against ticks as they arrive, with the fill model, slippage,
+/// commission, contracts and risk manager of a backtest, so that paper
+/// trading fills the way backtests do. Orders submitted on a tick fill on
+/// later ticks only.
+///
+/// Every change in the status of an order is recorded as an event. Only the
+/// latest events, fills and trades are kept.
+pub struct PaperBroker {
+    backtest: Backtest,
+    account: Account,
+    risk: Option<RiskManager>,
+    prices: BTreeMap<String, f32>,
+    /// Latest absolute changes between the ticks of each symbol.
+    changes: BTreeMap<String, VecDeque<f32>>,
+    queues: BTreeMap<u64, f32>,
+    /// The open orders as last recorded, by id.
+    open: BTreeMap<u64, Order>,
+    ticks: usize,
+    timestamp: String,
+    events: Vec<OrderEvent>,
+    dropped_events: usize,
+}
+
+impl PaperBroker {
+    pub fn new(backtest: Backtest) -> Self {
+        PaperBroker {
+            account: backtest.account(),
+            risk: backtest.risk(),
+            backtest,
+            prices: BTreeMap::new(),
+            changes: BTreeMap::new(),
+            queues: BTreeMap::new(),
+            open: BTreeMap::new(),
+            ticks: 0,
+            timestamp: String::new(),
+            events: Vec::new(),
+            dropped_events: 0,
+        }
+    }
+
+    pub fn account(&self) -> &Account {
+        &self.account
+    }
+
+    /// Number of events recorded so far, including the dropped ones.
+    pub fn event_count(&self) -> usize {
+        self.dropped_events + self.events.len()
+    }
+
+    fn trim(&mut self) {
+        self.dropped_events += OrderEvent::trim(&mut self.events);
+        self.account.trim(MAX_HISTORY);
+    }
+
+    fn record(&mut self) {
+        if let Some(risk) = self.risk.as_mut() {
+            self.events.extend(
+                risk.rejections
+                    .drain(..)
+                    .map(|rejection| OrderEvent::rejected(&rejection)),
+            );
+        }
+
+        let open: BTreeMap<u64, Order> = self
+            .account
+            .orders
+            .iter()
+            .map(|order| (order.id, order.clone()))
+            .collect();
+        for (id, order) in &self.open {
+            if !open.contains_key(id) {
+                self.events.push(OrderEvent::new(
+                    order,
+                    OrderStatus::Cancelled,
+                    &self.timestamp,
+                ));
+            }
+        }
+        for (id, order) in &open {
+            if !self.open.contains_key(id) {
+                self.events.push(OrderEvent::new(
+                    order,
+                    OrderStatus::Submitted,
+                    &self.timestamp,
+                ));
+            }
+        }
+        self.queues.retain(|id, _| open.contains_key(id));
+        self.open = open;
+        self.trim();
+    }
+}
+
+impl Broker for PaperBroker {
+    /// Fills the open orders of the symbol of the tick that it reaches.
+    fn on_tick(&mut self, tick: &Tick) -> Vec<Fill> {
+        let index = self.ticks;
+        self.ticks += 1;
+        self.timestamp = tick.timestamp.clone();
+        let fill_model = self.backtest.fill_model();
+        let period = self.backtest.volatility_period().unwrap_or(0);
+        let known = self
+            .changes
+            .get(&tick.symbol)
+            .filter(|changes| period > 0 && changes.len() == period)
+            .map(|changes| changes.iter().sum::<f32>() / period as f32);
+
+        let count = self.account.fills.len();
+        for order in std::mem::take(&mut self.account.orders) {
+            let queued = self.queues.entry(order.id).or_insert(0.0);
+            let price = (order.symbol == tick.symbol)
+                .then(|| fill_model.fill_on_tick(&order, tick, queued))
+                .flatten();
+            let Some(price) = price else {
+                self.account.orders.push(order);
+                continue;
+            };
+            self.queues.remove(&order.id);
+            self.open.remove(&order.id);
+            self.backtest.execute(
+                &mut self.account,
+                &order,
+                price,
+                known,
+                index,
+                &tick.timestamp,
+            );
+            let fill = self.account.fills.last().cloned();
+            self.events.push(OrderEvent {
+                price: fill.map(|fill| fill.price),
+                ..OrderEvent::new(&order, OrderStatus::Filled, &tick.timestamp)
+            });
+        }
+
+        if let Some(previous) = self.prices.insert(tick.symbol.clone(), tick.price) {
+            let changes = self.changes.entry(tick.symbol.clone()).or_default();
+            changes.push_back((tick.price - previous).abs());
+            if changes.len() > period {
+                changes.pop_front();
+            }
+        }
+        if let Some(risk) = self.risk.as_mut() {
+            risk.on_tick(&self.account, tick);
+        }
+        let fills = self.account.fills[count..].to_vec();
+        self.trim();
+        fills
+    }
+
+    fn trade<F>(&mut self, symbol: &str, index: usize, strategy: F)
+    where
+        F: FnOnce(&mut Context),
+    {
+        let mut context =
+            Context::new(index, symbol, &mut self.account).with_risk(self.risk.as_mut());
+        strategy(&mut context);
+        self.record();
+    }
+
+    fn submit(
+        &mut self,
+        symbol: &str,
+        side: Side,
+        quantity: f32,
+        order_type: OrderType,
+    ) -> Result<u64, String> {
+        let mut id = None;
+        let index = self.ticks;
+        self.trade(symbol, index, |context| {
+            id = context.submit(side, quantity, order_type);
+        });
+        id.ok_or_else(|| {
+            self.events
+                .last()
+                .and_then(|event| event.reason.clone())
+                .unwrap_or_else(|| "order rejected".to_string())
+        })
+    }
+
+    fn cancel(&mut self, id: u64) -> Result<(), String> {
+        if !self.account.cancel(id) {
+            return Err(format!("order {} is not open", id));
+        }
+        self.record();
+        Ok(())
+    }
+
+    /// Replaces an open order, which loses its place in the queue. Orders
+    /// replaced with a larger quantity are checked by the risk manager again.
+    fn replace(&mut self, id: u64, quantity: f32, order_type: OrderType) -> Result<(), String> {
+        let Some(position) = self.account.orders.iter().position(|order| order.id == id) else {
+            return Err(format!("order {} is not open", id));
+        };
+        let order = self.account.orders.remove(position);
+        if let Some(risk) = self.risk.as_mut()
+            && let Err(err) = risk.check(
+                &self.account,
+                &order.symbol,
+                order.side,
+                quantity,
+                self.ticks,
+            )
+        {
+            self.account.orders.insert(position, order);
+            self.record();
+            return Err(err);
+        }
+
+        let order = Order {
+            quantity,
+            order_type,
+            ..order
+        };
+        self.events.push(OrderEvent::new(
+            &order,
+            OrderStatus::Replaced,
+            &self.timestamp,
+        ));
+        self.queues.remove(&id);
+        self.open.insert(id, order.clone());
+        self.account.orders.insert(position, order);
+        Ok(())
+    }
+
+    fn orders(&self) -> Vec<Order> {
+        self.account.orders.clone()
+    }
+
+    fn events(&self) -> &[OrderEvent] {
+        &self.events
+    }
+
+    fn positions(&self) -> Vec<PositionState> {
+        PositionState::from_account(&self.account, &self.prices)
+    }
+
+    fn state(&self) -> AccountState {
+        let halted = self.risk.as_ref().is_some_and(|risk| risk.is_halted());
+        AccountState::new(&self.timestamp, &self.account, &self.prices, halted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtest::{Backtest, OrderType, RiskManager, Side};
+    use crate::broker::{Broker, MAX_HISTORY, OrderStatus};
+    use crate::domain::Tick;
+    use crate::paper::PaperBroker;
+
+    fn tick(price: f32) -> Tick {
+        Tick {
+            timestamp: "2023-01-02T10:00:00Z".to_string(),
+            symbol: "ES".to_string(),
+            price,
+            volume: 1.0,
+        }
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut broker = PaperBroker::new(Backtest::new(10000.0));
+        let id = broker
+            .submit("ES", Side::Buy, 1.0, OrderType::Limit(90.0))
+            .unwrap();
+        assert!(broker.on_tick(&tick(100.0)).is_empty());
+        assert_eq!(broker.state().open_orders, 1);
+
+        assert!(broker.cancel(id).is_ok());
+        assert!(broker.cancel(id).is_err());
+        let statuses: Vec<OrderStatus> = broker.events().iter().map(|e| e.status).collect();
+        assert_eq!(statuses, [OrderStatus::Submitted, OrderStatus::Cancelled]);
+        assert_eq!(broker.events()[1].timestamp, "2023-01-02T10:00:00Z");
+        assert!(broker.positions().is_empty());
+        assert_eq!(broker.state().equity, 10000.0);
+    }
+
+    #[test]
+    fn test_replace() {
+        let risk = RiskManager::new().with_max_symbol_exposure(50.0);
+        let mut broker = PaperBroker::new(Backtest::new(10000.0).with_risk(risk));
+        broker.on_tick(&tick(100.0));
+        let id = broker
+            .submit("ES", Side::Buy, 10.0, OrderType::Limit(90.0))
+            .unwrap();
+
+        // 60 units at 100 are more than half of the equity
+        assert!(broker.replace(id, 60.0, OrderType::Limit(95.0)).is_err());
+        assert_eq!(broker.orders()[0].quantity, 10.0);
+        assert!(broker.replace(id, 20.0, OrderType::Limit(101.0)).is_ok());
+        assert!(broker.replace(id + 1, 20.0, OrderType::Market).is_err());
+
+        let fills = broker.on_tick(&tick(100.0));
+        assert_eq!(fills.len(), 1);
+        assert_eq!((fills[0].order_id, fills[0].quantity), (id, 20.0));
+        let statuses: Vec<OrderStatus> = broker.events().iter().map(|e| e.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                OrderStatus::Submitted,
+                OrderStatus::Rejected,
+                OrderStatus::Replaced,
+                OrderStatus::Filled,
+            ]
+        );
+        assert_eq!(broker.positions()[0].quantity, 20.0);
+    }
+
+    #[test]
+    fn test_bounded_history() {
+        let mut broker = PaperBroker::new(Backtest::new(10000.0));
+        let trades = MAX_HISTORY / 2 + 100;
+        for _ in 0..trades {
+            broker
+                .submit("ES", Side::Buy, 1.0, OrderType::Market)
+                .unwrap();
+            broker.on_tick(&tick(100.0));
+            broker
+                .submit("ES", Side::Sell, 1.0, OrderType::Market)
+                .unwrap();
+            broker.on_tick(&tick(101.0));
+        }
+
+        assert!(broker.events().len() <= MAX_HISTORY);
+        assert_eq!(broker.event_count(), trades * 4);
+        assert!(broker.account().fills.len() <= MAX_HISTORY);
+        assert_eq!(broker.account().fill_count(), trades * 2);
+        assert_eq!(broker.state().realized_profit, trades as f64);
+        assert_eq!(broker.state().equity, 10000.0 + trades as f64);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::backtest::Side;
+use crate::broker::{Broker, OrderStatus};
+use crate::paper::PaperBroker;
+use crate::storage::{Database, Error};
+
+const TABLES: [&str; 3] = [
+    "CREATE TABLE IF NOT EXISTS paper_orders (session SYMBOL, order_id LONG, symbol SYMBOL, \
+     side SYMBOL, quantity DOUBLE, status SYMBOL, price DOUBLE, reason STRING, \
+     timestamp TIMESTAMP) timestamp(timestamp) PARTITION BY DAY",
+    "CREATE TABLE IF NOT EXISTS paper_fills (session SYMBOL, order_id LONG, symbol SYMBOL, \
+     side SYMBOL, quantity DOUBLE, price DOUBLE, commission DOUBLE, \
+     timestamp TIMESTAMP) timestamp(timestamp) PARTITION BY DAY",
+    "CREATE TABLE IF NOT EXISTS paper_account (session SYMBOL, cash DOUBLE, equity DOUBLE, \
+     realized_profit DOUBLE, unrealized_profit DOUBLE, commission DOUBLE, open_orders INT, \
+     halted BOOLEAN, timestamp TIMESTAMP) timestamp(timestamp) PARTITION BY DAY",
+];
+
+/// Persists a paper trading session to QuestDB: every order event and fill,
+/// and a snapshot of the account whenever something was recorded.
+pub struct Journal {
+    session: String,
+    /// Number of events and fills journaled so far.
+    events: usize,
+    fills: usize,
+}
+
+impl Journal {
+    pub fn new(session: &str) -> Self {
+        Journal {
+            session: session.to_string(),
+            events: 0,
+            fills: 0,
+        }
+    }
+
+    pub async fn create_tables(database: &Database) -> Result<(), Error> {
+        for statement in TABLES {
+            database.execute(statement).await?;
+        }
+        Ok(())
+    }
+
+    /// The statements inserting what the broker recorded since the last
+    /// call. Takes the broker by reference so that the statements can be
+    /// executed once it is released.
+    pub fn statements(&mut self, broker: &PaperBroker) -> Vec<String> {
+        let session = quote(&self.session);
+        let mut statements = Vec::new();
+        // Events and fills dropped by the broker before being journaled are
+        // skipped.
+        let events = broker.events();
+        let start = self
+            .events
+            .saturating_sub(broker.event_count() - events.len());
+        for event in &events[start..] {
+            let status = match event.status {
+                OrderStatus::Submitted => "submitted",
+                OrderStatus::PartiallyFilled => "partially_filled",
+                OrderStatus::Filled => "filled",
+                OrderStatus::Replaced => "replaced",
+                OrderStatus::Cancelled => "cancelled",
+                OrderStatus::Rejected => "rejected",
+            };
+            statements.push(format!(
+                "INSERT INTO paper_orders VALUES ({}, {}, {}, {}, {}, '{}', {}, {}, {})",
+                session,
+                event
+                    .order_id
+                    .map_or("NULL".to_string(), |id| id.to_string()),
+                quote(&event.symbol),
+                side(event.side),
+                event.quantity,
+                status,
+                event
+                    .price
+                    .map_or("NULL".to_string(), |price| price.to_string()),
+                event.reason.as_deref().map_or("NULL".to_string(), quote),
+                quote(&event.timestamp),
+            ));
+        }
+        let account = broker.account();
+        let start = self
+            .fills
+            .saturating_sub(account.fill_count() - account.fills.len());
+        for fill in &account.fills[start..] {
+            statements.push(format!(
+                "INSERT INTO paper_fills VALUES ({}, {}, {}, {}, {}, {}, {}, {})",
+                session,
+                fill.order_id,
+                quote(&fill.symbol),
+                side(fill.side),
+                fill.quantity,
+                fill.price,
+                fill.commission,
+                quote(&fill.timestamp),
+            ));
+        }
+
+        if !statements.is_empty() {
+            let state = broker.state();
+            statements.push(format!(
+                "INSERT INTO paper_account VALUES ({}, {}, {}, {}, {}, {}, {}, {}, {})",
+                session,
+                state.cash,
+                state.equity,
+                state.realized_profit,
+                state.unrealized_profit,
+                state.commission,
+                state.open_orders,
+                state.halted,
+                quote(&state.timestamp),
+            ));
+        }
+        self.events = broker.event_count();
+        self.fills = account.fill_count();
+        statements
+    }
+}
+
+fn side(side: Side) -> &'static str {
+    match side {
+        Side::Buy => "'buy'",
+        Side::Sell => "'sell'",
+    }
+}
+
+/// A string literal, with its quotes escaped.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::backtest::{Backtest, OrderType, RiskManager, Side};
+    use crate::broker::Broker;
+    use crate::domain::Tick;
+    use crate::paper::{Journal, PaperBroker};
+
+    #[test]
+    fn test_statements() {
+        let risk = RiskManager::new().with_max_open_orders(1);
+        let mut broker = PaperBroker::new(Backtest::new(10000.0).with_risk(risk));
+        let mut journal = Journal::new("o'neil");
+        let tick = |price: f32| Tick {
+            timestamp: "2023-01-02T10:00:00Z".to_string(),
+            symbol: "ES".to_string(),
+            price,
+            volume: 1.0,
+        };
+        broker.on_tick(&tick(100.0));
+        assert!(journal.statements(&broker).is_empty());
+
+        broker
+            .submit("ES", Side::Buy, 1.0, OrderType::Market)
+            .unwrap();
+        assert!(
+            broker
+                .submit("ES", Side::Buy, 1.0, OrderType::Market)
+                .is_err()
+        );
+        broker.on_tick(&tick(101.0));
+        let statements = journal.statements(&broker);
+        assert_eq!(statements.len(), 5);
+        assert!(statements[0].contains("'submitted'"));
+        assert_eq!(
+            statements[1],
+            "INSERT INTO paper_orders VALUES ('o''neil', NULL, 'ES', 'buy', 1, 'rejected', NULL, \
+             '1 orders are open, at most 1 are allowed', '2023-01-02T10:00:00Z')"
+        );
+        assert!(statements[2].contains("'filled', 101,"));
+        assert!(statements[3].starts_with("INSERT INTO paper_fills VALUES ('o''neil', 1, 'ES'"));
+        assert!(statements[4].starts_with("INSERT INTO paper_account VALUES ('o''neil', 9899,"));
+        assert!(journal.statements(&broker).is_empty());
+    }
+}
@@ -0,0 +1,9 @@
+mod broker;
+mod journal;
+mod session;
+mod trader;
+
+pub use broker::PaperBroker;
+pub use journal::Journal;
+pub use session::{PaperTrading, SessionInfo};
+pub use trader::PaperTrader;
@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use actix_web::rt;
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::datafeed::{LiveTicks, POLL_LIMIT};
+use crate::paper::{Journal, PaperTrader};
+use crate::storage::Database;
+
+/// How often sessions poll for new ticks.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Number of stopped sessions kept for their state to be looked up.
+const MAX_STOPPED_SESSIONS: usize = 16;
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SessionInfo {
+    pub session: u64,
+    pub symbol: String,
+    pub running: bool,
+}
+
+struct Session {
+    trader: Arc<Mutex<PaperTrader>>,
+    running: Arc<AtomicBool>,
+}
+
+/// Paper trading sessions
+///
+/// Each session runs a trader on a background task that polls for the new
+/// ticks of its symbol, feeds them to the trader, and journals what it
+/// recorded to QuestDB. The latest stopped sessions keep their state until
+/// more sessions are started.
+///
+/// Session ids key the journal, so they count up from the time the server
+/// started, in microseconds, rather than from one: sessions of a restarted
+/// server do not reuse the ids of earlier sessions.
+pub struct PaperTrading {
+    database: Database,
+    sessions: Mutex<BTreeMap<u64, Session>>,
+    next_session: AtomicU64,
+}
+
+impl PaperTrading {
+    pub fn new(database: Database) -> Self {
+        PaperTrading {
+            database,
+            sessions: Mutex::new(BTreeMap::new()),
+            next_session: AtomicU64::new(Utc::now().timestamp_micros() as u64),
+        }
+    }
+
+    /// Starts trading on the ticks of the feed, returning the id of the
+    /// session.
+    pub fn start(&self, trader: PaperTrader, mut feed: LiveTicks) -> u64 {
+        let id = self.next_session.fetch_add(1, Ordering::Relaxed);
+        let trader = Arc::new(Mutex::new(trader));
+        let running = Arc::new(AtomicBool::new(true));
+        {
+            let mut sessions = self.sessions.lock().unwrap();
+            let stopped: Vec<u64> = sessions
+                .iter()
+                .filter(|(_, session)| !session.running.load(Ordering::Relaxed))
+                .map(|(id, _)| *id)
+                .collect();
+            for id in &stopped[..stopped.len().saturating_sub(MAX_STOPPED_SESSIONS - 1)] {
+                sessions.remove(id);
+            }
+            sessions.insert(
+                id,
+                Session {
+                    trader: trader.clone(),
+                    running: running.clone(),
+                },
+            );
+        }
+
+        let database = self.database.clone();
+        let mut journal = Journal::new(&id.to_string());
+        rt::spawn(async move {
+            if let Err(err) = Journal::create_tables(&database).await {
+                tracing::warn!("paper session {}: failed to create tables: {}", id, err);
+            }
+            // Polls again straight away while catching up on past ticks
+            let mut catching_up = false;
+            while running.load(Ordering::Relaxed) {
+                if !catching_up {
+                    rt::time::sleep(POLL_INTERVAL).await;
+                }
+                let ticks = match feed.poll(&database).await {
+                    Ok(ticks) => ticks,
+                    Err(err) => {
+                        tracing::warn!("paper session {}: failed to poll ticks: {}", id, err);
+                        catching_up = false;
+                        continue;
+                    }
+                };
+                catching_up = ticks.len() == POLL_LIMIT;
+                let statements = {
+                    let mut trader = trader.lock().unwrap();
+                    for tick in &ticks {
+                        trader.on_tick(tick);
+                    }
+                    journal.statements(trader.broker())
+                };
+                for statement in statements {
+                    if let Err(err) = database.execute(&statement).await {
+                        tracing::warn!("paper session {}: failed to journal: {}", id, err);
+                    }
+                }
+            }
+        });
+        id
+    }
+
+    /// Stops a session, returning whether it was running, or `None` when
+    /// there is no such session.
+    pub fn stop(&self, id: u64) -> Option<bool> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .get(&id)
+            .map(|session| session.running.swap(false, Ordering::Relaxed))
+    }
+
+    pub fn sessions(&self) -> Vec<SessionInfo> {
+        self.sessions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(id, session)| SessionInfo {
+                session: *id,
+                symbol: session.trader.lock().unwrap().symbol().to_string(),
+                running: session.running.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+
+    /// Calls `f` with the trader of a session, if there is one.
+    pub fn with_trader<R>(&self, id: u64, f: impl FnOnce(&mut PaperTrader) -> R) -> Option<R> {
+        let trader = self.sessions.lock().unwrap().get(&id)?.trader.clone();
+        let mut trader = trader.lock().unwrap();
+        Some(f(&mut trader))
+    }
+}
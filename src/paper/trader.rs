@@ -0,0 +1,253 @@
+use crate::backtest::Strategy;
+use crate::broker::Broker;
+use crate::datafeed::BarBuilder;
+use crate::domain::{Bar, Interval, Tick};
+use crate::paper::PaperBroker;
+
+/// Runs a strategy forward on the live ticks of a symbol, trading through a
+/// broker, the simulated one unless another is given.
+///
+/// The strategy starts on the warm-up bars, if any, with the first tick. It
+/// is then called on every tick and on every bar of the interval built from
+/// the ticks, keeping the latest `MAX_BARS` bars. Bars are indexed from the
+/// first warm-up bar.
+pub struct PaperTrader<B: Broker = PaperBroker> {
+    symbol: String,
+    strategy: Box<dyn Strategy + Send>,
+    builder: BarBuilder,
+    bars: Vec<Bar>,
+    /// Number of bars so far, including those no longer kept.
+    bar_count: usize,
+    started: bool,
+    ticks: usize,
+    broker: B,
+}
+
+/// Number of bars kept for the strategy to calculate its indicators.
+pub const MAX_BARS: usize = 1000;
+
+impl<B: Broker> PaperTrader<B> {
+    pub fn new(
+        symbol: &str,
+        interval: &Interval,
+        strategy: Box<dyn Strategy + Send>,
+        broker: B,
+    ) -> Self {
+        PaperTrader {
+            symbol: symbol.to_string(),
+            strategy,
+            builder: BarBuilder::new(interval),
+            bars: Vec::new(),
+            bar_count: 0,
+            started: false,
+            ticks: 0,
+            broker,
+        }
+    }
+
+    /// Bars before the live ticks, for the strategy to calculate its
+    /// indicators from.
+    pub fn with_bars(mut self, bars: Vec<Bar>) -> Self {
+        self.bar_count = bars.len();
+        self.bars = bars;
+        self.bars.drain(..self.bars.len().saturating_sub(MAX_BARS));
+        self
+    }
+
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    pub fn bars(&self) -> &[Bar] {
+        &self.bars
+    }
+
+    pub fn broker(&self) -> &B {
+        &self.broker
+    }
+
+    pub fn broker_mut(&mut self) -> &mut B {
+        &mut self.broker
+    }
+
+    /// Fills the open orders against a tick of the symbol, then calls the
+    /// strategy with the tick and with any bar it closes. Ticks of other
+    /// symbols are ignored.
+    pub fn on_tick(&mut self, tick: &Tick) {
+        if tick.symbol != self.symbol {
+            return;
+        }
+        self.broker.on_tick(tick);
+
+        let strategy = &mut self.strategy;
+        if !self.started {
+            strategy.on_start(&self.bars);
+            self.started = true;
+        }
+        self.broker.trade(&self.symbol, self.ticks, |context| {
+            strategy.on_tick(context, tick)
+        });
+        self.ticks += 1;
+
+        for bar in self.builder.push(tick) {
+            self.bars.push(bar);
+            self.bars.drain(..self.bars.len().saturating_sub(MAX_BARS));
+            self.bar_count += 1;
+            let bar = &self.bars[self.bars.len() - 1];
+            strategy.on_append(&self.bars);
+            self.broker
+                .trade(&self.symbol, self.bar_count - 1, |context| {
+                    strategy.on_bar(context, bar)
+                });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::backtest::{Backtest, Context, IndicatorCross, Strategy};
+    use crate::broker::{Broker, OrderStatus};
+    use crate::domain::{Bar, Indicators, Interval, Tick};
+    use crate::paper::trader::MAX_BARS;
+    use crate::paper::{PaperBroker, PaperTrader};
+
+    /// A short uptrend with a pullback, starting with the bars of the moving
+    /// average tests.
+    fn sample_bars() -> Vec<Bar> {
+        [
+            (100.0, 105.0, 95.0, 102.0, 49.0),
+            (102.0, 106.0, 98.0, 104.0, 49.0),
+            (104.0, 107.0, 99.0, 103.0, 49.0),
+            (103.0, 108.0, 100.0, 105.0, 49.0),
+            (103.0, 107.0, 100.0, 106.0, 49.0),
+            (106.0, 110.0, 104.0, 109.0, 60.0),
+            (109.0, 111.0, 105.0, 107.0, 45.0),
+            (107.0, 108.0, 101.0, 102.0, 80.0),
+            (102.0, 104.0, 97.0, 99.0, 70.0),
+            (99.0, 103.0, 96.0, 101.0, 55.0),
+            (101.0, 106.0, 100.0, 105.0, 50.0),
+            (105.0, 109.0, 103.0, 108.0, 65.0),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, (open, high, low, close, volume))| Bar {
+            timestamp: format!("2023-01-01T00:{:02}:00Z", i),
+            open: *open,
+            high: *high,
+            low: *low,
+            close: *close,
+            volume: *volume,
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_paper_trader() {
+        let strategy = IndicatorCross::new(Indicators::SMA(3), 1.0);
+        let broker = PaperBroker::new(Backtest::new(10000.0));
+        let mut trader = PaperTrader::new("ES", &Interval::T49, Box::new(strategy), broker);
+
+        // An opening tick, then one tick closing each bar at its close
+        trader.on_tick(&Tick {
+            timestamp: "2022-12-31T23:59:00Z".to_string(),
+            symbol: "ES".to_string(),
+            price: 100.0,
+            volume: 0.0,
+        });
+        for bar in sample_bars() {
+            trader.on_tick(&Tick {
+                timestamp: bar.timestamp,
+                symbol: "ES".to_string(),
+                price: bar.close,
+                volume: 49.0,
+            });
+        }
+        trader.on_tick(&Tick {
+            timestamp: "2023-01-01T00:12:00Z".to_string(),
+            symbol: "NQ".to_string(),
+            price: 1.0,
+            volume: 49.0,
+        });
+
+        assert_eq!(trader.bars().len(), 12);
+        let broker = trader.broker();
+        let fills: Vec<(f32, f32)> = broker
+            .account()
+            .fills
+            .iter()
+            .map(|fill| (fill.quantity, fill.price))
+            .collect();
+        // Signals on the same bars as the backtest, filled on the next tick
+        assert_eq!(fills, [(1.0, 106.0), (2.0, 102.0), (2.0, 105.0)]);
+        let statuses: Vec<OrderStatus> = broker.events().iter().map(|e| e.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                OrderStatus::Submitted,
+                OrderStatus::Filled,
+                OrderStatus::Submitted,
+                OrderStatus::Filled,
+                OrderStatus::Submitted,
+                OrderStatus::Filled,
+            ]
+        );
+        assert_eq!(broker.positions()[0].quantity, 1.0);
+        assert_eq!(broker.state().open_orders, 0);
+    }
+
+    /// Records the bars the strategy is called with.
+    #[derive(Default)]
+    struct Calls {
+        starts: Vec<usize>,
+        appended: Vec<usize>,
+        indexes: Vec<usize>,
+    }
+
+    struct Recorder(Arc<Mutex<Calls>>);
+
+    impl Strategy for Recorder {
+        fn on_start(&mut self, data: &[Bar]) {
+            self.0.lock().unwrap().starts.push(data.len());
+        }
+
+        fn on_append(&mut self, data: &[Bar]) {
+            self.0.lock().unwrap().appended.push(data.len());
+        }
+
+        fn on_bar(&mut self, context: &mut Context, _bar: &Bar) {
+            self.0.lock().unwrap().indexes.push(context.index());
+        }
+    }
+
+    #[test]
+    fn test_bar_history() {
+        let calls = Arc::new(Mutex::new(Calls::default()));
+        let strategy = Box::new(Recorder(calls.clone()));
+        let broker = PaperBroker::new(Backtest::new(10000.0));
+        let warm_up = sample_bars();
+        let mut trader =
+            PaperTrader::new("ES", &Interval::T49, strategy, broker).with_bars(warm_up.clone());
+        for _ in 1..MAX_BARS {
+            trader.on_tick(&Tick {
+                timestamp: "2023-01-02T00:00:00Z".to_string(),
+                symbol: "ES".to_string(),
+                price: 100.0,
+                volume: 49.0,
+            });
+        }
+
+        // The oldest bars are dropped, and the strategy only starts once
+        assert_eq!(trader.bars().len(), MAX_BARS);
+        assert_eq!(trader.bars()[0], warm_up[warm_up.len() - 1]);
+        let calls = calls.lock().unwrap();
+        assert_eq!(calls.starts, [warm_up.len()]);
+        assert_eq!(calls.appended.len(), MAX_BARS - 1);
+        assert_eq!(calls.appended[0], warm_up.len() + 1);
+        assert_eq!(calls.appended.last(), Some(&MAX_BARS));
+        // Bars are still counted once the oldest are dropped
+        assert_eq!(calls.indexes[0], warm_up.len());
+        assert_eq!(calls.indexes.last(), Some(&(warm_up.len() + MAX_BARS - 2)));
+    }
+}
@@ -0,0 +1,81 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analysis::{FibonacciLevels, ZigZag},
+    datafeed::HistoricalData,
+    domain::{Bar, Interval, Resolution},
+    routes::SeriesParameters,
+};
+
+use super::series::parse_datetime;
+
+#[derive(Debug, Deserialize)]
+pub struct FibonacciParameters {
+    /// Two or three comma separated anchor timestamps. Each anchor is the
+    /// first bar at or after its timestamp. Defaults to the last swings.
+    anchors: Option<String>,
+    /// Number of swings to anchor the levels at when no anchors are given,
+    /// 2 or 3. Defaults to 2.
+    points: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FibonacciAnalysis {
+    pub symbol: String,
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub levels: FibonacciLevels,
+}
+
+/// Fibonacci retracement, extension and time zone levels between explicit
+/// anchors or the most recent swings of the requested bars.
+pub async fn get_fibonacci_levels(
+    historical: web::Data<HistoricalData>,
+    series: web::Query<SeriesParameters>,
+    params: web::Query<FibonacciParameters>,
+) -> impl Responder {
+    let bad_request =
+        |err: String| HttpResponse::BadRequest().body(format!("get_fibonacci_levels: {}", err));
+    let threshold = match series.reversal_threshold() {
+        Ok(threshold) => threshold,
+        Err(err) => return bad_request(err),
+    };
+    let data = match series.fetch(&historical, "get_fibonacci_levels").await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let levels = match &params.anchors {
+        Some(anchors) => anchor_indices(&data.data, anchors)
+            .and_then(|indices| FibonacciLevels::from_indices(&data.data, &indices)),
+        None => {
+            let swings = ZigZag::new(threshold).swings(&data.data);
+            FibonacciLevels::from_swings(&data.data, &swings, params.points.unwrap_or(2))
+        }
+    };
+
+    match levels {
+        Ok(levels) => HttpResponse::Ok().json(FibonacciAnalysis {
+            symbol: series.symbol.clone(),
+            resolution: series.resolution.clone(),
+            interval: series.interval,
+            levels,
+        }),
+        Err(err) => bad_request(err),
+    }
+}
+
+/// Indices of the first bars at or after each of the comma separated
+/// timestamps.
+fn anchor_indices(data: &[Bar], anchors: &str) -> Result<Vec<usize>, String> {
+    anchors
+        .split(',')
+        .map(|anchor| {
+            let anchor = parse_datetime(anchor.trim())?;
+            data.iter()
+                .position(|bar| bar.datetime().is_some_and(|datetime| datetime >= anchor))
+                .ok_or_else(|| format!("no bar at or after anchor {}", anchor))
+        })
+        .collect()
+}
@@ -0,0 +1,72 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analysis::{Level, SupportResistance, ZigZag},
+    datafeed::HistoricalData,
+    domain::{Interval, Resolution},
+    routes::{SeriesParameters, resolve_symbol},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct LevelParameters {
+    /// Relative distance within which prices are clustered into a zone.
+    /// Defaults to 0.005.
+    tolerance: Option<f32>,
+    /// Number of levels to return, strongest first. Defaults to 10.
+    top: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LevelAnalysis {
+    pub symbol: String,
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub levels: Vec<Level>,
+}
+
+/// Support and resistance zones of the requested bars, with round numbers at
+/// the tick size of the symbol, which must be resolved.
+pub async fn get_support_resistance(
+    historical: web::Data<HistoricalData>,
+    series: web::Query<SeriesParameters>,
+    params: web::Query<LevelParameters>,
+) -> impl Responder {
+    let bad_request =
+        |err: String| HttpResponse::BadRequest().body(format!("get_support_resistance: {}", err));
+    let threshold = match series.reversal_threshold() {
+        Ok(threshold) => threshold,
+        Err(err) => return bad_request(err),
+    };
+    if let Some(tolerance) = params.tolerance
+        && (!tolerance.is_finite() || tolerance <= 0.0)
+    {
+        return bad_request("tolerance must be a positive number".to_string());
+    }
+    let Some(info) = resolve_symbol(&series.symbol, &series.exchange) else {
+        return bad_request(format!(
+            "unknown symbol '{}' on {}",
+            series.symbol, series.exchange
+        ));
+    };
+    let data = match series.fetch(&historical, "get_support_resistance").await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let mut detector = SupportResistance::new(info.tick_size());
+    if let Some(tolerance) = params.tolerance {
+        detector = detector.with_tolerance(tolerance);
+    }
+
+    let swings = ZigZag::new(threshold).swings(&data.data);
+    let mut levels = detector.levels(&data.data, &swings);
+    levels.truncate(params.top.unwrap_or(10));
+
+    HttpResponse::Ok().json(LevelAnalysis {
+        symbol: series.symbol.clone(),
+        resolution: series.resolution.clone(),
+        interval: series.interval,
+        levels,
+    })
+}
@@ -0,0 +1,9 @@
+mod fibonacci;
+mod levels;
+mod series;
+mod waves;
+
+pub use fibonacci::*;
+pub use levels::*;
+pub use series::*;
+pub use waves::*;
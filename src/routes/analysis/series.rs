@@ -0,0 +1,77 @@
+use actix_web::HttpResponse;
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::{
+    analysis::ReversalThreshold,
+    datafeed::HistoricalData,
+    domain::{EnhancedSeries, Interval, Resolution},
+};
+
+/// Query parameters selecting the bars that an analysis runs over. Analysis
+/// routes extract these alongside their own parameters from the same query.
+#[derive(Debug, Deserialize)]
+pub struct SeriesParameters {
+    pub symbol: String,
+    pub exchange: String,
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub from: String,
+    pub to: Option<String>,
+    pub limit_lower: Option<usize>,
+    pub limit_upper: Option<usize>,
+    /// Reversal threshold for swing detection, e.g. `percent:5`, `atr:14:3`
+    /// or `bars:5`. Defaults to `atr:14:3`.
+    pub swings: Option<String>,
+}
+
+impl SeriesParameters {
+    /// Fetches the selected bars, responding with a bad request when the
+    /// parameters cannot be parsed.
+    pub async fn fetch(
+        &self,
+        historical: &HistoricalData,
+        context: &str,
+    ) -> Result<EnhancedSeries, HttpResponse> {
+        let bad_request =
+            |err: String| HttpResponse::BadRequest().body(format!("{}: {}", context, err));
+        let from = parse_datetime(&self.from).map_err(bad_request)?;
+        let to = self
+            .to
+            .as_deref()
+            .map(parse_datetime)
+            .transpose()
+            .map_err(bad_request)?;
+
+        historical
+            .fetch(
+                &self.symbol,
+                &self.exchange,
+                &self.resolution,
+                &self.interval,
+                &from,
+                &to,
+                &self.limit_lower,
+                &self.limit_upper,
+                &None,
+                &None,
+            )
+            .await
+            .map_err(|err| {
+                HttpResponse::InternalServerError().body(format!("{}: {}", context, err))
+            })
+    }
+
+    pub fn reversal_threshold(&self) -> Result<ReversalThreshold, String> {
+        self.swings
+            .as_deref()
+            .map(str::parse)
+            .unwrap_or_else(|| Ok(ReversalThreshold::default()))
+    }
+}
+
+pub(crate) fn parse_datetime(value: &str) -> Result<NaiveDateTime, String> {
+    value
+        .parse()
+        .map_err(|err| format!("invalid timestamp '{}': {}", value, err))
+}
@@ -0,0 +1,52 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analysis::{ElliottWave, Swing, WaveCount},
+    datafeed::HistoricalData,
+    domain::{Interval, Resolution},
+    routes::SeriesParameters,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct WaveParameters {
+    /// Number of counts to return, best first. Defaults to 5.
+    top: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WaveAnalysis {
+    pub symbol: String,
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub swings: Vec<Swing>,
+    pub counts: Vec<WaveCount>,
+}
+
+/// Proposes the best Elliott Wave counts over the swings of the requested
+/// bars.
+pub async fn get_wave_counts(
+    historical: web::Data<HistoricalData>,
+    series: web::Query<SeriesParameters>,
+    params: web::Query<WaveParameters>,
+) -> impl Responder {
+    let threshold = match series.reversal_threshold() {
+        Ok(threshold) => threshold,
+        Err(err) => return HttpResponse::BadRequest().body(format!("get_wave_counts: {}", err)),
+    };
+    let data = match series.fetch(&historical, "get_wave_counts").await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let swings = data.with_swings(&threshold).swings.unwrap_or_default();
+    let counts = ElliottWave.top_counts(&swings, params.top.unwrap_or(5));
+
+    HttpResponse::Ok().json(WaveAnalysis {
+        symbol: series.symbol.clone(),
+        resolution: series.resolution.clone(),
+        interval: series.interval,
+        swings,
+        counts,
+    })
+}
@@ -0,0 +1,13 @@
+mod monte_carlo;
+mod optimize;
+mod portfolio;
+mod report;
+mod strategy;
+mod walk_forward;
+
+pub use monte_carlo::*;
+pub use optimize::*;
+pub use portfolio::*;
+pub use report::*;
+pub use strategy::*;
+pub use walk_forward::*;
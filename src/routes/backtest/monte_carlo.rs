@@ -0,0 +1,131 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backtest::{MonteCarlo, MonteCarloResult, Resampling},
+    datafeed::HistoricalData,
+    domain::{Interval, Resolution, Series},
+    routes::{SeriesParameters, StrategyParameters, resolve_symbol},
+};
+
+/// Most simulations a single request may run.
+const MAX_RUNS: usize = 100000;
+
+#[derive(Debug, Deserialize)]
+pub struct MonteCarloParameters {
+    /// Number of simulations. Defaults to 1000.
+    runs: Option<usize>,
+    /// `reshuffle` or `bootstrap`. Defaults to `reshuffle`.
+    resampling: Option<String>,
+    /// Maximum extra slippage per unit on entry and on exit. Defaults to 0.
+    random_slippage: Option<f32>,
+    /// Probability of skipping each trade. Defaults to 0.
+    skip: Option<f64>,
+    /// Loss of the initial capital, in percent, that counts as ruin.
+    /// Defaults to 50.
+    ruin: Option<f64>,
+    seed: Option<u64>,
+}
+
+impl MonteCarloParameters {
+    fn monte_carlo(&self) -> Result<MonteCarlo, String> {
+        let runs = self.runs.unwrap_or(1000);
+        if runs == 0 || runs > MAX_RUNS {
+            return Err(format!("runs must be between 1 and {}", MAX_RUNS));
+        }
+        let resampling = match &self.resampling {
+            Some(resampling) => resampling.parse()?,
+            None => Resampling::Reshuffle,
+        };
+        let mut monte_carlo = MonteCarlo::new(runs, resampling);
+        if let Some(slippage) = self.random_slippage {
+            if !slippage.is_finite() || slippage < 0.0 {
+                return Err("random_slippage must not be negative".to_string());
+            }
+            monte_carlo = monte_carlo.with_slippage(slippage);
+        }
+        if let Some(skip) = self.skip {
+            if !(0.0..=1.0).contains(&skip) {
+                return Err("skip must be between 0 and 1".to_string());
+            }
+            monte_carlo = monte_carlo.with_skip(skip);
+        }
+        if let Some(ruin) = self.ruin {
+            if !(ruin > 0.0 && ruin <= 100.0) {
+                return Err("ruin must be above 0 and at most 100".to_string());
+            }
+            monte_carlo = monte_carlo.with_ruin(ruin);
+        }
+        if let Some(seed) = self.seed {
+            monte_carlo = monte_carlo.with_seed(seed);
+        }
+        Ok(monte_carlo)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MonteCarloAnalysis {
+    pub symbol: String,
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub indicator: String,
+    pub params: Vec<f32>,
+    pub initial_capital: f64,
+    pub final_equity: f64,
+    pub trade_count: usize,
+    pub simulation: MonteCarloResult,
+}
+
+/// Backtests the indicator crossover strategy on the requested bars and
+/// simulates its trades with Monte Carlo resampling.
+pub async fn get_monte_carlo(
+    historical: web::Data<HistoricalData>,
+    series: web::Query<SeriesParameters>,
+    strategy: web::Query<StrategyParameters>,
+    params: web::Query<MonteCarloParameters>,
+) -> impl Responder {
+    let bad_request =
+        |err: String| HttpResponse::BadRequest().body(format!("get_monte_carlo: {}", err));
+    let symbol = resolve_symbol(&series.symbol, &series.exchange);
+    let (mut runner, backtest, monte_carlo) =
+        match strategy.strategy(symbol.as_ref()).and_then(|runner| {
+            Ok((
+                runner,
+                strategy.backtest(&series.symbol, symbol.as_ref())?,
+                params.monte_carlo()?,
+            ))
+        }) {
+            Ok(run) => run,
+            Err(err) => return bad_request(err),
+        };
+    let monte_carlo = monte_carlo.with_contract(&series.symbol, backtest.contract(&series.symbol));
+    let data = match series.fetch(&historical, "get_monte_carlo").await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let data = Series::new(data.symbol, data.resolution, data.interval, data.data);
+    let strategy = strategy.into_inner();
+
+    // Backtests and simulations are CPU bound, so run them off the async workers
+    let analysis = web::block(move || {
+        let result = backtest.run(&mut runner, &data);
+        MonteCarloAnalysis {
+            symbol: data.symbol,
+            resolution: data.resolution,
+            interval: data.interval,
+            params: strategy.indicator_params().unwrap_or_default(),
+            indicator: strategy.indicator,
+            initial_capital: result.initial_capital,
+            final_equity: result.final_equity(),
+            trade_count: result.trades.len(),
+            simulation: monte_carlo.run(&result),
+        }
+    })
+    .await;
+
+    match analysis {
+        Ok(analysis) => HttpResponse::Ok().json(analysis),
+        Err(err) => HttpResponse::InternalServerError().body(format!("get_monte_carlo: {}", err)),
+    }
+}
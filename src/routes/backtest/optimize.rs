@@ -0,0 +1,128 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backtest::{Evaluation, Objective, Optimizer, ParameterRange, Report, Search},
+    datafeed::HistoricalData,
+    domain::{Interval, Resolution, Series},
+    routes::{SeriesParameters, StrategyParameters, resolve_symbol},
+};
+
+/// Most backtests a single optimization may run.
+const MAX_EVALUATIONS: usize = 10000;
+
+#[derive(Debug, Deserialize)]
+pub struct OptimizeParameters {
+    /// Comma-separated range of each indicator parameter, e.g.
+    /// `5:20:5,2,20:40:10` for the AMA `er_period`, `fast_period` and
+    /// `slow_period`.
+    pub ranges: String,
+    /// `grid`, `random:<samples>[:<seed>]` or
+    /// `genetic:<population>:<generations>[:<seed>]`. Defaults to `grid`.
+    pub search: Option<String>,
+    /// Report metric to rank by, e.g. `net_profit` or `calmar`. Defaults to
+    /// `sharpe`.
+    pub objective: Option<String>,
+    /// Number of results to return, best first. Defaults to 10.
+    pub top: Option<usize>,
+}
+
+impl OptimizeParameters {
+    pub fn optimizer(&self) -> Result<Optimizer, String> {
+        let ranges = self
+            .ranges
+            .split(',')
+            .map(str::parse)
+            .collect::<Result<Vec<ParameterRange>, String>>()?;
+        let search = match &self.search {
+            Some(search) => search.parse()?,
+            None => Search::Grid,
+        };
+        let objective = match &self.objective {
+            Some(objective) => objective.parse()?,
+            None => Objective::Sharpe,
+        };
+
+        let optimizer = Optimizer::new(ranges, search, objective);
+        let evaluations = optimizer.evaluations()?;
+        if evaluations > MAX_EVALUATIONS {
+            return Err(format!(
+                "{} backtests requested, at most {} are allowed",
+                evaluations, MAX_EVALUATIONS
+            ));
+        }
+        Ok(optimizer)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Optimization {
+    pub symbol: String,
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub indicator: String,
+    pub objective: String,
+    pub combinations: usize,
+    /// Number of valid combinations backtested.
+    pub evaluated: usize,
+    pub results: Vec<Evaluation>,
+}
+
+/// Optimizes the indicator parameters of the indicator crossover strategy on
+/// the requested bars.
+pub async fn get_optimization(
+    historical: web::Data<HistoricalData>,
+    series: web::Query<SeriesParameters>,
+    strategy: web::Query<StrategyParameters>,
+    params: web::Query<OptimizeParameters>,
+) -> impl Responder {
+    let bad_request =
+        |err: String| HttpResponse::BadRequest().body(format!("get_optimization: {}", err));
+    let symbol = resolve_symbol(&series.symbol, &series.exchange);
+    let (optimizer, backtest) = match params.optimizer().and_then(|optimizer| {
+        Ok((
+            optimizer,
+            strategy.backtest(&series.symbol, symbol.as_ref())?,
+        ))
+    }) {
+        Ok(run) => run,
+        Err(err) => return bad_request(err),
+    };
+    let data = match series.fetch(&historical, "get_optimization").await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let data = Series::new(data.symbol, data.resolution, data.interval, data.data);
+    let strategy = strategy.into_inner();
+    let objective = optimizer.objective().to_string();
+    let top = params.top.unwrap_or(10);
+    let combinations = optimizer.combinations().unwrap_or_default();
+
+    // Backtests are CPU bound, so run them off the async workers
+    let optimization = web::block(move || {
+        let mut results = optimizer.optimize(|params| {
+            let mut runner = strategy.strategy_with(params, symbol.as_ref()).ok()?;
+            let result = backtest.run(&mut runner, &data);
+            Some(Report::new(&result, &data.data))
+        });
+        let evaluated = results.len();
+        results.truncate(top);
+        Optimization {
+            symbol: data.symbol,
+            resolution: data.resolution,
+            interval: data.interval,
+            indicator: strategy.indicator,
+            objective,
+            combinations,
+            evaluated,
+            results,
+        }
+    })
+    .await;
+
+    match optimization {
+        Ok(optimization) => HttpResponse::Ok().json(optimization),
+        Err(err) => HttpResponse::InternalServerError().body(format!("get_optimization: {}", err)),
+    }
+}
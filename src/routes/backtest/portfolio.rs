@@ -0,0 +1,161 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backtest::{Report, SymbolPerformance},
+    datafeed::HistoricalData,
+    domain::{Interval, Resolution, Series, SymbolInfo},
+    routes::{StrategyParameters, cached_symbols, parse_datetime},
+};
+
+/// Query parameters selecting the bars of each symbol of a portfolio.
+#[derive(Debug, Deserialize)]
+pub struct PortfolioParameters {
+    /// Comma-separated symbols, as `<exchange>:<symbol>` or by name alone,
+    /// e.g. `XCME:ES,XHKF:HSI`. Defaults to every symbol.
+    symbols: Option<String>,
+    resolution: Resolution,
+    interval: Interval,
+    from: String,
+    to: Option<String>,
+    limit_lower: Option<usize>,
+    limit_upper: Option<usize>,
+}
+
+impl PortfolioParameters {
+    fn symbols(&self) -> Result<Vec<SymbolInfo>, String> {
+        let all = cached_symbols().map_err(|err| format!("Failed to read symbols: {}", err))?;
+        let Some(symbols) = &self.symbols else {
+            return Ok(all.to_vec());
+        };
+        symbols
+            .split(',')
+            .map(|symbol| {
+                let symbol = symbol.trim();
+                let (exchange, name) = match symbol.split_once(':') {
+                    Some((exchange, name)) => (Some(exchange), name),
+                    None => (None, symbol),
+                };
+                all.iter()
+                    .find(|info| {
+                        info.name.eq_ignore_ascii_case(name)
+                            && exchange.is_none_or(|exchange| info.exchange == exchange)
+                    })
+                    .cloned()
+                    .ok_or_else(|| format!("unknown symbol '{}'", symbol))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PortfolioBacktest {
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub indicator: String,
+    pub params: Vec<f32>,
+    pub symbols: Vec<SymbolPerformance>,
+    pub correlation: Vec<Vec<Option<f64>>>,
+    #[serde(flatten)]
+    pub report: Report,
+}
+
+/// Backtests the indicator crossover strategy on the bars of several symbols
+/// at once, from one account, and responds with the performance report of
+/// the portfolio and of each symbol.
+pub async fn get_portfolio_backtest(
+    historical: web::Data<HistoricalData>,
+    portfolio: web::Query<PortfolioParameters>,
+    strategy: web::Query<StrategyParameters>,
+) -> impl Responder {
+    let bad_request =
+        |err: String| HttpResponse::BadRequest().body(format!("get_portfolio_backtest: {}", err));
+    let internal_error = |err: String| {
+        HttpResponse::InternalServerError().body(format!("get_portfolio_backtest: {}", err))
+    };
+    let symbols = match portfolio.symbols() {
+        Ok(symbols) if symbols.is_empty() => return bad_request("no symbols".to_string()),
+        Ok(symbols) => symbols,
+        Err(err) => return bad_request(err),
+    };
+    let (runners, backtest) = match symbols
+        .iter()
+        .map(|info| strategy.strategy(Some(info)))
+        .collect::<Result<Vec<_>, String>>()
+        .and_then(|runners| Ok((runners, strategy.portfolio_backtest(&symbols)?)))
+    {
+        Ok(run) => run,
+        Err(err) => return bad_request(err),
+    };
+    let (from, to) = match parse_datetime(&portfolio.from).and_then(|from| {
+        let to = portfolio.to.as_deref().map(parse_datetime).transpose()?;
+        Ok((from, to))
+    }) {
+        Ok(range) => range,
+        Err(err) => return bad_request(err),
+    };
+
+    let mut series = Vec::with_capacity(symbols.len());
+    let mut ticks = Vec::new();
+    for info in &symbols {
+        let data = match historical
+            .fetch(
+                &info.name,
+                &info.exchange,
+                &portfolio.resolution,
+                &portfolio.interval,
+                &from,
+                &to,
+                &portfolio.limit_lower,
+                &portfolio.limit_upper,
+                &None,
+                &None,
+            )
+            .await
+        {
+            Ok(data) => data,
+            Err(err) => return internal_error(err.to_string()),
+        };
+        // Tick fill models fill against the ticks the bars are built from
+        if backtest.replays_ticks() {
+            match historical
+                .fetch_ticks(&info.name, &info.exchange, &from, &to, &None, &None)
+                .await
+            {
+                Ok(data) => ticks.push(data),
+                Err(err) => return internal_error(err.to_string()),
+            }
+        }
+        // Contracts of the backtest are keyed by symbol name
+        series.push(Series::new(
+            info.name.clone(),
+            data.resolution,
+            data.interval,
+            data.data,
+        ));
+    }
+    let strategy = strategy.into_inner();
+
+    // Backtests are CPU bound, so run them off the async workers
+    let backtest = web::block(move || {
+        let mut runners = runners.into_iter();
+        let result = backtest.run_portfolio_with_ticks(&series, &ticks, |_| {
+            runners.next().expect("a strategy for each series")
+        });
+        PortfolioBacktest {
+            resolution: portfolio.resolution.clone(),
+            interval: portfolio.interval,
+            params: strategy.indicator_params().unwrap_or_default(),
+            indicator: strategy.indicator,
+            report: Report::new(&result.result, &[]),
+            symbols: result.symbols,
+            correlation: result.correlation,
+        }
+    })
+    .await;
+
+    match backtest {
+        Ok(backtest) => HttpResponse::Ok().json(backtest),
+        Err(err) => internal_error(err.to_string()),
+    }
+}
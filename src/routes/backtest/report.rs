@@ -0,0 +1,91 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backtest::Report,
+    datafeed::HistoricalData,
+    domain::{Interval, Resolution, Series},
+    routes::{SeriesParameters, StrategyParameters, resolve_symbol},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct ReportParameters {
+    /// `json` or `html`. Defaults to `json`.
+    format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BacktestReport {
+    pub symbol: String,
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub indicator: String,
+    pub params: Vec<f32>,
+    #[serde(flatten)]
+    pub report: Report,
+}
+
+/// Backtests the indicator crossover strategy on the requested bars and
+/// responds with its performance report, as JSON or as an HTML page.
+pub async fn get_backtest_report(
+    historical: web::Data<HistoricalData>,
+    series: web::Query<SeriesParameters>,
+    strategy: web::Query<StrategyParameters>,
+    params: web::Query<ReportParameters>,
+) -> impl Responder {
+    let bad_request =
+        |err: String| HttpResponse::BadRequest().body(format!("get_backtest_report: {}", err));
+    let html = match params.format.as_deref() {
+        None | Some("json") => false,
+        Some("html") => true,
+        Some(format) => return bad_request(format!("unsupported format '{}'", format)),
+    };
+    let symbol = resolve_symbol(&series.symbol, &series.exchange);
+    let (mut runner, backtest) = match strategy
+        .strategy(symbol.as_ref())
+        .and_then(|runner| Ok((runner, strategy.backtest(&series.symbol, symbol.as_ref())?)))
+    {
+        Ok(run) => run,
+        Err(err) => return bad_request(err),
+    };
+    let data = match series.fetch(&historical, "get_backtest_report").await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let data = Series::new(data.symbol, data.resolution, data.interval, data.data);
+    let strategy = strategy.into_inner();
+
+    // Backtests are CPU bound, so run them off the async workers
+    let report = web::block(move || {
+        let result = backtest.run(&mut runner, &data);
+        BacktestReport {
+            report: Report::new(&result, &data.data),
+            symbol: data.symbol,
+            resolution: data.resolution,
+            interval: data.interval,
+            params: strategy.indicator_params().unwrap_or_default(),
+            indicator: strategy.indicator,
+        }
+    })
+    .await;
+    let report = match report {
+        Ok(report) => report,
+        Err(err) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("get_backtest_report: {}", err));
+        }
+    };
+
+    if html {
+        let title = format!(
+            "{} {} {:?} backtest",
+            report.symbol, report.indicator, report.params
+        );
+        HttpResponse::Ok()
+            .content_type("text/html; charset=utf-8")
+            .body(report.report.to_html(&title))
+    } else {
+        HttpResponse::Ok().json(report)
+    }
+}
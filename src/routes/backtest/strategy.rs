@@ -0,0 +1,182 @@
+use serde::Deserialize;
+
+use crate::{
+    backtest::{
+        Backtest, IndicatorCross, RiskManager, fetch_commission_schedules, fetch_contracts,
+    },
+    domain::{Indicators, SymbolInfo},
+};
+
+/// Query parameters of the strategy that a backtest route runs and of the
+/// simulated execution. Backtest routes extract these alongside
+/// `SeriesParameters` from the same query.
+#[derive(Debug, Deserialize)]
+pub struct StrategyParameters {
+    /// Indicator whose primary line the close is traded across, e.g. `ama`.
+    pub indicator: String,
+    /// Comma-separated indicator parameters, e.g. `10,2,30`. Defaults to the
+    /// defaults of the indicator.
+    pub params: Option<String>,
+    /// Quantity held long or short. Defaults to 1.
+    pub quantity: Option<f32>,
+    /// Defaults to 100000.
+    pub capital: Option<f64>,
+    /// Fill model, e.g. `next_bar_open` or `queue:500`. Defaults to
+    /// `next_bar_open`.
+    pub fill_model: Option<String>,
+    /// Slippage, e.g. `fixed:0.25` or `volatility:14:0.1`. Defaults to `none`.
+    pub slippage: Option<String>,
+    /// Name of a commission schedule in `data/commissions.json`. Defaults to
+    /// `none`.
+    pub commission: Option<String>,
+    /// Position sizing, e.g. `fixed_fractional:50`, `volatility:14:1` or
+    /// `kelly:0.5:10`. Defaults to trading `quantity`.
+    pub sizing: Option<String>,
+    /// Largest exposure of a symbol, in percent of the equity.
+    pub max_symbol_exposure: Option<f64>,
+    /// Largest gross exposure, in percent of the equity.
+    pub max_total_exposure: Option<f64>,
+    /// Loss in a day, in percent of the equity at its start, that halts
+    /// trading for the rest of the day.
+    pub daily_loss_limit: Option<f64>,
+    pub max_open_orders: Option<usize>,
+}
+
+impl StrategyParameters {
+    pub fn indicator_params(&self) -> Result<Vec<f32>, String> {
+        let Some(params) = &self.params else {
+            return Ok(Vec::new());
+        };
+        params
+            .split(',')
+            .map(|param| {
+                param
+                    .trim()
+                    .parse::<f32>()
+                    .map_err(|err| format!("invalid parameter '{}': {}", param, err))
+            })
+            .collect()
+    }
+
+    /// The strategy with the indicator bound to the trading session of the
+    /// symbol, if it can be resolved.
+    pub fn strategy(&self, symbol: Option<&SymbolInfo>) -> Result<IndicatorCross, String> {
+        self.strategy_with(&self.indicator_params()?, symbol)
+    }
+
+    /// The strategy with the given indicator parameters instead of `params`.
+    pub fn strategy_with(
+        &self,
+        params: &[f32],
+        symbol: Option<&SymbolInfo>,
+    ) -> Result<IndicatorCross, String> {
+        let indicator = Indicators::from((self.indicator.as_str(), &params.to_vec()));
+        let indicator = match symbol {
+            Some(symbol) => indicator.with_context(&symbol.session, &symbol.timezone, &None),
+            None => indicator.with_context("24x7", "Etc/UTC", &None),
+        };
+        indicator.validate()?;
+
+        let quantity = self.quantity.unwrap_or(1.0);
+        if !quantity.is_finite() || quantity <= 0.0 {
+            return Err("quantity must be a positive number".to_string());
+        }
+        Ok(IndicatorCross::new(indicator, quantity))
+    }
+
+    /// The backtest of a symbol, valuing it by its contract and charging it
+    /// the commission of its symbol type and exchange in the requested
+    /// schedule. Symbols that cannot be resolved are valued one to one and
+    /// charged the default commission of the schedule.
+    pub fn backtest(&self, symbol: &str, info: Option<&SymbolInfo>) -> Result<Backtest, String> {
+        let symbols: Vec<(&str, &SymbolInfo)> =
+            info.map(|info| (symbol, info)).into_iter().collect();
+        self.configure(&symbols)
+    }
+
+    /// The backtest of a portfolio of resolved symbols, keyed by name.
+    pub fn portfolio_backtest(&self, symbols: &[SymbolInfo]) -> Result<Backtest, String> {
+        let symbols: Vec<(&str, &SymbolInfo)> = symbols
+            .iter()
+            .map(|info| (info.name.as_str(), info))
+            .collect();
+        self.configure(&symbols)
+    }
+
+    /// The risk manager, if any sizing or limit is requested.
+    pub fn risk(&self) -> Result<Option<RiskManager>, String> {
+        let percent = |name: &str, value: Option<f64>| match value {
+            Some(value) if !value.is_finite() || value <= 0.0 => {
+                Err(format!("{} must be a positive number", name))
+            }
+            _ => Ok(value),
+        };
+        let max_symbol_exposure = percent("max_symbol_exposure", self.max_symbol_exposure)?;
+        let max_total_exposure = percent("max_total_exposure", self.max_total_exposure)?;
+        let daily_loss_limit = percent("daily_loss_limit", self.daily_loss_limit)?;
+        if self.max_open_orders == Some(0) {
+            return Err("max_open_orders must be a positive number".to_string());
+        }
+        if self.sizing.is_none()
+            && max_symbol_exposure.is_none()
+            && max_total_exposure.is_none()
+            && daily_loss_limit.is_none()
+            && self.max_open_orders.is_none()
+        {
+            return Ok(None);
+        }
+
+        let mut risk = RiskManager::new();
+        if let Some(sizing) = &self.sizing {
+            risk = risk.with_sizing(sizing.parse()?);
+        }
+        if let Some(percent) = max_symbol_exposure {
+            risk = risk.with_max_symbol_exposure(percent);
+        }
+        if let Some(percent) = max_total_exposure {
+            risk = risk.with_max_total_exposure(percent);
+        }
+        if let Some(percent) = daily_loss_limit {
+            risk = risk.with_daily_loss_limit(percent);
+        }
+        if let Some(count) = self.max_open_orders {
+            risk = risk.with_max_open_orders(count);
+        }
+        Ok(Some(risk))
+    }
+
+    fn configure(&self, symbols: &[(&str, &SymbolInfo)]) -> Result<Backtest, String> {
+        let capital = self.capital.unwrap_or(100000.0);
+        if !capital.is_finite() || capital <= 0.0 {
+            return Err("capital must be a positive number".to_string());
+        }
+        let mut backtest = Backtest::new(capital);
+        if let Some(fill_model) = &self.fill_model {
+            backtest = backtest.with_fill_model(fill_model.parse()?);
+        }
+        if let Some(slippage) = &self.slippage {
+            backtest = backtest.with_slippage(slippage.parse()?);
+        }
+        if !symbols.is_empty() {
+            let contracts = fetch_contracts()?;
+            for (symbol, info) in symbols {
+                backtest = backtest.with_contract(symbol, contracts.contract(info)?);
+            }
+        }
+        if let Some(name) = &self.commission {
+            let schedules = fetch_commission_schedules()?;
+            let schedule = schedules
+                .get(name)
+                .ok_or_else(|| format!("unknown commission schedule '{}'", name))?;
+            backtest = backtest.with_commission(schedule.default.clone());
+            for (symbol, info) in symbols {
+                let commission = schedule.commission(&info.exchange, &info.symbol_type);
+                backtest = backtest.with_symbol_commission(symbol, commission);
+            }
+        }
+        if let Some(risk) = self.risk()? {
+            backtest = backtest.with_risk(risk);
+        }
+        Ok(backtest)
+    }
+}
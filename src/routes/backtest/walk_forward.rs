@@ -0,0 +1,116 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backtest::{Report, WalkForward, WalkForwardMode, WindowResult},
+    datafeed::HistoricalData,
+    domain::{Interval, Resolution, Series},
+    routes::{OptimizeParameters, SeriesParameters, StrategyParameters, resolve_symbol},
+};
+
+/// Most backtests a single walk-forward analysis may run, across the
+/// optimizations of all its windows.
+const MAX_BACKTESTS: usize = 100000;
+
+#[derive(Debug, Deserialize)]
+pub struct WalkForwardParameters {
+    /// `anchored` or `rolling`. Defaults to `rolling`.
+    mode: Option<String>,
+    /// Number of bars in each in-sample window, or in the first one when
+    /// anchored.
+    in_sample: usize,
+    /// Number of bars in each out-of-sample window.
+    out_of_sample: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WalkForwardAnalysis {
+    pub symbol: String,
+    pub resolution: Resolution,
+    pub interval: Interval,
+    pub indicator: String,
+    pub mode: String,
+    pub objective: String,
+    pub efficiency: Option<f64>,
+    pub windows: Vec<WindowResult>,
+    /// Report on the stitched out-of-sample backtest.
+    pub report: Report,
+}
+
+/// Walk-forward analysis of the indicator crossover strategy on the requested
+/// bars, optimizing the indicator parameters in each in-sample window.
+pub async fn get_walk_forward(
+    historical: web::Data<HistoricalData>,
+    series: web::Query<SeriesParameters>,
+    strategy: web::Query<StrategyParameters>,
+    optimize: web::Query<OptimizeParameters>,
+    params: web::Query<WalkForwardParameters>,
+) -> impl Responder {
+    let bad_request =
+        |err: String| HttpResponse::BadRequest().body(format!("get_walk_forward: {}", err));
+    let mode = match params.mode.as_deref().map(str::parse).transpose() {
+        Ok(mode) => mode.unwrap_or(WalkForwardMode::Rolling),
+        Err(err) => return bad_request(err),
+    };
+    if params.in_sample == 0 || params.out_of_sample == 0 {
+        return bad_request("windows must contain at least one bar".to_string());
+    }
+    let symbol = resolve_symbol(&series.symbol, &series.exchange);
+    let (optimizer, backtest) = match optimize.optimizer().and_then(|optimizer| {
+        Ok((
+            optimizer,
+            strategy.backtest(&series.symbol, symbol.as_ref())?,
+        ))
+    }) {
+        Ok(run) => run,
+        Err(err) => return bad_request(err),
+    };
+    let data = match series.fetch(&historical, "get_walk_forward").await {
+        Ok(data) => data,
+        Err(response) => return response,
+    };
+
+    let data = Series::new(data.symbol, data.resolution, data.interval, data.data);
+    let strategy = strategy.into_inner();
+    let walk_forward = WalkForward::new(mode, params.in_sample, params.out_of_sample);
+    // Each window is optimized, then backtested out of sample
+    let backtests = walk_forward.windows(data.len()).len().saturating_mul(
+        optimizer
+            .evaluations()
+            .unwrap_or_default()
+            .saturating_add(1),
+    );
+    if backtests > MAX_BACKTESTS {
+        return bad_request(format!(
+            "{} backtests requested, at most {} are allowed; use longer windows or fewer parameter combinations",
+            backtests, MAX_BACKTESTS
+        ));
+    }
+    let capital = strategy.capital.unwrap_or(100000.0);
+
+    // Backtests are CPU bound, so run them off the async workers
+    let analysis = web::block(move || {
+        let result = walk_forward.run(&optimizer, data.len(), |params, range| {
+            let mut runner = strategy.strategy_with(params, symbol.as_ref()).ok()?;
+            Some(backtest.run_range(&mut runner, &data, range))
+        })?;
+        Ok::<_, String>(WalkForwardAnalysis {
+            report: Report::new(&result.out_of_sample(capital), &data.data),
+            symbol: data.symbol,
+            resolution: data.resolution,
+            interval: data.interval,
+            indicator: strategy.indicator,
+            mode: mode.to_string(),
+            objective: optimizer.objective().to_string(),
+            efficiency: result.efficiency,
+            windows: result.windows,
+        })
+    })
+    .await;
+
+    match analysis {
+        Ok(Ok(analysis)) => HttpResponse::Ok().json(analysis),
+        Ok(Err(err)) => bad_request(err),
+        Err(err) => HttpResponse::InternalServerError().body(format!("get_walk_forward: {}", err)),
+    }
+}
@@ -4,8 +4,10 @@ use actix_web::{HttpResponse, Responder, web};
 use serde::Deserialize;
 
 use crate::{
+    analysis::ReversalThreshold,
     datafeed::HistoricalData,
     domain::{Indicators, Interval, Resolution},
+    routes::resolve_symbol,
 };
 
 #[derive(Debug, serde::Deserialize)]
@@ -18,6 +20,20 @@ pub struct Parameters {
     to: Option<String>,
     limit_lower: Option<usize>,
     limit_upper: Option<usize>,
+    /// Number of extra bars to fetch before `from` to warm up indicators.
+    /// Defaults to the lookback required by the requested indicators.
+    lookback: Option<usize>,
+    /// Anchor timestamp for indicators anchored to a point in time, e.g. VWAP.
+    anchor: Option<String>,
+    /// Reversal threshold for swing detection, e.g. `percent:5`, `atr:14:3`
+    /// or `bars:5`. Swings are omitted unless requested.
+    swings: Option<String>,
+    /// Whether to add chart marks for harmonic and chart patterns found
+    /// between the swings, confirmed by `swings` or `atr:14:3` by default.
+    patterns: Option<bool>,
+    /// Indicator line to find divergences with price swings in, e.g. `rsi`
+    /// or `macd:histogram`. The indicator must be requested in `indicators`.
+    divergences: Option<String>,
     #[serde(deserialize_with = "deserialize_stringified_map")]
     indicators: Option<Vec<Indicators>>,
 }
@@ -26,7 +42,25 @@ pub async fn get_historical_data(
     historical: web::Data<HistoricalData>,
     params: web::Query<Parameters>,
 ) -> impl Responder {
-    historical
+    let indicators = match bind_indicators(&params) {
+        Ok(indicators) => indicators,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!("get_historical_data: {}", err));
+        }
+    };
+    let swings = match params
+        .swings
+        .as_deref()
+        .map(str::parse::<ReversalThreshold>)
+        .transpose()
+    {
+        Ok(swings) => swings,
+        Err(err) => {
+            return HttpResponse::BadRequest().body(format!("get_historical_data: {}", err));
+        }
+    };
+
+    let series = match historical
         .fetch(
             &params.symbol,
             &params.exchange,
@@ -36,13 +70,65 @@ pub async fn get_historical_data(
             &params.to.as_ref().map(|t| t.parse().unwrap()), // TODO - handle errors (remove unwrap)
             &params.limit_lower,
             &params.limit_upper,
-            &params.indicators,
+            &params.lookback,
+            &indicators,
         )
         .await
-        .map(|data| HttpResponse::Ok().json(data))
-        .unwrap_or_else(|err| {
-            HttpResponse::InternalServerError().body(format!("get_historical_data: {}", err))
+    {
+        Ok(series) => series,
+        Err(err) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("get_historical_data: {}", err));
+        }
+    };
+
+    let threshold = swings.clone().unwrap_or_default();
+    let series = match &swings {
+        Some(threshold) => series.with_swings(threshold),
+        None => series,
+    };
+    let series = match params.patterns {
+        Some(true) => series.with_patterns(&threshold),
+        _ => series,
+    };
+    let series = match &params.divergences {
+        Some(source) => match series.with_divergences(source, &threshold) {
+            Ok(series) => series,
+            Err(err) => {
+                return HttpResponse::BadRequest().body(format!("get_historical_data: {}", err));
+            }
+        },
+        None => series,
+    };
+
+    HttpResponse::Ok().json(series)
+}
+
+/// Binds the trading session of the requested symbol and the anchor timestamp
+/// to the requested indicators and validates them. Symbols that cannot be
+/// resolved default to a UTC session starting at midnight.
+fn bind_indicators(params: &Parameters) -> Result<Option<Vec<Indicators>>, String> {
+    let Some(indicators) = &params.indicators else {
+        return Ok(None);
+    };
+
+    let (session, timezone) = resolve_symbol(&params.symbol, &params.exchange)
+        .map(|symbol| (symbol.session, symbol.timezone))
+        .unwrap_or_else(|| ("24x7".to_string(), "Etc/UTC".to_string()));
+
+    let indicators: Vec<Indicators> = indicators
+        .iter()
+        .map(|indicator| {
+            indicator
+                .clone()
+                .with_context(&session, &timezone, &params.anchor)
         })
+        .collect();
+    for indicator in &indicators {
+        indicator.validate()?;
+    }
+
+    Ok(Some(indicators))
 }
 
 fn deserialize_stringified_map<'de, D>(deserializer: D) -> Result<Option<Vec<Indicators>>, D::Error>
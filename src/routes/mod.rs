@@ -1,7 +1,13 @@
+mod analysis;
+mod backtest;
 mod data;
 mod health;
+mod paper;
 mod symbols;
 
+pub use analysis::*;
+pub use backtest::*;
 pub use data::*;
 pub use health::*;
+pub use paper::*;
 pub use symbols::*;
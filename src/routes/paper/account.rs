@@ -0,0 +1,55 @@
+use actix_web::{HttpResponse, Responder, web};
+use serde::Serialize;
+
+use crate::{
+    backtest::Order,
+    broker::{Broker, OrderEvent},
+    paper::PaperTrading,
+    routes::SessionParameters,
+};
+
+#[derive(Debug, Serialize)]
+pub struct PaperOrders {
+    /// Orders waiting to be filled.
+    pub open: Vec<Order>,
+    /// Every change in the status of an order, oldest first.
+    pub events: Vec<OrderEvent>,
+}
+
+fn not_found(context: &str, session: u64) -> HttpResponse {
+    HttpResponse::NotFound().body(format!("{}: unknown session {}", context, session))
+}
+
+pub async fn get_paper_orders(
+    paper: web::Data<PaperTrading>,
+    params: web::Query<SessionParameters>,
+) -> impl Responder {
+    let orders = paper.with_trader(params.session, |trader| PaperOrders {
+        open: trader.broker().orders(),
+        events: trader.broker().events().to_vec(),
+    });
+    match orders {
+        Some(orders) => HttpResponse::Ok().json(orders),
+        None => not_found("get_paper_orders", params.session),
+    }
+}
+
+pub async fn get_paper_positions(
+    paper: web::Data<PaperTrading>,
+    params: web::Query<SessionParameters>,
+) -> impl Responder {
+    match paper.with_trader(params.session, |trader| trader.broker().positions()) {
+        Some(positions) => HttpResponse::Ok().json(positions),
+        None => not_found("get_paper_positions", params.session),
+    }
+}
+
+pub async fn get_paper_account(
+    paper: web::Data<PaperTrading>,
+    params: web::Query<SessionParameters>,
+) -> impl Responder {
+    match paper.with_trader(params.session, |trader| trader.broker().state()) {
+        Some(state) => HttpResponse::Ok().json(state),
+        None => not_found("get_paper_account", params.session),
+    }
+}
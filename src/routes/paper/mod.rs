@@ -0,0 +1,5 @@
+mod account;
+mod sessions;
+
+pub use account::*;
+pub use sessions::*;
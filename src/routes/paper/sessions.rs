@@ -0,0 +1,111 @@
+use actix_web::{HttpResponse, Responder, web};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::{
+    datafeed::{HistoricalData, LiveTicks},
+    domain::Interval,
+    paper::{PaperBroker, PaperTrader, PaperTrading, SessionInfo},
+    routes::{StrategyParameters, parse_datetime, resolve_symbol},
+};
+
+#[derive(Debug, Deserialize)]
+pub struct PaperSessionParameters {
+    pub symbol: String,
+    pub exchange: String,
+    /// Interval of the bars the strategy trades on.
+    pub interval: Interval,
+    /// Trades the ticks after this time, e.g. to catch up on the ticks of
+    /// the day. Defaults to now.
+    pub from: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionParameters {
+    pub session: u64,
+}
+
+/// Starts paper trading the indicator crossover strategy on the live ticks
+/// of a symbol, with the costs and risk limits of a backtest. The indicator
+/// is warmed up on the bars of the ticks before `from`.
+pub async fn start_paper_session(
+    paper: web::Data<PaperTrading>,
+    historical: web::Data<HistoricalData>,
+    params: web::Query<PaperSessionParameters>,
+    strategy: web::Query<StrategyParameters>,
+) -> impl Responder {
+    let bad_request =
+        |err: String| HttpResponse::BadRequest().body(format!("start_paper_session: {}", err));
+    let from = match &params.from {
+        Some(from) => match parse_datetime(from) {
+            Ok(from) => from,
+            Err(err) => return bad_request(err),
+        },
+        None => Utc::now().naive_utc(),
+    };
+    // Ticks are stored with upper case symbols
+    let symbol = params.symbol.to_uppercase();
+    let info = resolve_symbol(&params.symbol, &params.exchange);
+    let (runner, backtest) = match strategy
+        .strategy(info.as_ref())
+        .and_then(|runner| Ok((runner, strategy.backtest(&symbol, info.as_ref())?)))
+    {
+        Ok(run) => run,
+        Err(err) => return bad_request(err),
+    };
+
+    let bars = match historical
+        .fetch_warmup_bars(
+            &symbol,
+            &params.exchange,
+            &params.interval,
+            &from,
+            &[runner.indicator().clone()],
+        )
+        .await
+    {
+        Ok(bars) => bars,
+        Err(err) => {
+            return HttpResponse::InternalServerError()
+                .body(format!("start_paper_session: {}", err));
+        }
+    };
+
+    let trader = PaperTrader::new(
+        &symbol,
+        &params.interval,
+        Box::new(runner),
+        PaperBroker::new(backtest),
+    )
+    .with_bars(bars);
+    let feed = LiveTicks::new(
+        &symbol,
+        &params.exchange,
+        &from.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string(),
+    );
+    let session = paper.start(trader, feed);
+    HttpResponse::Ok().json(SessionInfo {
+        session,
+        symbol,
+        running: true,
+    })
+}
+
+/// Stops a paper trading session. Its orders, positions and account remain
+/// available until newer sessions have been stopped.
+pub async fn stop_paper_session(
+    paper: web::Data<PaperTrading>,
+    params: web::Query<SessionParameters>,
+) -> impl Responder {
+    match paper.stop(params.session) {
+        Some(_) => HttpResponse::Ok().finish(),
+        None => HttpResponse::NotFound().body(format!(
+            "stop_paper_session: unknown session {}",
+            params.session
+        )),
+    }
+}
+
+pub async fn get_paper_sessions(paper: web::Data<PaperTrading>) -> impl Responder {
+    HttpResponse::Ok().json(paper.sessions())
+}
@@ -0,0 +1,40 @@
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use crate::{
+    domain::SymbolInfo,
+    routes::{fetch_symbols, symbols_path},
+};
+
+/// Symbols last read from the symbols file, with the time the file was
+/// modified when they were read.
+static SYMBOLS: RwLock<Option<(SystemTime, Arc<Vec<SymbolInfo>>)>> = RwLock::new(None);
+
+/// Symbols of the symbols file. The file is only read again once it has
+/// been modified.
+pub fn cached_symbols() -> Result<Arc<Vec<SymbolInfo>>, csv::Error> {
+    let modified = std::fs::metadata(symbols_path())
+        .and_then(|metadata| metadata.modified())
+        .ok();
+    if let Some(modified) = modified
+        && let Some((read, symbols)) = SYMBOLS.read().unwrap().as_ref()
+        && *read == modified
+    {
+        return Ok(symbols.clone());
+    }
+
+    let symbols = Arc::new(fetch_symbols()?);
+    if let Some(modified) = modified {
+        *SYMBOLS.write().unwrap() = Some((modified, symbols.clone()));
+    }
+    Ok(symbols)
+}
+
+/// The symbol info of a symbol on an exchange, if it can be resolved.
+pub fn resolve_symbol(symbol: &str, exchange: &str) -> Option<SymbolInfo> {
+    cached_symbols()
+        .ok()?
+        .iter()
+        .find(|info| info.name.eq_ignore_ascii_case(symbol) && info.exchange == exchange)
+        .cloned()
+}
@@ -1,5 +1,7 @@
+mod cache;
 mod resolve;
 mod search;
 
+pub use cache::*;
 pub use resolve::*;
 pub use search::*;
@@ -1,6 +1,8 @@
+use std::path::PathBuf;
+
 use actix_web::{web, HttpResponse, Responder};
 
-use crate::{domain::{SymbolInfo, SymbolType}, routes::symbols::search};
+use crate::{domain::{SymbolInfo, SymbolType}, routes::{cached_symbols, symbols::search}};
 
 #[derive(serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -27,8 +29,8 @@ pub async fn symbols_handler(
             }
         }
     } else {
-        match fetch_symbols() {
-            Ok(val) => HttpResponse::Ok().json(val),
+        match cached_symbols() {
+            Ok(val) => HttpResponse::Ok().json(val.as_ref()),
             Err(err) => {
                 HttpResponse::InternalServerError().body(format!("error fetching symbols: {}", err))
             }
@@ -37,18 +39,22 @@ pub async fn symbols_handler(
 }
 
 pub async fn get_all_symbols() -> impl Responder {
-    match fetch_symbols() {
-        Ok(val) => HttpResponse::Ok().json(val),
+    match cached_symbols() {
+        Ok(val) => HttpResponse::Ok().json(val.as_ref()),
         Err(err) => {
             HttpResponse::InternalServerError().body(format!("error fetching symbols: {}", err))
         }
     }
 }
 
-pub fn fetch_symbols() -> Result<Vec<SymbolInfo>, csv::Error> {
+pub fn symbols_path() -> PathBuf {
     let base_path = std::env::current_dir().expect("Failed to determine the current directory");
     let res_directory = base_path.join("data");
-    let filename = res_directory.join("symbols.csv");
+    res_directory.join("symbols.csv")
+}
+
+pub fn fetch_symbols() -> Result<Vec<SymbolInfo>, csv::Error> {
+    let filename = symbols_path();
 
     let reader = csv::Reader::from_path(filename);
     let mut symbols: Vec<SymbolInfo> = Vec::new();
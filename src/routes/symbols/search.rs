@@ -1,4 +1,4 @@
-use crate::{domain::{SymbolInfo, SymbolType}, routes::fetch_symbols};
+use crate::{domain::{SymbolInfo, SymbolType}, routes::cached_symbols};
 
 pub fn search(
     name: &String, 
@@ -7,9 +7,9 @@ pub fn search(
 ) -> Result<Vec<SymbolInfo>, anyhow::Error> {
     let mut symbols: Vec<SymbolInfo> = Vec::new();
 
-    match fetch_symbols() {
+    match cached_symbols() {
         Ok(val) => {
-            for symbol in val {
+            for symbol in val.iter() {
                 if symbol.name.to_lowercase().contains(&name.to_lowercase()) {
                     if let Some(exchange) = exchange {
                         if &symbol.exchange != exchange {
@@ -21,7 +21,7 @@ pub fn search(
                             continue;
                         }
                     }
-                    symbols.push(symbol);
+                    symbols.push(symbol.clone());
                 }
             }
         },
@@ -131,4 +131,25 @@ impl Database {
             "data": deserialized
         }))?)
     }
+
+    /// Executes a statement that returns no dataset, e.g. `CREATE TABLE` or
+    /// `INSERT`.
+    pub async fn execute(&self, statement: &str) -> Result<(), Error> {
+        let encoded_statement: String =
+            form_urlencoded::byte_serialize(statement.as_bytes()).collect();
+        let url = format!("{}/exec?query={}", self.url, encoded_statement);
+
+        let res = self
+            .client
+            .get(url.as_str())
+            .send()
+            .await?
+            .json::<serde_json::Value>()
+            .await?;
+        if res.get("error").is_some() {
+            let err: SQLError = serde_json::from_value(res)?;
+            return Err(Error::SQLError(err));
+        }
+        Ok(())
+    }
 }